@@ -0,0 +1,189 @@
+use crate::api::account::{AccountBalance, AccountEntity, AccountTotalEntity, CurrencyAmount};
+use crate::api::transaction::TransactionEntity;
+
+/// Opening balance plus income and transfers in, minus outcome and transfers out.
+/// Amounts in another currency are not converted, they are returned apart.
+pub fn account_balance(
+    account: &AccountEntity,
+    totals: &[AccountTotalEntity],
+    transfers: &[TransactionEntity],
+    at: chrono::DateTime<chrono::Utc>,
+) -> AccountBalance {
+    let mut income = 0.0;
+    let mut outcome = 0.0;
+    let mut other_currencies: Vec<CurrencyAmount> = vec![];
+    for total in totals {
+        let same_currency = total.currency.eq_ignore_ascii_case(&account.currency);
+        match total.r#type.as_str() {
+            "income" if same_currency => income += total.amount,
+            "outcome" if same_currency => outcome += total.amount,
+            "income" => add_other(&mut other_currencies, &total.currency, total.amount),
+            "outcome" => add_other(&mut other_currencies, &total.currency, -total.amount),
+            _ => {}
+        }
+    }
+
+    let mut transferred_in = 0.0;
+    let mut transferred_out = 0.0;
+    for tx in transfers {
+        let transfer = tx.transfer.clone().unwrap_or_default();
+        let same_currency = tx.currency.eq_ignore_ascii_case(&account.currency);
+        if tx.account_id == Some(account.id) {
+            match transfer.from_amount {
+                Some(amount) => transferred_out += amount,
+                None if same_currency => transferred_out += tx.amount,
+                None => add_other(&mut other_currencies, &tx.currency, -tx.amount),
+            }
+        }
+        if transfer.to_account_id == Some(account.id) {
+            match transfer.to_amount {
+                Some(amount) => transferred_in += amount,
+                None if same_currency => transferred_in += tx.amount,
+                None => add_other(&mut other_currencies, &tx.currency, tx.amount),
+            }
+        }
+    }
+    other_currencies.sort_by(|a, b| a.currency.cmp(&b.currency));
+
+    AccountBalance {
+        account_id: account.id.to_hex(),
+        currency: account.currency.clone(),
+        opening_balance: account.opening_balance,
+        income,
+        outcome,
+        transferred_in,
+        transferred_out,
+        balance: account.opening_balance + income - outcome + transferred_in - transferred_out,
+        other_currencies,
+        at,
+    }
+}
+
+fn add_other(other_currencies: &mut Vec<CurrencyAmount>, currency: &str, amount: f64) {
+    match other_currencies
+        .iter_mut()
+        .find(|other| other.currency == currency)
+    {
+        Some(other) => other.amount += amount,
+        None => other_currencies.push(CurrencyAmount {
+            currency: currency.to_string(),
+            amount,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bson::oid::ObjectId;
+
+    use crate::api::account::AccountType;
+    use crate::api::transaction::TransferEntity;
+
+    use super::*;
+
+    fn account(currency: &str, opening_balance: f64) -> AccountEntity {
+        AccountEntity {
+            id: ObjectId::new(),
+            user_id: ObjectId::new(),
+            name: "Visa".to_string(),
+            r#type: AccountType::CreditCard,
+            currency: currency.to_string(),
+            opening_balance,
+            card_number: Some(8432),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn total(currency: &str, r#type: &str, amount: f64) -> AccountTotalEntity {
+        AccountTotalEntity {
+            currency: currency.to_string(),
+            r#type: r#type.to_string(),
+            amount,
+        }
+    }
+
+    fn transfer(
+        from: ObjectId,
+        to: ObjectId,
+        amount: f64,
+        currency: &str,
+        to_amount: Option<f64>,
+    ) -> TransactionEntity {
+        TransactionEntity {
+            id: ObjectId::new(),
+            message_id: ObjectId::new(),
+            user_id: ObjectId::new(),
+            invoice_id: ObjectId::new(),
+            account_id: Some(from),
+            title: "ATM".to_string(),
+            amount,
+            currency: currency.to_string(),
+            category_id: "transfer".to_string(),
+            r#type: "transfer".to_string(),
+            unit: None,
+            quantity: 1.0,
+            transfer: Some(TransferEntity {
+                to_account_id: Some(to),
+                from_amount: None,
+                to_amount,
+            }),
+            split_id: None,
+            edited_fields: vec![],
+            issued_at: chrono::Utc::now(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_account_balance() {
+        let account = account("USD", 100.0);
+        let totals = vec![
+            total("USD", "income", 50.0),
+            total("USD", "outcome", 30.5),
+            total("EUR", "outcome", 12.5),
+            total("EUR", "income", 2.5),
+        ];
+
+        let balance = account_balance(&account, &totals, &[], chrono::Utc::now());
+
+        assert_eq!(balance.income, 50.0);
+        assert_eq!(balance.outcome, 30.5);
+        assert_eq!(balance.balance, 119.5);
+        assert_eq!(
+            balance.other_currencies,
+            vec![CurrencyAmount {
+                currency: "EUR".to_string(),
+                amount: -10.0,
+            }]
+        );
+
+        let balance = account_balance(&account, &[], &[], chrono::Utc::now());
+        assert_eq!(balance.balance, 100.0);
+        assert!(balance.other_currencies.is_empty());
+    }
+
+    #[test]
+    fn test_account_balance_with_transfers() {
+        let bank = account("VND", 5_000_000.0);
+        let cash = account("VND", 0.0);
+        let wallet = account("USD", 0.0);
+        let transfers = vec![
+            transfer(bank.id, cash.id, 2_000_000.0, "VND", None),
+            transfer(bank.id, wallet.id, 2_500_000.0, "VND", Some(100.0)),
+        ];
+
+        let balance = account_balance(&bank, &[], &transfers, chrono::Utc::now());
+        assert_eq!(balance.transferred_out, 4_500_000.0);
+        assert_eq!(balance.balance, 500_000.0);
+
+        let balance = account_balance(&cash, &[], &transfers, chrono::Utc::now());
+        assert_eq!(balance.transferred_in, 2_000_000.0);
+        assert_eq!(balance.balance, 2_000_000.0);
+
+        let balance = account_balance(&wallet, &[], &transfers, chrono::Utc::now());
+        assert_eq!(balance.transferred_in, 100.0);
+        assert!(balance.other_currencies.is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use utoipa::OpenApi;
+
+use crate::api::account::{
+    Account, AccountBalance, AccountBalanceQuery, AccountError, AccountType, CreateAccountBody,
+    CreateAccountInput, CurrencyAmount, UpdateAccountBody, UpdateAccountInput,
+};
+use crate::api::state::AppState;
+use crate::api::workspace::Scope;
+use crate::common::errors::AppError;
+use crate::common::hooks::ValidJson;
+use crate::macros::object_id;
+
+#[utoipa::path(
+    get,
+    path = "",
+    responses(
+        (status = 200, description = "List accounts successfully", body = [Account]),
+    )
+)]
+pub async fn list_accounts(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+) -> Result<Json<Vec<Account>>, AppError> {
+    let accounts = state
+        .account_service
+        .find_by_user_id(scope.owner_id)
+        .await?;
+
+    Ok(Json(accounts))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body = CreateAccountBody,
+    responses(
+        (status = 201, description = "Create account successfully", body = Account),
+        (status = 409, description = "Another account uses the card"),
+    )
+)]
+pub async fn create_account(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    ValidJson(body): ValidJson<CreateAccountBody>,
+) -> Result<(StatusCode, Json<Account>), AppError> {
+    let account = state
+        .account_service
+        .create(CreateAccountInput {
+            user_id: scope.owner_id,
+            name: body.name,
+            r#type: body.r#type,
+            currency: body.currency,
+            opening_balance: body.opening_balance,
+            card_number: body.card_number,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{account_id}",
+    responses(
+        (status = 200, description = "Get account successfully", body = Account),
+    ),
+    params(
+        ("account_id" = String, Path, description = "Account database id"),
+    )
+)]
+pub async fn get_account(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(account_id): Path<String>,
+) -> Result<Json<Account>, AppError> {
+    let account = state
+        .account_service
+        .find_by_id(object_id!(&account_id))
+        .await?
+        .filter(|account| account.user_id == scope.owner_id.to_hex())
+        .ok_or(AccountError::NotFound)?;
+
+    Ok(Json(account))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/{account_id}",
+    request_body = UpdateAccountBody,
+    responses(
+        (status = 200, description = "Update account successfully", body = Account),
+        (status = 409, description = "Another account uses the card"),
+    ),
+    params(
+        ("account_id" = String, Path, description = "Account database id"),
+    )
+)]
+pub async fn update_account(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(account_id): Path<String>,
+    ValidJson(body): ValidJson<UpdateAccountBody>,
+) -> Result<Json<Account>, AppError> {
+    let account = state
+        .account_service
+        .update(UpdateAccountInput {
+            id: object_id!(&account_id),
+            user_id: scope.owner_id,
+            name: body.name,
+            opening_balance: body.opening_balance,
+            card_number: body.card_number,
+        })
+        .await?
+        .ok_or(AccountError::NotFound)?;
+
+    Ok(Json(account))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{account_id}",
+    responses(
+        (status = 204, description = "Delete account successfully, its transactions are kept without an account"),
+    ),
+    params(
+        ("account_id" = String, Path, description = "Account database id"),
+    )
+)]
+pub async fn delete_account(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(account_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let deleted = state
+        .account_service
+        .delete(object_id!(&account_id), scope.owner_id)
+        .await?;
+    if !deleted {
+        return Err(AccountError::NotFound.into());
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{account_id}/balance",
+    params(
+        ("account_id" = String, Path, description = "Account database id"),
+        AccountBalanceQuery,
+    ),
+    responses(
+        (status = 200, description = "Get account balance successfully", body = AccountBalance),
+    )
+)]
+pub async fn get_balance(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(account_id): Path<String>,
+    Query(query): Query<AccountBalanceQuery>,
+) -> Result<Json<AccountBalance>, AppError> {
+    let balance = state
+        .account_service
+        .get_balance(
+            object_id!(&account_id),
+            scope.owner_id,
+            query.at.unwrap_or_else(chrono::Utc::now),
+        )
+        .await?;
+
+    Ok(Json(balance))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_accounts,
+        create_account,
+        get_account,
+        update_account,
+        delete_account,
+        get_balance,
+    ),
+    components(
+        schemas(
+            Account,
+            AccountType,
+            AccountBalance,
+            CurrencyAmount,
+            CreateAccountBody,
+            UpdateAccountBody,
+        )
+    ),
+    tags(
+        (name = "crate::api::account", description = "Account API")
+    )
+)]
+pub struct AccountApiDoc;
@@ -0,0 +1,30 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::account::AccountType;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountEntity {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub name: String,
+    pub r#type: AccountType,
+    pub currency: String,
+    pub opening_balance: f64,
+    // last 4 digits of the card, matched with the card number of the receipts
+    pub card_number: Option<i16>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Sum of the transactions of an account with the same currency and type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountTotalEntity {
+    pub currency: String,
+    pub r#type: String,
+    pub amount: f64,
+}
@@ -0,0 +1,243 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::api::account::{AccountEntity, AccountError};
+use crate::object_id;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountType {
+    Cash,
+    Bank,
+    CreditCard,
+    EWallet,
+}
+
+impl FromStr for AccountType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cash" => Ok(Self::Cash),
+            "bank" => Ok(Self::Bank),
+            "credit_card" => Ok(Self::CreditCard),
+            "e_wallet" => Ok(Self::EWallet),
+            _ => Err(anyhow!("unknown account type {s}")),
+        }
+    }
+}
+
+impl AccountType {
+    /// Whether receipts paid with a card can be assigned to the account.
+    pub fn has_card(&self) -> bool {
+        matches!(self, Self::Bank | Self::CreditCard)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Cash => "cash",
+            Self::Bank => "bank",
+            Self::CreditCard => "credit card",
+            Self::EWallet => "e-wallet",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    #[schema(example = "66a1f0c2ce6a5cbb87195b10")]
+    pub id: String,
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    #[schema(example = "Visa Platinum")]
+    pub name: String,
+    #[schema(example = "credit_card")]
+    pub r#type: AccountType,
+    #[schema(example = "USD")]
+    pub currency: String,
+    #[schema(example = 0.0)]
+    pub opening_balance: f64,
+    /// Last 4 digits of the card
+    #[schema(example = 8432)]
+    pub card_number: Option<i16>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<AccountEntity> for Account {
+    fn from(value: AccountEntity) -> Self {
+        Self {
+            id: value.id.to_hex(),
+            user_id: value.user_id.to_hex(),
+            name: value.name,
+            r#type: value.r#type,
+            currency: value.currency,
+            opening_balance: value.opening_balance,
+            card_number: value.card_number,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+/// The accounts a transfer read from a message goes between, `from` is known when the
+/// receipt shows a card. A message only tells the kind of the accounts, e.g. an ATM
+/// withdrawal goes from a bank to cash, so the user is asked again when a kind is missing or
+/// matches several accounts rather than saving a transfer no balance can place.
+pub fn transfer_accounts(
+    accounts: &[Account],
+    from: Option<ObjectId>,
+    from_type: Option<AccountType>,
+    to_type: Option<AccountType>,
+) -> Result<(ObjectId, ObjectId), AccountError> {
+    let from = match from {
+        Some(from) => from,
+        None => only_account_of_type(accounts, from_type, "from")?,
+    };
+    let to = only_account_of_type(accounts, to_type, "to")?;
+    if from == to {
+        return Err(AccountError::UnclearTransfer(
+            "a transfer goes between two different accounts".to_string(),
+        ));
+    }
+
+    Ok((from, to))
+}
+
+/// The account of a kind, when the user has only one.
+fn only_account_of_type(
+    accounts: &[Account],
+    r#type: Option<AccountType>,
+    side: &str,
+) -> Result<ObjectId, AccountError> {
+    let r#type = r#type.ok_or_else(|| {
+        AccountError::UnclearTransfer(format!("tell which account the transfer goes {side}"))
+    })?;
+    let mut matching = accounts.iter().filter(|account| account.r#type == r#type);
+    match (matching.next(), matching.next()) {
+        (Some(account), None) => Ok(object_id!(&account.id)),
+        (None, _) => Err(AccountError::UnclearTransfer(format!(
+            "there is no {} account the transfer goes {side}",
+            r#type.label()
+        ))),
+        _ => Err(AccountError::UnclearTransfer(format!(
+            "there are several {} accounts, tell which one the transfer goes {side}",
+            r#type.label()
+        ))),
+    }
+}
+
+/// Amount of a currency other than the one of the account, left out of the balance.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyAmount {
+    #[schema(example = "EUR")]
+    pub currency: String,
+    #[schema(example = -12.5)]
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalance {
+    #[schema(example = "66a1f0c2ce6a5cbb87195b10")]
+    pub account_id: String,
+    #[schema(example = "USD")]
+    pub currency: String,
+    #[schema(example = 0.0)]
+    pub opening_balance: f64,
+    #[schema(example = 1500.0)]
+    pub income: f64,
+    #[schema(example = 420.5)]
+    pub outcome: f64,
+    /// Received from other accounts
+    #[schema(example = 0.0)]
+    pub transferred_in: f64,
+    /// Sent to other accounts
+    #[schema(example = 0.0)]
+    pub transferred_out: f64,
+    /// Opening balance plus income and transfers in, minus outcome and transfers out
+    #[schema(example = 1079.5)]
+    pub balance: f64,
+    #[schema(example = json!([{"currency": "EUR", "amount": -12.5}]))]
+    pub other_currencies: Vec<CurrencyAmount>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(r#type: AccountType) -> Account {
+        Account {
+            id: ObjectId::new().to_hex(),
+            user_id: "66990b1947d76ec3781adc9d".to_string(),
+            name: "Wallet".to_string(),
+            r#type,
+            currency: "VND".to_string(),
+            opening_balance: 0.0,
+            card_number: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_transfer_accounts() {
+        let accounts = vec![
+            account(AccountType::Bank),
+            account(AccountType::Cash),
+            account(AccountType::EWallet),
+            account(AccountType::EWallet),
+        ];
+        let [bank, cash] = [0, 1].map(|i| object_id!(&accounts[i].id));
+
+        // an ATM withdrawal
+        assert_eq!(
+            transfer_accounts(
+                &accounts,
+                None,
+                Some(AccountType::Bank),
+                Some(AccountType::Cash)
+            )
+            .unwrap(),
+            (bank, cash)
+        );
+        // the card on the receipt is the source
+        assert_eq!(
+            transfer_accounts(&accounts, Some(bank), None, Some(AccountType::Cash)).unwrap(),
+            (bank, cash)
+        );
+
+        let unclear = |from, from_type, to_type| {
+            matches!(
+                transfer_accounts(&accounts, from, from_type, to_type),
+                Err(AccountError::UnclearTransfer(_))
+            )
+        };
+        // two e-wallets, which one
+        assert!(unclear(
+            None,
+            Some(AccountType::Bank),
+            Some(AccountType::EWallet)
+        ));
+        // no credit card account
+        assert!(unclear(
+            None,
+            Some(AccountType::CreditCard),
+            Some(AccountType::Cash)
+        ));
+        assert!(unclear(None, None, Some(AccountType::Cash)));
+        assert!(unclear(Some(bank), None, Some(AccountType::Bank)));
+    }
+}
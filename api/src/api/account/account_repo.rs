@@ -0,0 +1,202 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use bson::{doc, from_document};
+use futures::StreamExt;
+use mongodb::options::ReturnDocument;
+use mongodb::Collection;
+
+use crate::api::account::*;
+use crate::api::transaction::TransactionEntity;
+
+#[async_trait]
+pub trait AccountRepoExt: Send + Sync {
+    async fn insert_one(&self, data: CreateAccountData) -> Result<AccountEntity, AccountError>;
+    async fn find_by_id(&self, id: ObjectId) -> Result<Option<AccountEntity>, AccountError>;
+    async fn find_by_user_id(&self, user_id: ObjectId) -> Result<Vec<AccountEntity>, AccountError>;
+    async fn find_by_card_number(
+        &self,
+        user_id: ObjectId,
+        card_number: i16,
+    ) -> Result<Option<AccountEntity>, AccountError>;
+    async fn update_by_id(
+        &self,
+        data: UpdateAccountData,
+    ) -> Result<Option<AccountEntity>, AccountError>;
+    async fn delete_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, AccountError>;
+    async fn get_totals(
+        &self,
+        id: ObjectId,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<AccountTotalEntity>, AccountError>;
+    async fn find_transfers(
+        &self,
+        id: ObjectId,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<TransactionEntity>, AccountError>;
+}
+
+pub type AccountRepoDyn = Arc<dyn AccountRepoExt + Send + Sync>;
+
+pub struct AccountRepo {
+    pub collection: Collection<AccountEntity>,
+    pub transaction_col: Collection<TransactionEntity>,
+}
+
+#[async_trait]
+impl AccountRepoExt for AccountRepo {
+    async fn insert_one(&self, data: CreateAccountData) -> Result<AccountEntity, AccountError> {
+        let document = AccountEntity {
+            id: ObjectId::new(),
+            user_id: data.user_id,
+            name: data.name,
+            r#type: data.r#type,
+            currency: data.currency,
+            opening_balance: data.opening_balance,
+            card_number: data.card_number,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        self.collection
+            .insert_one(&document)
+            .await
+            .map_err(|e| AccountError::Unknown(e.into()))?;
+
+        Ok(document)
+    }
+
+    async fn find_by_id(&self, id: ObjectId) -> Result<Option<AccountEntity>, AccountError> {
+        self.collection
+            .find_one(doc! { "_id": id })
+            .await
+            .map_err(|e| AccountError::Unknown(e.into()))
+    }
+
+    async fn find_by_user_id(&self, user_id: ObjectId) -> Result<Vec<AccountEntity>, AccountError> {
+        let mut cursor = self
+            .collection
+            .find(doc! { "userId": user_id })
+            .sort(doc! { "_id": 1 })
+            .await
+            .map_err(|e| AccountError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+
+    async fn find_by_card_number(
+        &self,
+        user_id: ObjectId,
+        card_number: i16,
+    ) -> Result<Option<AccountEntity>, AccountError> {
+        self.collection
+            .find_one(doc! { "userId": user_id, "cardNumber": card_number as i32 })
+            .await
+            .map_err(|e| AccountError::Unknown(e.into()))
+    }
+
+    async fn update_by_id(
+        &self,
+        data: UpdateAccountData,
+    ) -> Result<Option<AccountEntity>, AccountError> {
+        let mut set = doc! { "updatedAt": chrono::Utc::now() };
+        if let Some(name) = data.name {
+            set.insert("name", name);
+        }
+        if let Some(opening_balance) = data.opening_balance {
+            set.insert("openingBalance", opening_balance);
+        }
+        if let Some(card_number) = data.card_number {
+            set.insert("cardNumber", card_number as i32);
+        }
+
+        self.collection
+            .find_one_and_update(
+                doc! { "_id": data.id, "userId": data.user_id },
+                doc! { "$set": set },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| AccountError::Unknown(e.into()))
+    }
+
+    async fn delete_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, AccountError> {
+        self.collection
+            .delete_one(doc! { "_id": id, "userId": user_id })
+            .await
+            .map(|v| v.deleted_count == 1)
+            .map_err(|e| AccountError::Unknown(e.into()))
+    }
+
+    async fn get_totals(
+        &self,
+        id: ObjectId,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<AccountTotalEntity>, AccountError> {
+        let mut cursor = self
+            .transaction_col
+            .aggregate(vec![
+                doc! {
+                    "$match": {
+                        "accountId": id,
+                        "type": { "$in": ["income", "outcome"] },
+                        "issuedAt": { "$lte": at },
+                    }
+                },
+                doc! {
+                    "$group": {
+                        "_id": { "currency": "$currency", "type": "$type" },
+                        "amount": { "$sum": "$amount" },
+                    }
+                },
+                doc! {
+                    "$project": {
+                        "_id": 0,
+                        "currency": "$_id.currency",
+                        "type": "$_id.type",
+                        "amount": "$amount",
+                    }
+                },
+            ])
+            .await
+            .map_err(|e| AccountError::Unknown(e.into()))?;
+
+        let mut totals = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            let total: AccountTotalEntity =
+                from_document(document).map_err(|e| AccountError::Unknown(e.into()))?;
+            totals.push(total);
+        }
+
+        Ok(totals)
+    }
+
+    async fn find_transfers(
+        &self,
+        id: ObjectId,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<TransactionEntity>, AccountError> {
+        let mut cursor = self
+            .transaction_col
+            .find(doc! {
+                "type": "transfer",
+                "$or": [{ "accountId": id }, { "transfer.toAccountId": id }],
+                "issuedAt": { "$lte": at },
+            })
+            .await
+            .map_err(|e| AccountError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+}
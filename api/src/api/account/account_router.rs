@@ -0,0 +1,32 @@
+use axum::middleware::from_fn_with_state;
+use axum::routing::get;
+use axum::Router;
+
+use crate::api::account::account_controller::*;
+use crate::api::state::AppState;
+use crate::mw::authorization_mw;
+
+pub struct AccountRouter(Router<AppState>);
+
+impl AccountRouter {
+    pub fn new(state: AppState) -> Self {
+        let routes = Router::new()
+            .route("/", get(list_accounts).post(create_account))
+            .route(
+                "/:account_id",
+                get(get_account)
+                    .patch(update_account)
+                    .delete(delete_account),
+            )
+            .route("/:account_id/balance", get(get_balance))
+            .route_layer(from_fn_with_state(state.clone(), authorization_mw));
+
+        Self(routes)
+    }
+}
+
+impl From<AccountRouter> for Router<AppState> {
+    fn from(router: AccountRouter) -> Self {
+        router.0
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+
+use crate::api::account::*;
+use crate::api::transaction::TransactionServiceDyn;
+use crate::common::errors::AppError;
+
+#[async_trait]
+pub trait AccountServiceExt: Send + Sync {
+    async fn create(&self, input: CreateAccountInput) -> Result<Account, AppError>;
+    async fn find_by_id(&self, id: ObjectId) -> Result<Option<Account>, AppError>;
+    async fn find_by_user_id(&self, user_id: ObjectId) -> Result<Vec<Account>, AppError>;
+    /// The account paid with a card, from the last 4 digits printed on a receipt.
+    async fn find_by_card_number(
+        &self,
+        user_id: ObjectId,
+        card_number: i16,
+    ) -> Result<Option<Account>, AppError>;
+    async fn update(&self, input: UpdateAccountInput) -> Result<Option<Account>, AppError>;
+    async fn delete(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, AppError>;
+    async fn get_balance(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<AccountBalance, AppError>;
+}
+
+pub type AccountServiceDyn = Arc<dyn AccountServiceExt + Send + Sync>;
+
+pub struct AccountService {
+    pub repo: AccountRepoDyn,
+    pub transaction_service: TransactionServiceDyn,
+}
+
+impl AccountService {
+    async fn check_card_number(
+        &self,
+        id: Option<ObjectId>,
+        user_id: ObjectId,
+        r#type: AccountType,
+        card_number: Option<i16>,
+    ) -> Result<(), AppError> {
+        let Some(card_number) = card_number else {
+            return Ok(());
+        };
+        if !r#type.has_card() {
+            return Err(AccountError::UnexpectedCardNumber.into());
+        }
+
+        let other = self
+            .repo
+            .find_by_card_number(user_id, card_number)
+            .await?
+            .filter(|account| Some(account.id) != id);
+        if other.is_some() {
+            return Err(AccountError::DuplicateCardNumber(card_number).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AccountServiceExt for AccountService {
+    async fn create(&self, input: CreateAccountInput) -> Result<Account, AppError> {
+        self.check_card_number(None, input.user_id, input.r#type, input.card_number)
+            .await?;
+
+        self.repo
+            .insert_one(input.into())
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    async fn find_by_id(&self, id: ObjectId) -> Result<Option<Account>, AppError> {
+        self.repo
+            .find_by_id(id)
+            .await
+            .map(|v| v.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    async fn find_by_user_id(&self, user_id: ObjectId) -> Result<Vec<Account>, AppError> {
+        self.repo
+            .find_by_user_id(user_id)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn find_by_card_number(
+        &self,
+        user_id: ObjectId,
+        card_number: i16,
+    ) -> Result<Option<Account>, AppError> {
+        self.repo
+            .find_by_card_number(user_id, card_number)
+            .await
+            .map(|v| v.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    async fn update(&self, input: UpdateAccountInput) -> Result<Option<Account>, AppError> {
+        let Some(account) = self
+            .repo
+            .find_by_id(input.id)
+            .await?
+            .filter(|account| account.user_id == input.user_id)
+        else {
+            return Ok(None);
+        };
+        self.check_card_number(
+            Some(account.id),
+            account.user_id,
+            account.r#type,
+            input.card_number,
+        )
+        .await?;
+
+        self.repo
+            .update_by_id(input.into())
+            .await
+            .map(|v| v.map(Into::into))
+            .map_err(Into::into)
+    }
+
+    async fn delete(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, AppError> {
+        let deleted = self.repo.delete_by_id(id, user_id).await?;
+        if deleted {
+            self.transaction_service.unset_account(id).await?;
+        }
+
+        Ok(deleted)
+    }
+
+    async fn get_balance(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<AccountBalance, AppError> {
+        let account = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .filter(|account| account.user_id == user_id)
+            .ok_or(AccountError::NotFound)?;
+        let totals = self.repo.get_totals(id, at).await?;
+        let transfers = self.repo.find_transfers(id, at).await?;
+
+        Ok(account_balance(&account, &totals, &transfers, at))
+    }
+}
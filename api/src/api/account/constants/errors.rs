@@ -0,0 +1,35 @@
+use crate::common::errors::ErrorResponse;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AccountError {
+    #[error("account not found")]
+    NotFound,
+    #[error("another account already uses card {0:04}")]
+    DuplicateCardNumber(i16),
+    #[error("only card accounts have a card number")]
+    UnexpectedCardNumber,
+    #[error("{0}")]
+    UnclearTransfer(String),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for AccountError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::DuplicateCardNumber(_) => (StatusCode::CONFLICT, self.to_string()),
+            Self::UnexpectedCardNumber => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::UnclearTransfer(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+pub struct AccountBalanceQuery {
+    /// Balance at the end of this time, now by default
+    pub at: Option<chrono::DateTime<chrono::Utc>>,
+}
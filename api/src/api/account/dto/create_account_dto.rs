@@ -0,0 +1,57 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::account::AccountType;
+
+pub struct CreateAccountData {
+    pub user_id: ObjectId,
+    pub name: String,
+    pub r#type: AccountType,
+    pub currency: String,
+    pub opening_balance: f64,
+    pub card_number: Option<i16>,
+}
+
+pub struct CreateAccountInput {
+    pub user_id: ObjectId,
+    pub name: String,
+    pub r#type: AccountType,
+    pub currency: String,
+    pub opening_balance: f64,
+    pub card_number: Option<i16>,
+}
+
+impl From<CreateAccountInput> for CreateAccountData {
+    fn from(value: CreateAccountInput) -> Self {
+        Self {
+            user_id: value.user_id,
+            name: value.name,
+            r#type: value.r#type,
+            currency: value.currency.to_uppercase(),
+            opening_balance: value.opening_balance,
+            card_number: value.card_number,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccountBody {
+    #[schema(example = "Visa Platinum")]
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    #[schema(example = "credit_card")]
+    pub r#type: AccountType,
+    #[schema(example = "USD")]
+    #[validate(length(equal = 3))]
+    pub currency: String,
+    #[schema(example = 0.0)]
+    #[serde(default)]
+    pub opening_balance: f64,
+    /// Last 4 digits of the card
+    #[schema(example = 8432)]
+    #[validate(range(min = 0, max = 9999))]
+    pub card_number: Option<i16>,
+}
@@ -0,0 +1,7 @@
+mod account_balance_dto;
+mod create_account_dto;
+mod update_account_dto;
+
+pub use account_balance_dto::*;
+pub use create_account_dto::*;
+pub use update_account_dto::*;
@@ -0,0 +1,47 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub struct UpdateAccountData {
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub name: Option<String>,
+    pub opening_balance: Option<f64>,
+    pub card_number: Option<i16>,
+}
+
+pub struct UpdateAccountInput {
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub name: Option<String>,
+    pub opening_balance: Option<f64>,
+    pub card_number: Option<i16>,
+}
+
+impl From<UpdateAccountInput> for UpdateAccountData {
+    fn from(value: UpdateAccountInput) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            name: value.name,
+            opening_balance: value.opening_balance,
+            card_number: value.card_number,
+        }
+    }
+}
+
+/// The type and currency of an account are fixed, its balance depends on them.
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAccountBody {
+    #[schema(example = "Visa Platinum")]
+    #[validate(length(min = 1, max = 64))]
+    pub name: Option<String>,
+    #[schema(example = 250.0)]
+    pub opening_balance: Option<f64>,
+    /// Last 4 digits of the card
+    #[schema(example = 8432)]
+    #[validate(range(min = 0, max = 9999))]
+    pub card_number: Option<i16>,
+}
@@ -0,0 +1,20 @@
+pub(crate) use account_balance::*;
+#[allow(unused_imports)]
+pub use account_controller::AccountApiDoc;
+pub(crate) use account_entity::*;
+pub use account_model::*;
+pub(crate) use account_repo::*;
+pub use account_router::*;
+pub use account_service::*;
+pub(crate) use constants::*;
+pub(crate) use dto::*;
+
+mod account_balance;
+mod account_controller;
+mod account_entity;
+mod account_model;
+mod account_repo;
+mod account_router;
+mod account_service;
+mod constants;
+mod dto;
@@ -39,6 +39,61 @@ pub fn validate_currency_code<'a>(code: &'a str) -> Option<&'static Currency> {
     None
 }
 
+/// Currencies that are not written with minor units.
+const ZERO_DECIMAL_CURRENCIES: &[&str] = &[
+    "BIF", "CLP", "DJF", "GNF", "ISK", "JPY", "KMF", "KRW", "PYG", "RWF", "UGX", "VND", "VUV",
+    "XAF", "XOF", "XPF",
+];
+
+/// Languages writing numbers as `1.234,56`, the others write `1,234.56`.
+const COMMA_DECIMAL_LANGUAGES: &[&str] = &["vi", "id", "de", "es", "it", "pt", "nl", "tr"];
+
+/// Formats an amount with grouped thousands followed by the currency symbol, e.g. `145,000 ₫`.
+pub fn format_money(amount: f64, code: &str) -> String {
+    format_money_in(amount, code, "en")
+}
+
+/// Formats an amount the way the language writes numbers, e.g. `145.000 ₫` in Vietnamese.
+pub fn format_money_in(amount: f64, code: &str, language: &str) -> String {
+    let (group_separator, decimal_separator) = if COMMA_DECIMAL_LANGUAGES.contains(&language) {
+        ('.', ',')
+    } else {
+        (',', '.')
+    };
+
+    let decimals = if ZERO_DECIMAL_CURRENCIES.contains(&code) {
+        0
+    } else {
+        2
+    };
+    let number = format!("{:.*}", decimals, amount.abs());
+    let (integer, fraction) = match number.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (number.as_str(), None),
+    };
+
+    let mut grouped = String::new();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(group_separator);
+        }
+        grouped.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        grouped.push(decimal_separator);
+        grouped.push_str(fraction);
+    }
+
+    let sign = if amount < 0.0 && grouped.chars().any(|c| c != '0' && c.is_ascii_digit()) {
+        "-"
+    } else {
+        ""
+    };
+    let symbol = validate_currency_code(code).map_or(code, |currency| currency.symbol);
+
+    format!("{sign}{grouped} {symbol}")
+}
+
 pub const CURRENCIES: &'static [Currency] = &[
     Currency::new("AED", "د.إ", "United Arab Emirates Dirham", "درهم إماراتي"),
     Currency::new("AFN", "؋", "Afghan Afghani", "افغانۍ"),
@@ -258,3 +313,27 @@ pub const CURRENCIES: &'static [Currency] = &[
     Currency::new("ZMW", "ZK", "Zambian Kwacha", "Kwacha"),
     Currency::new("ZWL", "Z$", "Zimbabwean Dollar", "Dollar"),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_money() {
+        assert_eq!(format_money(145_000.0, "VND"), "145,000 ₫");
+        assert_eq!(format_money(1_234_567.891, "USD"), "1,234,567.89 $");
+        assert_eq!(format_money(5.0, "USD"), "5.00 $");
+        assert_eq!(format_money(980.0, "JPY"), "980 ¥");
+        assert_eq!(format_money(-12.5, "EUR"), "-12.50 €");
+        assert_eq!(format_money(-0.001, "EUR"), "0.00 €");
+        assert_eq!(format_money(100.0, "XYZ"), "100.00 XYZ");
+    }
+
+    #[test]
+    fn test_format_money_in() {
+        assert_eq!(format_money_in(145_000.0, "VND", "vi"), "145.000 ₫");
+        assert_eq!(format_money_in(1_234.5, "EUR", "vi"), "1.234,50 €");
+        assert_eq!(format_money_in(1_980.0, "JPY", "ja"), "1,980 ¥");
+        assert_eq!(format_money_in(-12.5, "USD", "fr"), "-12.50 $");
+    }
+}
@@ -48,7 +48,7 @@ impl<'a> Region<'a> {
     }
 }
 
-pub const SUPPORTED_LANGUAGES: &'static [&'static str] = &["en"];
+pub const SUPPORTED_LANGUAGES: &'static [&'static str] = &["en", "vi", "ja"];
 
 pub fn validate_language_code<'a>(language_code: &'a str) -> Option<&'static str> {
     for c in SUPPORTED_LANGUAGES {
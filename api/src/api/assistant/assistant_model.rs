@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use serde::Serialize;
+use strum::EnumString;
+
+use crate::api::assistant::tools::IntentToolRaw;
+use crate::api::report::ExpenseByRange;
+use crate::api::transaction::Transaction;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum Command {
+    Help,
+    Undo,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Intent {
+    LogExpense,
+    Question,
+    Command(Command),
+}
+
+impl Intent {
+    /// Commands we can tell without the LLM: messages starting with a slash, e.g. `/undo`,
+    /// and a bare command name, e.g. `undo`.
+    pub fn from_shortcut(prompt: &str) -> Option<Self> {
+        let prompt = prompt.trim();
+
+        if let Some(rest) = prompt.strip_prefix('/') {
+            let name = rest.split_whitespace().next()?;
+            let command = Command::from_str(name).unwrap_or(Command::Help);
+
+            return Some(Self::Command(command));
+        }
+
+        Command::from_str(prompt).ok().map(Self::Command)
+    }
+}
+
+impl From<IntentToolRaw> for Intent {
+    fn from(raw: IntentToolRaw) -> Self {
+        match raw.intent.as_str() {
+            "question" => Self::Question,
+            "command" => Self::Command(
+                raw.command
+                    .and_then(|command| Command::from_str(&command).ok())
+                    .unwrap_or(Command::Help),
+            ),
+            _ => Self::LogExpense,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ExpensesSummary {
+    pub from: String,
+    pub to: String,
+    pub total: f64,
+    pub categories: BTreeMap<String, f64>,
+    pub days: BTreeMap<String, f64>,
+}
+
+impl ExpensesSummary {
+    pub fn new(from: String, to: String, expenses: Vec<ExpenseByRange>) -> Self {
+        let mut categories = BTreeMap::new();
+        let mut days = BTreeMap::new();
+        for expense in expenses.into_iter().filter(|expense| expense.amount != 0.0) {
+            *categories.entry(expense.category_id).or_insert(0.0) += expense.amount;
+            *days.entry(expense.issued_at).or_insert(0.0) += expense.amount;
+        }
+
+        Self {
+            from,
+            to,
+            total: categories.values().sum(),
+            categories,
+            days,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TransactionSummary {
+    pub title: String,
+    pub amount: f64,
+    pub currency: String,
+    pub category: String,
+    pub r#type: String,
+    pub issued_at: String,
+}
+
+impl From<Transaction> for TransactionSummary {
+    fn from(value: Transaction) -> Self {
+        Self {
+            title: value.title,
+            amount: value.amount,
+            currency: value.currency,
+            category: value.category_id,
+            r#type: value.r#type,
+            issued_at: value.issued_at.format("%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_shortcut() {
+        assert_eq!(
+            Intent::from_shortcut("/undo"),
+            Some(Intent::Command(Command::Undo))
+        );
+        assert_eq!(
+            Intent::from_shortcut(" /Help me"),
+            Some(Intent::Command(Command::Help))
+        );
+        assert_eq!(
+            Intent::from_shortcut("/unknown"),
+            Some(Intent::Command(Command::Help))
+        );
+        assert_eq!(
+            Intent::from_shortcut("Undo"),
+            Some(Intent::Command(Command::Undo))
+        );
+        assert_eq!(Intent::from_shortcut("undo the coffee"), None);
+        assert_eq!(Intent::from_shortcut("/"), None);
+        assert_eq!(Intent::from_shortcut("coffee 5 USD"), None);
+    }
+
+    #[test]
+    fn test_expenses_summary() {
+        let expense = |category_id: &str, issued_at: &str, amount: f64| ExpenseByRange {
+            category_id: category_id.to_string(),
+            issued_at: issued_at.to_string(),
+            amount,
+        };
+        let summary = ExpensesSummary::new(
+            "2024-07-01".to_string(),
+            "2024-07-03".to_string(),
+            vec![
+                expense("groceries", "2024-07-01", 10.0),
+                expense("dining_out", "2024-07-01", 5.0),
+                expense("unknown", "2024-07-02", 0.0),
+                expense("groceries", "2024-07-03", 2.5),
+            ],
+        );
+
+        assert_eq!(summary.total, 17.5);
+        assert_eq!(summary.categories.get("groceries"), Some(&12.5));
+        assert_eq!(summary.days.get("2024-07-01"), Some(&15.0));
+        assert_eq!(summary.days.get("2024-07-02"), None);
+    }
+}
@@ -0,0 +1,349 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use chrono::NaiveDate;
+use serde_json::json;
+use tracing::debug;
+
+use crate::api::assistant::tools::{
+    make_classify_intent_tool, make_query_expenses_tool, make_search_transactions_tool,
+    IntentToolRaw, QueryExpensesToolRaw, SearchTransactionsToolRaw, QUERY_EXPENSES_TOOL_NAME,
+    SEARCH_TRANSACTIONS_TOOL_NAME,
+};
+use crate::api::assistant::*;
+use crate::api::message::MessageServiceDyn;
+use crate::api::report::ReportServiceDyn;
+use crate::api::transaction::{SearchTransactionsInput, TransactionServiceDyn};
+use crate::common::errors::AppError;
+use crate::common::mongo::FindOptions;
+use crate::object_id;
+use crate::services::llm::{ChatMessage, LLMError, LLMServiceDyn, ToolCall};
+
+#[async_trait]
+pub trait AssistantServiceExt: Send + Sync {
+    async fn classify(&self, prompt: &str) -> Result<Intent, AppError>;
+    async fn answer(
+        &self,
+        prompt: String,
+        options: AnswerOptions,
+    ) -> Result<(String, String), AppError>;
+    async fn run_command(
+        &self,
+        command: Command,
+        options: CommandOptions,
+    ) -> Result<String, AppError>;
+}
+
+pub type AssistantServiceDyn = Arc<dyn AssistantServiceExt + Send + Sync>;
+
+pub struct AssistantService {
+    pub llm_service: LLMServiceDyn,
+    pub report_service: ReportServiceDyn,
+    pub transaction_service: TransactionServiceDyn,
+    pub message_service: MessageServiceDyn,
+}
+
+impl AssistantService {
+    const MAX_STEPS: usize = 5;
+    const MAX_SEARCH_LIMIT: i64 = 50;
+    const MAX_RANGE_DAYS: i64 = 366;
+    const HELP: &'static str = "Send what you spent, e.g. \"coffee 5 USD\", or a photo of a receipt. \
+        Ask questions like \"how much did I spend on groceries last month?\". \
+        Commands: /undo removes the last logged entry, /help shows this message.";
+
+    fn parse_date(value: &str) -> Result<NaiveDate, String> {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("invalid date: {value}"))
+    }
+
+    fn start_of_day(date: NaiveDate) -> chrono::DateTime<chrono::Utc> {
+        date.and_hms_opt(0, 0, 0).unwrap().and_utc()
+    }
+
+    fn system_prompt(options: &AnswerOptions) -> String {
+        let categories = options
+            .categories
+            .iter()
+            .map(|category| format!("{} ({})", category.id, category.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "You answer questions about the user's spending in an expense tracking chat. \
+            Today is {today} (UTC). The user's main currency is {currency}. \
+            Always look the numbers up with the tools instead of guessing. \
+            Categories: {categories}. \
+            Reply in the language of the question, in one or two short sentences of plain text.",
+            today = chrono::Utc::now().format("%Y-%m-%d (%A)"),
+            currency = options.currency,
+        )
+    }
+
+    async fn query_expenses(&self, user_id: ObjectId, arguments: &str) -> Result<String, String> {
+        let raw = serde_json::from_str::<QueryExpensesToolRaw>(arguments)
+            .map_err(|e| e.to_string())?;
+        let from = Self::parse_date(&raw.from)?;
+        let to = Self::parse_date(&raw.to)?;
+        if to < from || (to - from).num_days() > Self::MAX_RANGE_DAYS {
+            return Err(format!(
+                "the range must be at most {} days",
+                Self::MAX_RANGE_DAYS
+            ));
+        }
+
+        let expenses = self
+            .report_service
+            .get_expenses_by_range(
+                user_id,
+                Self::start_of_day(from),
+                Self::start_of_day(to + chrono::Duration::days(1)),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        let summary = ExpensesSummary::new(raw.from, raw.to, expenses);
+
+        serde_json::to_string(&summary).map_err(|e| e.to_string())
+    }
+
+    async fn search_transactions(
+        &self,
+        user_id: ObjectId,
+        arguments: &str,
+    ) -> Result<String, String> {
+        let raw = serde_json::from_str::<SearchTransactionsToolRaw>(arguments)
+            .map_err(|e| e.to_string())?;
+        let from = raw.from.as_deref().map(Self::parse_date).transpose()?;
+        let to = raw.to.as_deref().map(Self::parse_date).transpose()?;
+        let limit = raw
+            .limit
+            .unwrap_or(20)
+            .clamp(1, Self::MAX_SEARCH_LIMIT);
+
+        let transactions = self
+            .transaction_service
+            .search(
+                SearchTransactionsInput {
+                    user_id,
+                    keyword: raw.keyword.filter(|keyword| !keyword.is_empty()),
+                    category_id: raw.category,
+                    r#type: raw.r#type,
+                    from: from.map(Self::start_of_day),
+                    to: to.map(|to| Self::start_of_day(to + chrono::Duration::days(1))),
+                },
+                FindOptions::with_limit(limit),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(TransactionSummary::from)
+            .collect::<Vec<_>>();
+
+        serde_json::to_string(&transactions).map_err(|e| e.to_string())
+    }
+
+    async fn call_tool(&self, user_id: ObjectId, tool_call: &ToolCall) -> String {
+        let result = match tool_call.name.as_str() {
+            QUERY_EXPENSES_TOOL_NAME => self.query_expenses(user_id, &tool_call.arguments).await,
+            SEARCH_TRANSACTIONS_TOOL_NAME => {
+                self.search_transactions(user_id, &tool_call.arguments)
+                    .await
+            }
+            name => Err(format!("unknown tool: {name}")),
+        };
+
+        result.unwrap_or_else(|error| json!({ "error": error }).to_string())
+    }
+
+    /// The history for the last step, which goes without tools. Providers reject tool calls
+    /// and results when no tools are offered, so they are folded into the prompt as text.
+    fn final_messages(messages: &[ChatMessage]) -> Vec<ChatMessage> {
+        let mut calls = vec![];
+        let mut notes = vec![];
+        let mut history = vec![];
+        for message in messages {
+            match message {
+                ChatMessage::Assistant { tool_calls, .. } => calls.extend(tool_calls),
+                ChatMessage::Tool {
+                    tool_call_id,
+                    content,
+                } => {
+                    let call = calls.iter().find(|call| &call.id == tool_call_id);
+                    notes.push(match call {
+                        Some(call) => format!("- {}({}): {content}", call.name, call.arguments),
+                        None => format!("- {content}"),
+                    });
+                }
+                message => history.push(message.clone()),
+            }
+        }
+
+        if !notes.is_empty() {
+            let note = format!(
+                "Data you already looked up:\n{}\nAnswer with this data, no more lookups.",
+                notes.join("\n")
+            );
+            match history.iter_mut().rev().find_map(|message| match message {
+                ChatMessage::User(prompt) => Some(prompt),
+                _ => None,
+            }) {
+                Some(prompt) => *prompt = format!("{prompt}\n\n{note}"),
+                None => history.push(ChatMessage::User(note)),
+            }
+        }
+
+        history
+    }
+}
+
+#[async_trait]
+impl AssistantServiceExt for AssistantService {
+    async fn classify(&self, prompt: &str) -> Result<Intent, AppError> {
+        if let Some(intent) = Intent::from_shortcut(prompt) {
+            return Ok(intent);
+        }
+
+        let (contents, _) = self
+            .llm_service
+            .chat_with_fn(prompt.to_string(), make_classify_intent_tool())
+            .await?;
+
+        // Anything we cannot classify keeps the original behaviour of logging it.
+        let intent = contents
+            .first()
+            .and_then(|content| serde_json::from_str::<IntentToolRaw>(content).ok())
+            .map(Intent::from)
+            .unwrap_or(Intent::LogExpense);
+        debug!(?intent, "classified message");
+
+        Ok(intent)
+    }
+
+    async fn answer(
+        &self,
+        prompt: String,
+        options: AnswerOptions,
+    ) -> Result<(String, String), AppError> {
+        let tools = vec![
+            make_query_expenses_tool(),
+            make_search_transactions_tool(options.categories.clone()),
+        ];
+        let mut messages = vec![
+            ChatMessage::System(Self::system_prompt(&options)),
+            ChatMessage::User(prompt),
+        ];
+        let mut completions = vec![];
+
+        for step in 1..=Self::MAX_STEPS {
+            // The last step goes without tools so the model has to answer.
+            let (step_messages, step_tools) = if step == Self::MAX_STEPS {
+                (Self::final_messages(&messages), vec![])
+            } else {
+                (messages.clone(), tools.clone())
+            };
+            let (reply, completion) = self
+                .llm_service
+                .chat_with_tools(step_messages, step_tools)
+                .await?;
+            completions.push(completion);
+
+            if reply.tool_calls.is_empty() {
+                let content = reply
+                    .content
+                    .map(|content| content.trim().to_string())
+                    .filter(|content| !content.is_empty())
+                    .ok_or(LLMError::EmptyResponse)?;
+
+                return Ok((content, format!("[{}]", completions.join(","))));
+            }
+
+            let mut results = vec![];
+            for tool_call in &reply.tool_calls {
+                debug!(name = tool_call.name, arguments = tool_call.arguments, "tool call");
+                results.push(ChatMessage::Tool {
+                    tool_call_id: tool_call.id.clone(),
+                    content: self.call_tool(options.user_id, tool_call).await,
+                });
+            }
+            messages.push(ChatMessage::Assistant {
+                content: reply.content,
+                tool_calls: reply.tool_calls,
+            });
+            messages.extend(results);
+        }
+
+        Err(LLMError::EmptyResponse.into())
+    }
+
+    async fn run_command(
+        &self,
+        command: Command,
+        options: CommandOptions,
+    ) -> Result<String, AppError> {
+        match command {
+            Command::Help => Ok(Self::HELP.to_string()),
+            Command::Undo => {
+                let message = self
+                    .message_service
+                    .find_last_logged(options.user_id)
+                    .await?
+                    .ok_or(AssistantError::NothingToUndo)?;
+                let count = message.transactions.as_ref().map_or(0, Vec::len);
+
+                self.message_service
+                    .delete_many_by_id(object_id!(&message.id), options.user_id)
+                    .await?;
+
+                Ok(format!("Removed the last entry ({count} item(s))."))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_final_messages() {
+        let messages = vec![
+            ChatMessage::System("system".to_string()),
+            ChatMessage::User("how much on coffee?".to_string()),
+            ChatMessage::Assistant {
+                content: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".to_string(),
+                    name: SEARCH_TRANSACTIONS_TOOL_NAME.to_string(),
+                    arguments: r#"{"keyword":"coffee"}"#.to_string(),
+                }],
+            },
+            ChatMessage::Tool {
+                tool_call_id: "call_1".to_string(),
+                content: r#"[{"amount":5}]"#.to_string(),
+            },
+        ];
+
+        let messages = AssistantService::final_messages(&messages);
+
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(&messages[0], ChatMessage::System(system) if system == "system"));
+        match &messages[1] {
+            ChatMessage::User(prompt) => {
+                assert!(prompt.starts_with("how much on coffee?"));
+                assert!(prompt.contains(r#"{"keyword":"coffee"}): [{"amount":5}]"#));
+            }
+            message => panic!("unexpected message: {message:?}"),
+        }
+    }
+
+    #[test]
+    fn test_final_messages_without_tools() {
+        let messages = vec![
+            ChatMessage::System("system".to_string()),
+            ChatMessage::User("hi".to_string()),
+        ];
+
+        let messages = AssistantService::final_messages(&messages);
+
+        assert!(matches!(&messages[1], ChatMessage::User(prompt) if prompt == "hi"));
+    }
+}
@@ -0,0 +1,27 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+use crate::common::errors::ErrorResponse;
+
+#[derive(Error, Debug)]
+pub enum AssistantError {
+    #[error("nothing to undo")]
+    NothingToUndo,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for AssistantError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::NothingToUndo => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
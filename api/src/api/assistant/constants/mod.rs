@@ -0,0 +1,4 @@
+mod errors;
+pub mod tools;
+
+pub use errors::*;
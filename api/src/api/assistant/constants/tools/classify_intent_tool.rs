@@ -0,0 +1,38 @@
+use async_openai::types::{FunctionObject, FunctionObjectArgs};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IntentToolRaw {
+    pub intent: String,
+    pub command: Option<String>,
+}
+
+pub fn make_classify_intent_tool() -> FunctionObject {
+    FunctionObjectArgs::default()
+        .name("classify_intent")
+        .description(
+            "Classify a chat message sent to an expense tracker. \
+            Use log_expense when the message records money spent or received, \
+            question when it asks about past spending or income, \
+            command when it asks the bot to do something such as undoing the last entry",
+        )
+        .parameters(json!({
+            "type": "object",
+            "required": ["intent"],
+            "properties": {
+                "intent": {
+                    "type": "string",
+                    "enum": ["log_expense", "question", "command"],
+                    "description": "The intent of the message",
+                },
+                "command": {
+                    "type": "string",
+                    "enum": ["help", "undo"],
+                    "description": "The requested command, only when the intent is command",
+                },
+            },
+        }))
+        .build()
+        .unwrap()
+}
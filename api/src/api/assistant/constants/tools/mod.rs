@@ -0,0 +1,7 @@
+mod classify_intent_tool;
+mod query_expenses_tool;
+mod search_transactions_tool;
+
+pub use classify_intent_tool::*;
+pub use query_expenses_tool::*;
+pub use search_transactions_tool::*;
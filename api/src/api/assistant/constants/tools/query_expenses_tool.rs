@@ -0,0 +1,36 @@
+use async_openai::types::{FunctionObject, FunctionObjectArgs};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub const QUERY_EXPENSES_TOOL_NAME: &str = "query_expenses";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QueryExpensesToolRaw {
+    pub from: String,
+    pub to: String,
+}
+
+pub fn make_query_expenses_tool() -> FunctionObject {
+    FunctionObjectArgs::default()
+        .name(QUERY_EXPENSES_TOOL_NAME)
+        .description(
+            "Get the user's total amounts grouped by category and by day in a date range. \
+            Amounts are summed as recorded, without currency conversion",
+        )
+        .parameters(json!({
+            "type": "object",
+            "required": ["from", "to"],
+            "properties": {
+                "from": {
+                    "type": "string",
+                    "description": "First day of the range (inclusive), format: YYYY-MM-DD",
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Last day of the range (inclusive), format: YYYY-MM-DD",
+                },
+            },
+        }))
+        .build()
+        .unwrap()
+}
@@ -0,0 +1,60 @@
+use crate::api::category::Category;
+use async_openai::types::{FunctionObject, FunctionObjectArgs};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub const SEARCH_TRANSACTIONS_TOOL_NAME: &str = "search_transactions";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SearchTransactionsToolRaw {
+    pub keyword: Option<String>,
+    pub category: Option<String>,
+    pub r#type: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub limit: Option<i64>,
+}
+
+pub fn make_search_transactions_tool(categories: Vec<Category>) -> FunctionObject {
+    let categories = categories
+        .into_iter()
+        .map(|category| category.id)
+        .collect::<Vec<String>>();
+
+    FunctionObjectArgs::default()
+        .name(SEARCH_TRANSACTIONS_TOOL_NAME)
+        .description("Find the user's transactions, newest first")
+        .parameters(json!({
+            "type": "object",
+            "properties": {
+                "keyword": {
+                    "type": "string",
+                    "description": "Part of the transaction title, e.g. coffee",
+                },
+                "category": {
+                    "type": "string",
+                    "enum": categories,
+                    "description": "The category of the transactions",
+                },
+                "type": {
+                    "type": "string",
+                    "enum": ["income", "outcome", "transfer", "debt", "other"],
+                    "description": "The type of the transactions",
+                },
+                "from": {
+                    "type": "string",
+                    "description": "First day to search (inclusive), format: YYYY-MM-DD",
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Last day to search (inclusive), format: YYYY-MM-DD",
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of transactions to return, at most 50",
+                },
+            },
+        }))
+        .build()
+        .unwrap()
+}
@@ -0,0 +1,14 @@
+use crate::api::category::Category;
+use bson::oid::ObjectId;
+
+#[derive(Clone, Debug)]
+pub struct AnswerOptions {
+    pub user_id: ObjectId,
+    pub currency: String,
+    pub categories: Vec<Category>,
+}
+
+#[derive(Clone, Debug)]
+pub struct CommandOptions {
+    pub user_id: ObjectId,
+}
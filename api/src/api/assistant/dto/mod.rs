@@ -0,0 +1,3 @@
+mod assistant_dto;
+
+pub use assistant_dto::*;
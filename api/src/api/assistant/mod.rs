@@ -0,0 +1,9 @@
+mod assistant_model;
+mod assistant_service;
+mod constants;
+mod dto;
+
+pub use assistant_model::*;
+pub use assistant_service::*;
+pub use constants::*;
+pub use dto::*;
@@ -33,3 +33,69 @@ impl Category {
         }
     }
 }
+
+/// Names of the categories in the other languages replies are written in: id, Vietnamese
+/// and Japanese.
+const CATEGORY_LABELS: &[(&str, &str, &str)] = &[
+    ("housing", "Nhà ở", "住居"),
+    ("household_items", "Đồ gia dụng", "家庭用品"),
+    ("childcare", "Chăm sóc trẻ em", "育児"),
+    ("transportation", "Đi lại", "交通"),
+    ("utilities", "Điện nước, internet", "光熱費・通信"),
+    ("groceries", "Thực phẩm", "食料品"),
+    ("dining_out", "Ăn ngoài", "外食"),
+    ("pets", "Thú cưng", "ペット"),
+    ("entertainment", "Giải trí", "娯楽"),
+    ("healthcare", "Y tế", "医療"),
+    ("insurance", "Bảo hiểm", "保険"),
+    ("personal_care", "Chăm sóc cá nhân", "美容・健康"),
+    ("debts", "Trả nợ thẻ, khoản vay", "ローン・カード返済"),
+    ("givings", "Từ thiện, quà tặng", "寄付・贈り物"),
+    ("shopping", "Mua sắm", "買い物"),
+    ("education", "Giáo dục", "教育"),
+    ("travel", "Du lịch", "旅行"),
+    ("miscellaneous", "Khác", "その他"),
+    ("transfer", "Chuyển tiền", "振替"),
+    ("debt", "Vay, cho vay", "貸し借り"),
+    ("unknown", "Không rõ", "不明"),
+];
+
+impl Category {
+    /// Name of the category in the language, the English name when it has no other.
+    pub fn label(&self, language: &str) -> &str {
+        CATEGORY_LABELS
+            .iter()
+            .find(|(id, _, _)| *id == self.id)
+            .and_then(|(_, vi, ja)| match language {
+                "vi" => Some(*vi),
+                "ja" => Some(*ja),
+                _ => None,
+            })
+            .unwrap_or(&self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label() {
+        let category = |id: &str, name: &str| {
+            Category::new(
+                id.to_string(),
+                name.to_string(),
+                String::new(),
+                String::new(),
+                "outcome".to_string(),
+            )
+        };
+        let groceries = category("groceries", "Groceries");
+
+        assert_eq!(groceries.label("vi"), "Thực phẩm");
+        assert_eq!(groceries.label("ja"), "食料品");
+        assert_eq!(groceries.label("en"), "Groceries");
+        assert_eq!(groceries.label("fr"), "Groceries");
+        assert_eq!(category("custom", "Custom").label("vi"), "Custom");
+    }
+}
@@ -145,6 +145,20 @@ impl CategoryServiceExt for CategoryService {
                 color: "#fdc3aa".to_string(),
                 r#type: "outcome".to_string(),
             },
+            Category {
+                id: "transfer".to_string(),
+                name: "Transfer".to_string(),
+                description: "ATM withdrawals, top-ups, credit card payments, moving money between own accounts, etc.".to_string(),
+                color: "#93c5fd".to_string(),
+                r#type: "transfer".to_string(),
+            },
+            Category {
+                id: "debt".to_string(),
+                name: "Debt".to_string(),
+                description: "Lending, borrowing and paying back money, etc.".to_string(),
+                color: "#c4b5fd".to_string(),
+                r#type: "debt".to_string(),
+            },
             Category {
                 id: "unknown".to_string(),
                 name: "Unknown".to_string(),
@@ -0,0 +1,32 @@
+use crate::common::errors::ErrorResponse;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DebtError {
+    #[error("debt not found")]
+    NotFound,
+    #[error("counterparty not found")]
+    CounterpartyNotFound,
+    #[error("counterparty {0} already exists")]
+    DuplicateCounterparty(String),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for DebtError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::CounterpartyNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::DuplicateCounterparty(_) => (StatusCode::CONFLICT, self.to_string()),
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
@@ -0,0 +1,107 @@
+use crate::api::debt::{CounterpartyEntity, DebtBalance, DebtEntity, OutstandingAmount};
+
+// below a cent, the debt is settled
+const SETTLED: f64 = 0.005;
+
+/// What each counterparty owes the user, or the user owes them, per currency.
+pub fn debt_balances(
+    counterparties: &[CounterpartyEntity],
+    debts: &[DebtEntity],
+) -> Vec<DebtBalance> {
+    counterparties
+        .iter()
+        .map(|counterparty| {
+            let mut outstanding: Vec<OutstandingAmount> = vec![];
+            for debt in debts
+                .iter()
+                .filter(|debt| debt.counterparty_id == counterparty.id)
+            {
+                let amount = debt.kind.signed(debt.amount);
+                match outstanding
+                    .iter_mut()
+                    .find(|other| other.currency == debt.currency)
+                {
+                    Some(other) => other.amount += amount,
+                    None => outstanding.push(OutstandingAmount {
+                        currency: debt.currency.clone(),
+                        amount,
+                    }),
+                }
+            }
+            outstanding.retain(|other| other.amount.abs() >= SETTLED);
+            outstanding.sort_by(|a, b| a.currency.cmp(&b.currency));
+
+            DebtBalance {
+                counterparty_id: counterparty.id.to_hex(),
+                name: counterparty.name.clone(),
+                outstanding,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bson::oid::ObjectId;
+
+    use crate::api::debt::DebtKind;
+
+    use super::*;
+
+    fn counterparty(name: &str) -> CounterpartyEntity {
+        CounterpartyEntity {
+            id: ObjectId::new(),
+            user_id: ObjectId::new(),
+            name: name.to_string(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn debt(counterparty_id: ObjectId, kind: DebtKind, amount: f64, currency: &str) -> DebtEntity {
+        DebtEntity {
+            id: ObjectId::new(),
+            user_id: ObjectId::new(),
+            counterparty_id,
+            transaction_id: None,
+            kind,
+            amount,
+            currency: currency.to_string(),
+            note: None,
+            issued_at: chrono::Utc::now(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_debt_balances() {
+        let minh = counterparty("Minh");
+        let lan = counterparty("Lan");
+        let debts = vec![
+            debt(minh.id, DebtKind::Lent, 500_000.0, "VND"),
+            debt(minh.id, DebtKind::RepaidToMe, 200_000.0, "VND"),
+            debt(minh.id, DebtKind::Borrowed, 20.0, "USD"),
+            debt(lan.id, DebtKind::Borrowed, 100_000.0, "VND"),
+            debt(lan.id, DebtKind::RepaidByMe, 100_000.0, "VND"),
+        ];
+
+        let balances = debt_balances(&[minh.clone(), lan], &debts);
+
+        assert_eq!(
+            balances[0].outstanding,
+            vec![
+                OutstandingAmount {
+                    currency: "USD".to_string(),
+                    amount: -20.0,
+                },
+                OutstandingAmount {
+                    currency: "VND".to_string(),
+                    amount: 300_000.0,
+                },
+            ]
+        );
+        // settled
+        assert!(balances[1].outstanding.is_empty());
+    }
+}
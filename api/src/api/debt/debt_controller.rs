@@ -0,0 +1,157 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use utoipa::OpenApi;
+
+use crate::api::debt::{
+    Counterparty, CreateCounterpartyBody, CreateCounterpartyInput, CreateDebtBody, CreateDebtInput,
+    Debt, DebtBalance, DebtError, DebtKind, ListDebtsQuery, OutstandingAmount,
+};
+use crate::api::state::AppState;
+use crate::api::workspace::Scope;
+use crate::common::errors::AppError;
+use crate::common::hooks::ValidJson;
+use crate::macros::object_id;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(
+        ListDebtsQuery,
+    ),
+    responses(
+        (status = 200, description = "List debts successfully, the latest first", body = [Debt]),
+    )
+)]
+pub async fn list_debts(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Query(query): Query<ListDebtsQuery>,
+) -> Result<Json<Vec<Debt>>, AppError> {
+    let debts = state
+        .debt_service
+        .list(
+            scope.owner_id,
+            query.counterparty_id.map(|id| object_id!(&id)),
+        )
+        .await?;
+
+    Ok(Json(debts))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body = CreateDebtBody,
+    responses(
+        (status = 201, description = "Create debt successfully", body = Debt),
+    )
+)]
+pub async fn create_debt(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    ValidJson(body): ValidJson<CreateDebtBody>,
+) -> Result<(StatusCode, Json<Debt>), AppError> {
+    let debt = state
+        .debt_service
+        .create(CreateDebtInput {
+            user_id: scope.owner_id,
+            counterparty_id: object_id!(&body.counterparty_id),
+            transaction_id: None,
+            kind: body.kind,
+            amount: body.amount,
+            currency: body.currency,
+            note: body.note,
+            issued_at: body.issued_at.unwrap_or_else(chrono::Utc::now),
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(debt)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{debt_id}",
+    responses(
+        (status = 204, description = "Delete debt successfully"),
+    ),
+    params(
+        ("debt_id" = String, Path, description = "Debt database id"),
+    )
+)]
+pub async fn delete_debt(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(debt_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let deleted = state
+        .debt_service
+        .delete(object_id!(&debt_id), scope.owner_id)
+        .await?;
+    if !deleted {
+        return Err(DebtError::NotFound.into());
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/balances",
+    responses(
+        (status = 200, description = "Get outstanding balances per counterparty successfully", body = [DebtBalance]),
+    )
+)]
+pub async fn get_balances(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+) -> Result<Json<Vec<DebtBalance>>, AppError> {
+    let balances = state.debt_service.get_balances(scope.owner_id).await?;
+
+    Ok(Json(balances))
+}
+
+#[utoipa::path(
+    post,
+    path = "/counterparties",
+    request_body = CreateCounterpartyBody,
+    responses(
+        (status = 201, description = "Create counterparty successfully", body = Counterparty),
+        (status = 409, description = "A counterparty has the same name"),
+    )
+)]
+pub async fn create_counterparty(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    ValidJson(body): ValidJson<CreateCounterpartyBody>,
+) -> Result<(StatusCode, Json<Counterparty>), AppError> {
+    let counterparty = state
+        .debt_service
+        .create_counterparty(CreateCounterpartyInput {
+            user_id: scope.owner_id,
+            name: body.name,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(counterparty)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_debts, create_debt, delete_debt, get_balances, create_counterparty),
+    components(
+        schemas(
+            Debt,
+            DebtKind,
+            DebtBalance,
+            OutstandingAmount,
+            Counterparty,
+            CreateDebtBody,
+            CreateCounterpartyBody,
+        )
+    ),
+    tags(
+        (name = "crate::api::debt", description = "Debt API")
+    )
+)]
+pub struct DebtApiDoc;
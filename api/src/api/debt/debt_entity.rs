@@ -0,0 +1,41 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::debt::DebtKind;
+
+/// A person the user lends money to or borrows money from.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CounterpartyEntity {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub name: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// An entry of the ledger with a counterparty. A loan and each of its repayments are
+/// separate entries, the outstanding balance is their sum.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtEntity {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub counterparty_id: ObjectId,
+    // the transaction the entry was read from, none when added by hand
+    pub transaction_id: Option<ObjectId>,
+    pub kind: DebtKind,
+    pub amount: f64,
+    pub currency: String,
+    pub note: Option<String>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
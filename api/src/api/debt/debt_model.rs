@@ -0,0 +1,141 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::api::debt::{CounterpartyEntity, DebtEntity};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebtKind {
+    /// The user lent money, the counterparty owes more
+    Lent,
+    /// The user borrowed money, the user owes more
+    Borrowed,
+    /// The counterparty paid the user back
+    RepaidToMe,
+    /// The user paid the counterparty back
+    RepaidByMe,
+}
+
+impl FromStr for DebtKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lent" => Ok(Self::Lent),
+            "borrowed" => Ok(Self::Borrowed),
+            "repaid_to_me" => Ok(Self::RepaidToMe),
+            "repaid_by_me" => Ok(Self::RepaidByMe),
+            _ => Err(anyhow!("unknown debt kind {s}")),
+        }
+    }
+}
+
+impl DebtKind {
+    /// Change of what the counterparty owes the user for an amount of this kind.
+    pub fn signed(&self, amount: f64) -> f64 {
+        match self {
+            Self::Lent | Self::RepaidByMe => amount,
+            Self::Borrowed | Self::RepaidToMe => -amount,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Counterparty {
+    #[schema(example = "66a2c1d0ce6a5cbb87195c20")]
+    pub id: String,
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    #[schema(example = "Minh")]
+    pub name: String,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<CounterpartyEntity> for Counterparty {
+    fn from(value: CounterpartyEntity) -> Self {
+        Self {
+            id: value.id.to_hex(),
+            user_id: value.user_id.to_hex(),
+            name: value.name,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Debt {
+    #[schema(example = "66a2c1d0ce6a5cbb87195c21")]
+    pub id: String,
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    #[schema(example = "66a2c1d0ce6a5cbb87195c20")]
+    pub counterparty_id: String,
+    /// Transaction the entry was read from
+    #[schema(example = "669fb456ce6a5cbb87195a60")]
+    pub transaction_id: Option<String>,
+    #[schema(example = "lent")]
+    pub kind: DebtKind,
+    #[schema(example = 500000.0)]
+    pub amount: f64,
+    #[schema(example = "VND")]
+    pub currency: String,
+    #[schema(example = "Lunch money")]
+    pub note: Option<String>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<DebtEntity> for Debt {
+    fn from(value: DebtEntity) -> Self {
+        Self {
+            id: value.id.to_hex(),
+            user_id: value.user_id.to_hex(),
+            counterparty_id: value.counterparty_id.to_hex(),
+            transaction_id: value.transaction_id.map(|id| id.to_hex()),
+            kind: value.kind,
+            amount: value.amount,
+            currency: value.currency,
+            note: value.note,
+            issued_at: value.issued_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OutstandingAmount {
+    #[schema(example = "VND")]
+    pub currency: String,
+    /// Positive when the counterparty owes the user, negative when the user owes them
+    #[schema(example = 300000.0)]
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DebtBalance {
+    #[schema(example = "66a2c1d0ce6a5cbb87195c20")]
+    pub counterparty_id: String,
+    #[schema(example = "Minh")]
+    pub name: String,
+    /// One amount per currency, settled currencies are left out
+    #[schema(example = json!([{"currency": "VND", "amount": 300000.0}]))]
+    pub outstanding: Vec<OutstandingAmount>,
+}
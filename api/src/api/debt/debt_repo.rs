@@ -0,0 +1,251 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use bson::{doc, Document};
+use futures::StreamExt;
+use mongodb::{ClientSession, Collection};
+
+use crate::api::debt::*;
+
+#[async_trait]
+pub trait DebtRepoExt: Send + Sync {
+    async fn insert_counterparty(
+        &self,
+        data: CreateCounterpartyData,
+    ) -> Result<CounterpartyEntity, DebtError>;
+    async fn find_counterparty_by_id(
+        &self,
+        id: ObjectId,
+    ) -> Result<Option<CounterpartyEntity>, DebtError>;
+    async fn find_counterparty_by_name(
+        &self,
+        user_id: ObjectId,
+        name: &str,
+    ) -> Result<Option<CounterpartyEntity>, DebtError>;
+    async fn find_counterparties(
+        &self,
+        user_id: ObjectId,
+    ) -> Result<Vec<CounterpartyEntity>, DebtError>;
+    async fn find_counterparty_by_name_with_session(
+        &self,
+        user_id: ObjectId,
+        name: &str,
+        session: &mut ClientSession,
+    ) -> Result<Option<CounterpartyEntity>, DebtError>;
+    async fn insert_counterparty_with_session(
+        &self,
+        data: CreateCounterpartyData,
+        session: &mut ClientSession,
+    ) -> Result<CounterpartyEntity, DebtError>;
+    async fn insert_one(&self, data: CreateDebtData) -> Result<DebtEntity, DebtError>;
+    async fn insert_many_with_session(
+        &self,
+        items: &[CreateDebtData],
+        session: &mut ClientSession,
+    ) -> Result<Vec<DebtEntity>, DebtError>;
+    async fn find(&self, filter: Document) -> Result<Vec<DebtEntity>, DebtError>;
+    async fn delete_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, DebtError>;
+    async fn delete_by_transaction_ids_with_session(
+        &self,
+        ids: &[ObjectId],
+        session: &mut ClientSession,
+    ) -> Result<u64, DebtError>;
+}
+
+pub type DebtRepoDyn = Arc<dyn DebtRepoExt + Send + Sync>;
+
+pub struct DebtRepo {
+    pub collection: Collection<DebtEntity>,
+    pub counterparty_col: Collection<CounterpartyEntity>,
+}
+
+fn new_counterparty(data: CreateCounterpartyData) -> CounterpartyEntity {
+    CounterpartyEntity {
+        id: ObjectId::new(),
+        user_id: data.user_id,
+        name: data.name,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+// the same name, ignoring case
+fn counterparty_name_filter(user_id: ObjectId, name: &str) -> Document {
+    doc! {
+        "userId": user_id,
+        "name": {
+            "$regex": format!("^{}$", regex::escape(name.trim())),
+            "$options": "i",
+        },
+    }
+}
+
+fn new_debt(data: &CreateDebtData) -> DebtEntity {
+    DebtEntity {
+        id: ObjectId::new(),
+        user_id: data.user_id,
+        counterparty_id: data.counterparty_id,
+        transaction_id: data.transaction_id,
+        kind: data.kind,
+        amount: data.amount,
+        currency: data.currency.clone(),
+        note: data.note.clone(),
+        issued_at: data.issued_at,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+#[async_trait]
+impl DebtRepoExt for DebtRepo {
+    async fn insert_counterparty(
+        &self,
+        data: CreateCounterpartyData,
+    ) -> Result<CounterpartyEntity, DebtError> {
+        let document = new_counterparty(data);
+
+        self.counterparty_col
+            .insert_one(&document)
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))?;
+
+        Ok(document)
+    }
+
+    async fn find_counterparty_by_id(
+        &self,
+        id: ObjectId,
+    ) -> Result<Option<CounterpartyEntity>, DebtError> {
+        self.counterparty_col
+            .find_one(doc! { "_id": id })
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))
+    }
+
+    async fn find_counterparty_by_name(
+        &self,
+        user_id: ObjectId,
+        name: &str,
+    ) -> Result<Option<CounterpartyEntity>, DebtError> {
+        self.counterparty_col
+            .find_one(counterparty_name_filter(user_id, name))
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))
+    }
+
+    async fn find_counterparties(
+        &self,
+        user_id: ObjectId,
+    ) -> Result<Vec<CounterpartyEntity>, DebtError> {
+        let mut cursor = self
+            .counterparty_col
+            .find(doc! { "userId": user_id })
+            .sort(doc! { "name": 1 })
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+
+    async fn find_counterparty_by_name_with_session(
+        &self,
+        user_id: ObjectId,
+        name: &str,
+        session: &mut ClientSession,
+    ) -> Result<Option<CounterpartyEntity>, DebtError> {
+        self.counterparty_col
+            .find_one(counterparty_name_filter(user_id, name))
+            .session(session)
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))
+    }
+
+    async fn insert_counterparty_with_session(
+        &self,
+        data: CreateCounterpartyData,
+        session: &mut ClientSession,
+    ) -> Result<CounterpartyEntity, DebtError> {
+        let document = new_counterparty(data);
+
+        self.counterparty_col
+            .insert_one(&document)
+            .session(session)
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))?;
+
+        Ok(document)
+    }
+
+    async fn insert_one(&self, data: CreateDebtData) -> Result<DebtEntity, DebtError> {
+        let document = new_debt(&data);
+
+        self.collection
+            .insert_one(&document)
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))?;
+
+        Ok(document)
+    }
+
+    async fn insert_many_with_session(
+        &self,
+        items: &[CreateDebtData],
+        session: &mut ClientSession,
+    ) -> Result<Vec<DebtEntity>, DebtError> {
+        let documents = items.iter().map(new_debt).collect::<Vec<_>>();
+        if documents.is_empty() {
+            return Ok(documents);
+        }
+
+        self.collection
+            .insert_many(&documents)
+            .session(session)
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))?;
+
+        Ok(documents)
+    }
+
+    async fn find(&self, filter: Document) -> Result<Vec<DebtEntity>, DebtError> {
+        let mut cursor = self
+            .collection
+            .find(filter)
+            .sort(doc! { "issuedAt": -1 })
+            .await
+            .map_err(|e| DebtError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+
+    async fn delete_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, DebtError> {
+        self.collection
+            .delete_one(doc! { "_id": id, "userId": user_id })
+            .await
+            .map(|v| v.deleted_count == 1)
+            .map_err(|e| DebtError::Unknown(e.into()))
+    }
+
+    async fn delete_by_transaction_ids_with_session(
+        &self,
+        ids: &[ObjectId],
+        session: &mut ClientSession,
+    ) -> Result<u64, DebtError> {
+        self.collection
+            .delete_many(doc! { "transactionId": { "$in": ids } })
+            .session(session)
+            .await
+            .map(|v| v.deleted_count)
+            .map_err(|e| DebtError::Unknown(e.into()))
+    }
+}
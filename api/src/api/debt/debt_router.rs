@@ -0,0 +1,28 @@
+use axum::middleware::from_fn_with_state;
+use axum::routing::{delete, get, post};
+use axum::Router;
+
+use crate::api::debt::debt_controller::*;
+use crate::api::state::AppState;
+use crate::mw::authorization_mw;
+
+pub struct DebtRouter(Router<AppState>);
+
+impl DebtRouter {
+    pub fn new(state: AppState) -> Self {
+        let routes = Router::new()
+            .route("/", get(list_debts).post(create_debt))
+            .route("/balances", get(get_balances))
+            .route("/counterparties", post(create_counterparty))
+            .route("/:debt_id", delete(delete_debt))
+            .route_layer(from_fn_with_state(state.clone(), authorization_mw));
+
+        Self(routes)
+    }
+}
+
+impl From<DebtRouter> for Router<AppState> {
+    fn from(router: DebtRouter) -> Self {
+        router.0
+    }
+}
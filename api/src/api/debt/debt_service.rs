@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::doc;
+use bson::oid::ObjectId;
+use mongodb::ClientSession;
+
+use crate::api::debt::*;
+use crate::common::errors::AppError;
+
+#[async_trait]
+pub trait DebtServiceExt: Send + Sync {
+    async fn create_counterparty(
+        &self,
+        input: CreateCounterpartyInput,
+    ) -> Result<Counterparty, AppError>;
+    /// The counterparty with this name, ignoring case, created when it is new.
+    async fn find_or_create_counterparty_with_session(
+        &self,
+        user_id: ObjectId,
+        name: &str,
+        session: &mut ClientSession,
+    ) -> Result<Counterparty, AppError>;
+    async fn get_balances(&self, user_id: ObjectId) -> Result<Vec<DebtBalance>, AppError>;
+    async fn create(&self, input: CreateDebtInput) -> Result<Debt, AppError>;
+    async fn insert_many_with_session(
+        &self,
+        items: &[CreateDebtInput],
+        session: &mut ClientSession,
+    ) -> Result<Vec<Debt>, AppError>;
+    async fn list(
+        &self,
+        user_id: ObjectId,
+        counterparty_id: Option<ObjectId>,
+    ) -> Result<Vec<Debt>, AppError>;
+    async fn delete(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, AppError>;
+    async fn delete_by_transaction_ids_with_session(
+        &self,
+        ids: &[ObjectId],
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError>;
+}
+
+pub type DebtServiceDyn = Arc<dyn DebtServiceExt + Send + Sync>;
+
+pub struct DebtService {
+    pub repo: DebtRepoDyn,
+}
+
+#[async_trait]
+impl DebtServiceExt for DebtService {
+    async fn create_counterparty(
+        &self,
+        input: CreateCounterpartyInput,
+    ) -> Result<Counterparty, AppError> {
+        let data: CreateCounterpartyData = input.into();
+        if self
+            .repo
+            .find_counterparty_by_name(data.user_id, &data.name)
+            .await?
+            .is_some()
+        {
+            return Err(DebtError::DuplicateCounterparty(data.name).into());
+        }
+
+        self.repo
+            .insert_counterparty(data)
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    async fn find_or_create_counterparty_with_session(
+        &self,
+        user_id: ObjectId,
+        name: &str,
+        session: &mut ClientSession,
+    ) -> Result<Counterparty, AppError> {
+        if let Some(counterparty) = self
+            .repo
+            .find_counterparty_by_name_with_session(user_id, name, session)
+            .await?
+        {
+            return Ok(counterparty.into());
+        }
+
+        self.repo
+            .insert_counterparty_with_session(
+                CreateCounterpartyInput {
+                    user_id,
+                    name: name.to_string(),
+                }
+                .into(),
+                session,
+            )
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    async fn get_balances(&self, user_id: ObjectId) -> Result<Vec<DebtBalance>, AppError> {
+        let (counterparties, debts) = tokio::try_join!(
+            self.repo.find_counterparties(user_id),
+            self.repo.find(doc! { "userId": user_id })
+        )?;
+
+        Ok(debt_balances(&counterparties, &debts))
+    }
+
+    async fn create(&self, input: CreateDebtInput) -> Result<Debt, AppError> {
+        self.repo
+            .find_counterparty_by_id(input.counterparty_id)
+            .await?
+            .filter(|counterparty| counterparty.user_id == input.user_id)
+            .ok_or(DebtError::CounterpartyNotFound)?;
+
+        self.repo
+            .insert_one((&input).into())
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    async fn insert_many_with_session(
+        &self,
+        items: &[CreateDebtInput],
+        session: &mut ClientSession,
+    ) -> Result<Vec<Debt>, AppError> {
+        let items = items
+            .iter()
+            .map(Into::into)
+            .collect::<Vec<CreateDebtData>>();
+
+        self.repo
+            .insert_many_with_session(&items, session)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn list(
+        &self,
+        user_id: ObjectId,
+        counterparty_id: Option<ObjectId>,
+    ) -> Result<Vec<Debt>, AppError> {
+        let mut filter = doc! { "userId": user_id };
+        if let Some(counterparty_id) = counterparty_id {
+            filter.insert("counterpartyId", counterparty_id);
+        }
+
+        self.repo
+            .find(filter)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn delete(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, AppError> {
+        self.repo
+            .delete_by_id(id, user_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn delete_by_transaction_ids_with_session(
+        &self,
+        ids: &[ObjectId],
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError> {
+        self.repo
+            .delete_by_transaction_ids_with_session(ids, session)
+            .await
+            .map_err(Into::into)
+    }
+}
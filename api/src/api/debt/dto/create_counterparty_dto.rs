@@ -0,0 +1,31 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub struct CreateCounterpartyData {
+    pub user_id: ObjectId,
+    pub name: String,
+}
+
+pub struct CreateCounterpartyInput {
+    pub user_id: ObjectId,
+    pub name: String,
+}
+
+impl From<CreateCounterpartyInput> for CreateCounterpartyData {
+    fn from(value: CreateCounterpartyInput) -> Self {
+        Self {
+            user_id: value.user_id,
+            name: value.name.trim().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateCounterpartyBody {
+    #[schema(example = "Minh")]
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+}
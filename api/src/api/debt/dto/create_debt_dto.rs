@@ -0,0 +1,63 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::debt::DebtKind;
+
+pub struct CreateDebtData {
+    pub user_id: ObjectId,
+    pub counterparty_id: ObjectId,
+    pub transaction_id: Option<ObjectId>,
+    pub kind: DebtKind,
+    pub amount: f64,
+    pub currency: String,
+    pub note: Option<String>,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub struct CreateDebtInput {
+    pub user_id: ObjectId,
+    pub counterparty_id: ObjectId,
+    pub transaction_id: Option<ObjectId>,
+    pub kind: DebtKind,
+    pub amount: f64,
+    pub currency: String,
+    pub note: Option<String>,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&CreateDebtInput> for CreateDebtData {
+    fn from(value: &CreateDebtInput) -> Self {
+        Self {
+            user_id: value.user_id,
+            counterparty_id: value.counterparty_id,
+            transaction_id: value.transaction_id,
+            kind: value.kind,
+            amount: value.amount,
+            currency: value.currency.to_uppercase(),
+            note: value.note.clone(),
+            issued_at: value.issued_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDebtBody {
+    #[schema(example = "66a2c1d0ce6a5cbb87195c20")]
+    pub counterparty_id: String,
+    #[schema(example = "repaid_to_me")]
+    pub kind: DebtKind,
+    #[schema(example = 200000.0)]
+    #[validate(range(exclusive_min = 0.0))]
+    pub amount: f64,
+    #[schema(example = "VND")]
+    #[validate(length(equal = 3))]
+    pub currency: String,
+    #[schema(example = "Paid back in cash")]
+    #[validate(length(max = 256))]
+    pub note: Option<String>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub issued_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(rename_all = "camelCase")]
+pub struct ListDebtsQuery {
+    /// Entries with this counterparty only
+    pub counterparty_id: Option<String>,
+}
@@ -0,0 +1,7 @@
+mod create_counterparty_dto;
+mod create_debt_dto;
+mod list_debts_dto;
+
+pub use create_counterparty_dto::*;
+pub use create_debt_dto::*;
+pub use list_debts_dto::*;
@@ -0,0 +1,20 @@
+pub(crate) use constants::*;
+pub(crate) use debt_balance::*;
+#[allow(unused_imports)]
+pub use debt_controller::DebtApiDoc;
+pub(crate) use debt_entity::*;
+pub use debt_model::*;
+pub(crate) use debt_repo::*;
+pub use debt_router::*;
+pub use debt_service::*;
+pub(crate) use dto::*;
+
+mod constants;
+mod debt_balance;
+mod debt_controller;
+mod debt_entity;
+mod debt_model;
+mod debt_repo;
+mod debt_router;
+mod debt_service;
+mod dto;
@@ -0,0 +1,10 @@
+use bson::oid::ObjectId;
+
+use crate::api::infer::models::InvoiceTool;
+use crate::api::invoice::Media;
+
+pub struct FindDuplicatesInput {
+    pub user_id: ObjectId,
+    pub invoice_tool: InvoiceTool,
+    pub media: Vec<Media>,
+}
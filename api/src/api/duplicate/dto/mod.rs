@@ -0,0 +1,3 @@
+mod find_duplicates_dto;
+
+pub use find_duplicates_dto::*;
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+// amounts read by OCR or typed by hand may be rounded differently
+const AMOUNT_TOLERANCE: f64 = 0.01;
+const MIN_TITLE_SIMILARITY: f64 = 0.5;
+
+/// Number of differing bits between two perceptual hashes, written as hex.
+pub fn hash_distance(a: &str, b: &str) -> Option<u32> {
+    let a = u64::from_str_radix(a, 16).ok()?;
+    let b = u64::from_str_radix(b, 16).ok()?;
+
+    Some((a ^ b).count_ones())
+}
+
+/// Identifies an invoice by what is printed on it, e.g. `VND|8000000|2024-07-22|8432`.
+/// Invoices without a total are not fingerprinted.
+pub fn invoice_fingerprint(
+    total: f64,
+    currency: &str,
+    issued_at: chrono::DateTime<chrono::Utc>,
+    card_number: Option<i16>,
+) -> Option<String> {
+    if total <= 0.0 {
+        return None;
+    }
+
+    Some(format!(
+        "{}|{}|{}|{}",
+        currency.to_uppercase(),
+        (total * 100.0).round() as i64,
+        issued_at.format("%Y-%m-%d"),
+        card_number.map_or("-".to_string(), |card| card.to_string())
+    ))
+}
+
+pub fn is_same_amount(a: f64, b: f64) -> bool {
+    (a - b).abs() <= a.abs().max(b.abs()) * AMOUNT_TOLERANCE
+}
+
+/// Share of words two titles have in common, so "Lunch with Nam" matches "lunch".
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let words = |text: &str| {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_string)
+            .collect::<HashSet<_>>()
+    };
+    let (a, b) = (words(a), words(b));
+    let smaller = a.len().min(b.len());
+    if smaller == 0 {
+        return 0.0;
+    }
+
+    a.intersection(&b).count() as f64 / smaller as f64
+}
+
+/// The fields two expenses are compared on.
+pub struct Expense<'a> {
+    pub title: &'a str,
+    pub amount: f64,
+    pub currency: &'a str,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Same amount and currency, and either a similar title or the same day.
+pub fn is_similar_expense(a: &Expense, b: &Expense) -> bool {
+    if !a.currency.eq_ignore_ascii_case(b.currency) || !is_same_amount(a.amount, b.amount) {
+        return false;
+    }
+
+    title_similarity(a.title, b.title) >= MIN_TITLE_SIMILARITY
+        || a.issued_at.date_naive() == b.issued_at.date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_hash_distance() {
+        assert_eq!(
+            hash_distance("f0f0f0f0f0f0f0f0", "f0f0f0f0f0f0f0f1"),
+            Some(1)
+        );
+        assert_eq!(hash_distance("0", "ffffffffffffffff"), Some(64));
+        assert_eq!(hash_distance("zz", "0"), None);
+    }
+
+    #[test]
+    fn test_invoice_fingerprint() {
+        let issued_at = chrono::Utc
+            .with_ymd_and_hms(2024, 7, 22, 13, 30, 0)
+            .unwrap();
+
+        assert_eq!(
+            invoice_fingerprint(80000.0, "vnd", issued_at, Some(8432)).as_deref(),
+            Some("VND|8000000|2024-07-22|8432")
+        );
+        assert_eq!(
+            invoice_fingerprint(12.5, "USD", issued_at, None).as_deref(),
+            Some("USD|1250|2024-07-22|-")
+        );
+        assert_eq!(invoice_fingerprint(0.0, "USD", issued_at, None), None);
+    }
+
+    #[test]
+    fn test_is_similar_expense() {
+        let monday = chrono::Utc.with_ymd_and_hms(2024, 7, 22, 12, 0, 0).unwrap();
+        let friday = chrono::Utc.with_ymd_and_hms(2024, 7, 26, 12, 0, 0).unwrap();
+        let expense = |title, amount, currency, issued_at| Expense {
+            title,
+            amount,
+            currency,
+            issued_at,
+        };
+
+        assert!(is_similar_expense(
+            &expense("Lunch with Nam", 80000.0, "VND", monday),
+            &expense("lunch", 80000.0, "VND", friday),
+        ));
+        assert!(is_similar_expense(
+            &expense("Pho bo", 80000.0, "VND", monday),
+            &expense("Lunch", 80500.0, "VND", monday),
+        ));
+        assert!(!is_similar_expense(
+            &expense("Pho bo", 80000.0, "VND", monday),
+            &expense("Lunch", 80000.0, "VND", friday),
+        ));
+        assert!(!is_similar_expense(
+            &expense("Lunch", 80000.0, "VND", monday),
+            &expense("Lunch", 95000.0, "VND", monday),
+        ));
+        assert!(!is_similar_expense(
+            &expense("Lunch", 5.0, "USD", monday),
+            &expense("Lunch", 5.0, "EUR", monday),
+        ));
+    }
+}
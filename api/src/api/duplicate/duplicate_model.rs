@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateReason {
+    // a photo looks like one already uploaded
+    Image,
+    // same total, currency, date and card as an earlier invoice
+    Invoice,
+    // an expense with the same amount was already logged
+    Transaction,
+}
+
+/// An earlier message the new one likely repeats. It is only a warning, the new
+/// message is saved anyway.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Duplicate {
+    #[schema(example = "image")]
+    pub reason: DuplicateReason,
+    #[schema(example = "669e5f02b781150b9a578205")]
+    pub message_id: String,
+    #[schema(example = "669e5f02b781150b9a578206")]
+    pub invoice_id: Option<String>,
+    #[schema(example = "669fb456ce6a5cbb87195a60")]
+    pub transaction_id: Option<String>,
+}
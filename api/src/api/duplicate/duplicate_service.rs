@@ -0,0 +1,148 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::api::duplicate::*;
+use crate::api::invoice::InvoiceServiceDyn;
+use crate::api::transaction::{SearchTransactionsInput, TransactionServiceDyn};
+use crate::common::errors::AppError;
+use crate::common::mongo::FindOptions;
+use crate::settings::DuplicateConfig;
+
+#[async_trait]
+pub trait DuplicateServiceExt: Send + Sync {
+    /// Finds earlier messages the new invoice likely repeats, by photo, by invoice
+    /// fingerprint and by similar transactions of the last days.
+    async fn find(&self, input: &FindDuplicatesInput) -> Result<Vec<Duplicate>, AppError>;
+}
+
+pub type DuplicateServiceDyn = Arc<dyn DuplicateServiceExt + Send + Sync>;
+
+pub struct DuplicateService {
+    pub invoice_service: InvoiceServiceDyn,
+    pub transaction_service: TransactionServiceDyn,
+    pub config: DuplicateConfig,
+}
+
+impl DuplicateService {
+    // transactions compared in the window, the most recent first
+    const MAX_TRANSACTIONS: i64 = 500;
+}
+
+#[async_trait]
+impl DuplicateServiceExt for DuplicateService {
+    async fn find(&self, input: &FindDuplicatesInput) -> Result<Vec<Duplicate>, AppError> {
+        let invoice_tool = &input.invoice_tool;
+        let since = chrono::Utc::now() - chrono::Duration::days(self.config.window_days);
+        let fingerprint = invoice_fingerprint(
+            invoice_tool.total,
+            &invoice_tool.currency,
+            invoice_tool.issued_at,
+            invoice_tool.card_number,
+        );
+
+        let (recent_invoices, same_invoices, transactions) = tokio::try_join!(
+            async {
+                if input.media.iter().all(|media| media.hash.is_none()) {
+                    return Ok(vec![]);
+                }
+                self.invoice_service.find_recent(input.user_id, since).await
+            },
+            async {
+                match &fingerprint {
+                    Some(fingerprint) => {
+                        self.invoice_service
+                            .find_by_fingerprint(input.user_id, fingerprint)
+                            .await
+                    }
+                    None => Ok(vec![]),
+                }
+            },
+            self.transaction_service.search(
+                SearchTransactionsInput {
+                    user_id: input.user_id,
+                    keyword: None,
+                    category_id: None,
+                    r#type: None,
+                    from: Some(since),
+                    to: None,
+                },
+                FindOptions::with_limit(Self::MAX_TRANSACTIONS),
+            ),
+        )?;
+
+        let mut duplicates: Vec<Duplicate> = vec![];
+
+        let hashes = input
+            .media
+            .iter()
+            .filter_map(|media| media.hash.as_deref())
+            .collect::<Vec<_>>();
+        for invoice in recent_invoices {
+            let is_same_photo = invoice
+                .media
+                .iter()
+                .filter_map(|media| media.hash.as_deref())
+                .any(|hash| {
+                    hashes.iter().any(|other| {
+                        hash_distance(hash, other)
+                            .is_some_and(|distance| distance <= self.config.max_image_distance)
+                    })
+                });
+            if is_same_photo {
+                duplicates.push(Duplicate {
+                    reason: DuplicateReason::Image,
+                    message_id: invoice.message_id,
+                    invoice_id: Some(invoice.id),
+                    transaction_id: None,
+                });
+            }
+        }
+
+        for invoice in same_invoices {
+            if duplicates
+                .iter()
+                .any(|d| d.message_id == invoice.message_id)
+            {
+                continue;
+            }
+            duplicates.push(Duplicate {
+                reason: DuplicateReason::Invoice,
+                message_id: invoice.message_id,
+                invoice_id: Some(invoice.id),
+                transaction_id: None,
+            });
+        }
+
+        for tx in &invoice_tool.transactions {
+            let expense = Expense {
+                title: &tx.title,
+                amount: tx.amount,
+                currency: &tx.currency,
+                issued_at: tx.issued_at,
+            };
+            let similar = transactions.iter().find(|other| {
+                !duplicates.iter().any(|d| d.message_id == other.message_id)
+                    && is_similar_expense(
+                        &expense,
+                        &Expense {
+                            title: &other.title,
+                            amount: other.amount,
+                            currency: &other.currency,
+                            issued_at: other.issued_at,
+                        },
+                    )
+            });
+            if let Some(other) = similar {
+                duplicates.push(Duplicate {
+                    reason: DuplicateReason::Transaction,
+                    message_id: other.message_id.clone(),
+                    invoice_id: None,
+                    transaction_id: Some(other.id.clone()),
+                });
+            }
+        }
+
+        Ok(duplicates)
+    }
+}
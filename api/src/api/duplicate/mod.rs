@@ -0,0 +1,9 @@
+pub(crate) use dto::*;
+pub(crate) use duplicate_match::*;
+pub use duplicate_model::*;
+pub use duplicate_service::*;
+
+mod dto;
+mod duplicate_match;
+mod duplicate_model;
+mod duplicate_service;
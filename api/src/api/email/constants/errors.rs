@@ -0,0 +1,40 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+use crate::common::errors::ErrorResponse;
+
+#[derive(Error, Debug)]
+pub enum EmailError {
+    #[error("invalid inbound secret")]
+    Unauthorized,
+    #[error("unknown recipient")]
+    UnknownRecipient,
+    #[error("unknown sender")]
+    UnknownSender,
+    #[error("invalid email: {0}")]
+    InvalidMessage(String),
+    #[error("email has no receipt")]
+    NoContent,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for EmailError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()),
+            Self::UnknownRecipient => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::UnknownSender => (StatusCode::FORBIDDEN, self.to_string()),
+            Self::InvalidMessage(_) | Self::NoContent => {
+                (StatusCode::UNPROCESSABLE_ENTITY, self.to_string())
+            }
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InboundAddressPayload {
+    /// Receipts forwarded to this address are added to the account
+    #[schema(example = "k3v9x0q2m7d1a8zt@in.whatsexpense.app")]
+    pub address: String,
+}
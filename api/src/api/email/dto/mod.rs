@@ -0,0 +1,3 @@
+mod inbound_email_dto;
+
+pub use inbound_email_dto::*;
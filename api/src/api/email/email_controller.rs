@@ -0,0 +1,124 @@
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::{Extension, Json};
+use utoipa::OpenApi;
+
+use crate::api::email::{parse_email, EmailError, InboundAddressPayload, EML_CONTENT_TYPE};
+use crate::api::invoice::{ImageFile, UploadImageInput};
+use crate::api::job::{CreateJobInput, Job, JobPayload};
+use crate::api::state::AppState;
+use crate::api::workspace::Scope;
+use crate::common::errors::AppError;
+use crate::common::secret::secret_matches;
+use crate::object_id;
+
+pub const INBOUND_SECRET_HEADER: &str = "x-inbound-secret";
+/// Envelope sender the provider authenticated with SPF or DKIM. The From header of the
+/// message is written by the sender, it tells nothing about who sent it.
+pub const INBOUND_SENDER_HEADER: &str = "x-inbound-sender";
+
+#[utoipa::path(
+    post,
+    path = "/inbound",
+    request_body(content = String, content_type = "message/rfc822", description = "Raw RFC 822 message"),
+    responses(
+        (status = 202, description = "Email accepted, the receipt is processed in the background", body = Job),
+        (status = 403, description = "The sender is not the owner of the inbound address"),
+        (status = 404, description = "No user owns the inbound address"),
+    ),
+    params(
+        ("x-inbound-secret" = String, Header, description = "Secret shared with the mail provider"),
+        ("x-inbound-sender" = String, Header, description = "Envelope sender authenticated by the mail provider"),
+    )
+)]
+pub async fn inbound_email(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Job>), AppError> {
+    let config = &state.settings.email;
+    let secret = headers
+        .get(INBOUND_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !secret_matches(secret, &config.inbound_secret) {
+        return Err(EmailError::Unauthorized.into());
+    }
+    let sender = headers
+        .get(INBOUND_SENDER_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().trim_start_matches('<').trim_end_matches('>'))
+        .ok_or(EmailError::UnknownSender)?;
+
+    let email = parse_email(&body)?;
+    let token = email
+        .inbound_token(&config.domain)
+        .ok_or(EmailError::UnknownRecipient)?;
+    let user = state
+        .user_service
+        .find_by_inbound_token(&token)
+        .await?
+        .ok_or(EmailError::UnknownRecipient)?;
+
+    // only the owner can forward receipts, anyone else guessing the address is dropped
+    if !sender.eq_ignore_ascii_case(&user.email) {
+        return Err(EmailError::UnknownSender.into());
+    }
+
+    let user_id = object_id!(&user.id);
+    let media = state
+        .invoice_service
+        .store_images(UploadImageInput {
+            owner_id: user_id,
+            files: vec![ImageFile {
+                content: body.to_vec(),
+                content_type: EML_CONTENT_TYPE.to_string(),
+            }],
+        })
+        .await?
+        .remove(0);
+
+    let job = state
+        .job_service
+        .enqueue(CreateJobInput {
+            user_id,
+            workspace_id: None,
+            payload: JobPayload::ProcessEmail { media },
+        })
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/address",
+    responses(
+        (status = 200, description = "Get inbound address successfully", body = InboundAddressPayload),
+    )
+)]
+pub async fn get_inbound_address(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+) -> Result<Json<InboundAddressPayload>, AppError> {
+    // forwarded receipts land in the ledger of the user
+    let token = state
+        .user_service
+        .get_inbound_token(scope.personal_owner()?)
+        .await?;
+
+    Ok(Json(InboundAddressPayload {
+        address: format!("{}@{}", token, state.settings.email.domain),
+    }))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(inbound_email, get_inbound_address),
+    components(schemas(InboundAddressPayload, Job)),
+    tags(
+        (name = "crate::api::email", description = "Email API")
+    )
+)]
+pub struct EmailApiDoc;
@@ -0,0 +1,301 @@
+use mailparse::{addrparse, parse_mail, DispositionType, MailAddr, MailHeaderMap, ParsedMail};
+
+use crate::api::email::EmailError;
+use crate::api::invoice::ImageFile;
+use crate::services::pdf::PDF_CONTENT_TYPE;
+
+// headers the recipient token is read from, the envelope ones come first
+const RECIPIENT_HEADERS: [&str; 4] = ["Delivered-To", "X-Original-To", "To", "Cc"];
+
+pub struct ParsedEmail {
+    pub recipients: Vec<String>,
+    pub subject: Option<String>,
+    pub text: String,
+    pub attachments: Vec<ImageFile>,
+}
+
+impl ParsedEmail {
+    /// Local part of the first recipient at the inbound domain, `+tags` are ignored.
+    pub fn inbound_token(&self, domain: &str) -> Option<String> {
+        self.recipients.iter().find_map(|address| {
+            let (local, host) = address.rsplit_once('@')?;
+            if !host.eq_ignore_ascii_case(domain) {
+                return None;
+            }
+            let token = local.split('+').next().unwrap_or(local);
+            (!token.is_empty()).then(|| token.to_lowercase())
+        })
+    }
+}
+
+/// Reads the addresses, the body and the receipt attachments of a raw RFC 822 message.
+/// Messages forwarded as attachments are read as part of the outer one.
+pub fn parse_email(raw: &[u8]) -> Result<ParsedEmail, EmailError> {
+    let mail = parse_mail(raw).map_err(|e| EmailError::InvalidMessage(e.to_string()))?;
+
+    let recipients = read_addresses(&mail, &RECIPIENT_HEADERS);
+    let subject = mail.headers.get_first_value("Subject");
+
+    let mut body = Body::default();
+    body.walk(&mail)?;
+
+    let text = if body.plain.iter().any(|text| !text.trim().is_empty()) {
+        body.plain.join("\n\n")
+    } else {
+        body.html
+            .iter()
+            .map(|html| html_to_text(html))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+
+    Ok(ParsedEmail {
+        recipients,
+        subject,
+        text: text.trim().to_string(),
+        attachments: body.attachments,
+    })
+}
+
+fn read_addresses(mail: &ParsedMail, headers: &[&str]) -> Vec<String> {
+    headers
+        .iter()
+        .flat_map(|header| mail.headers.get_all_values(header))
+        .filter_map(|value| addrparse(&value).ok())
+        .flat_map(|list| list.into_inner())
+        .flat_map(|address| match address {
+            MailAddr::Single(info) => vec![info.addr],
+            MailAddr::Group(group) => group.addrs.into_iter().map(|info| info.addr).collect(),
+        })
+        .map(|address| address.to_lowercase())
+        .collect()
+}
+
+#[derive(Default)]
+struct Body {
+    plain: Vec<String>,
+    html: Vec<String>,
+    attachments: Vec<ImageFile>,
+}
+
+impl Body {
+    fn walk(&mut self, part: &ParsedMail) -> Result<(), EmailError> {
+        let mimetype = part.ctype.mimetype.to_lowercase();
+
+        if !part.subparts.is_empty() {
+            for subpart in &part.subparts {
+                self.walk(subpart)?;
+            }
+            return Ok(());
+        }
+
+        if mimetype == "message/rfc822" {
+            let raw = part
+                .get_body_raw()
+                .map_err(|e| EmailError::InvalidMessage(e.to_string()))?;
+            let inner = parse_mail(&raw).map_err(|e| EmailError::InvalidMessage(e.to_string()))?;
+            return self.walk(&inner);
+        }
+
+        let disposition = part.get_content_disposition();
+        let filename = disposition
+            .params
+            .get("filename")
+            .or(part.ctype.params.get("name"))
+            .map(|name| name.to_lowercase())
+            .unwrap_or_default();
+
+        let content_type = if mimetype == "application/octet-stream" && filename.ends_with(".pdf") {
+            PDF_CONTENT_TYPE.to_string()
+        } else {
+            mimetype
+        };
+
+        if content_type.starts_with("image/") || content_type == PDF_CONTENT_TYPE {
+            // inline images of an html body are logos and tracking pixels
+            if disposition.disposition == DispositionType::Inline && filename.is_empty() {
+                return Ok(());
+            }
+            let content = part
+                .get_body_raw()
+                .map_err(|e| EmailError::InvalidMessage(e.to_string()))?;
+            self.attachments.push(ImageFile {
+                content,
+                content_type,
+            });
+            return Ok(());
+        }
+
+        if disposition.disposition == DispositionType::Attachment {
+            return Ok(());
+        }
+
+        let text = part
+            .get_body()
+            .map_err(|e| EmailError::InvalidMessage(e.to_string()))?;
+        match content_type.as_str() {
+            "text/plain" => self.plain.push(text),
+            "text/html" => self.html.push(text),
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Keeps the readable text of an html email. Table cells stay on one line so item names
+/// and prices of an order line up like on a printed receipt.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|end| &rest[end + 3..]).unwrap_or("");
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[1..end].trim().to_lowercase();
+        rest = &rest[end + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        match name {
+            "script" | "style" | "head" | "title" if !tag.starts_with('/') => {
+                let close = format!("</{name}");
+                rest = match rest.to_ascii_lowercase().find(&close) {
+                    Some(index) => rest[index..]
+                        .find('>')
+                        .map_or("", |end| &rest[index + end + 1..]),
+                    None => "",
+                };
+            }
+            "br" | "p" | "div" | "tr" | "li" | "h1" | "h2" | "h3" | "h4" | "table" => {
+                text.push('\n')
+            }
+            "td" | "th" => text.push(' '),
+            _ => {}
+        }
+    }
+    text.push_str(rest);
+
+    decode_entities(&text)
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end]);
+        let char = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => {
+                let code = entity.strip_prefix('#')?;
+                let code = match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+
+        match (entity, char) {
+            (Some(entity), Some(char)) => {
+                decoded.push(char);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_text() {
+        let html = r#"<html><head><style>td { color: red; }</style></head><body>
+            <p>Thanks for your order!</p>
+            <table><tr><td>Coffee&nbsp;beans</td><td>$12.00</td></tr>
+            <tr><td>Tea &amp; honey</td><td>&#36;5.50</td></tr></table>
+            <!-- tracking --><img src="pixel.gif"></body></html>"#;
+
+        assert_eq!(
+            html_to_text(html),
+            "Thanks for your order!\nCoffee beans $12.00\nTea & honey $5.50"
+        );
+    }
+
+    #[test]
+    fn test_parse_email() {
+        let raw = concat!(
+            "From: Alice <Alice@Example.com>\r\n",
+            "To: k3v9x0q2m7d1a8zt+orders@in.example.app\r\n",
+            "Subject: Fwd: Your order\r\n",
+            "Content-Type: multipart/mixed; boundary=\"outer\"\r\n",
+            "\r\n",
+            "--outer\r\n",
+            "Content-Type: multipart/alternative; boundary=\"inner\"\r\n",
+            "\r\n",
+            "--inner\r\n",
+            "Content-Type: text/html; charset=utf-8\r\n",
+            "\r\n",
+            "<p>Total</p><p>$17.50</p>\r\n",
+            "--inner--\r\n",
+            "--outer\r\n",
+            "Content-Type: application/octet-stream; name=\"Receipt.PDF\"\r\n",
+            "Content-Disposition: attachment; filename=\"Receipt.PDF\"\r\n",
+            "Content-Transfer-Encoding: base64\r\n",
+            "\r\n",
+            "JVBERi0=\r\n",
+            "--outer--\r\n",
+        );
+
+        let email = parse_email(raw.as_bytes()).unwrap();
+
+        assert_eq!(
+            email.inbound_token("in.example.app").as_deref(),
+            Some("k3v9x0q2m7d1a8zt")
+        );
+        assert_eq!(email.inbound_token("example.app"), None);
+        assert_eq!(email.subject.as_deref(), Some("Fwd: Your order"));
+        assert_eq!(email.text, "Total\n$17.50");
+        assert_eq!(email.attachments.len(), 1);
+        assert_eq!(email.attachments[0].content_type, PDF_CONTENT_TYPE);
+        assert_eq!(email.attachments[0].content, b"%PDF-");
+    }
+}
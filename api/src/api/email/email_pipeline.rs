@@ -0,0 +1,109 @@
+use crate::api::email::{parse_email, EmailError, ParsedEmail};
+use crate::api::invoice::{
+    create_invoice_message, redact_text, ImageFile, Media, UploadImageInput, UploadedImage,
+};
+use crate::api::message::Message;
+use crate::api::state::AppState;
+use crate::api::user::User;
+use crate::common::errors::AppError;
+use crate::object_id;
+use crate::services::pdf::PDF_CONTENT_TYPE;
+
+pub const EML_CONTENT_TYPE: &str = "message/rfc822";
+
+// same limit as the photos of one upload
+const MAX_EMAIL_ATTACHMENTS: usize = 3;
+// order confirmations carry long footers, the receipt is near the top
+const MAX_BODY_CHARS: usize = 8000;
+
+/// Extracts the invoice from a stored email. Receipt attachments are read like uploaded
+/// photos, and the body, e.g. an html order confirmation, is added to their text.
+pub async fn create_email_message(
+    state: &AppState,
+    user: User,
+    media: Media,
+) -> Result<Vec<Message>, AppError> {
+    let raw = state.invoice_service.read_media(&media).await?;
+    let email = parse_email(&raw)?;
+    let body = email_body(&email);
+
+    let files = select_attachments(email.attachments);
+    let mut image = if files.is_empty() {
+        UploadedImage {
+            media: vec![],
+            content: String::new(),
+            images: vec![],
+        }
+    } else {
+        state
+            .invoice_service
+            .upload_images(UploadImageInput {
+                owner_id: object_id!(&user.id),
+                files,
+            })
+            .await?
+    };
+
+    if body.is_empty() && image.content.is_empty() && image.images.is_empty() {
+        return Err(EmailError::NoContent.into());
+    }
+
+    image.content = [body, image.content]
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    image.media.push(media);
+
+    // inbound emails always go to the ledger of the user
+    let user_id = object_id!(&user.id);
+    create_invoice_message(state, user, user_id, image, None).await
+}
+
+/// The text of the email sent to the model, with the sensitive data masked like the text
+/// of the photos.
+pub fn email_body(email: &ParsedEmail) -> String {
+    if email.text.is_empty() {
+        return String::new();
+    }
+
+    let text = email.text.chars().take(MAX_BODY_CHARS).collect::<String>();
+    let body = match &email.subject {
+        Some(subject) => format!("{subject}\n{text}"),
+        None => text,
+    };
+
+    redact_text(&body)
+}
+
+/// A pdf invoice is read on its own, otherwise the first photos are kept.
+fn select_attachments(attachments: Vec<ImageFile>) -> Vec<ImageFile> {
+    let (pdfs, images): (Vec<_>, Vec<_>) = attachments
+        .into_iter()
+        .partition(|file| file.content_type == PDF_CONTENT_TYPE);
+
+    match pdfs.into_iter().next() {
+        Some(pdf) => vec![pdf],
+        None => images.into_iter().take(MAX_EMAIL_ATTACHMENTS).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_email_body() {
+        let email = ParsedEmail {
+            recipients: vec![],
+            subject: Some("Your order".to_string()),
+            text: "Billed to alice@example.com\nTotal $17.50".to_string(),
+            attachments: vec![],
+        };
+
+        assert_eq!(
+            email_body(&email),
+            "Your order\nBilled to [email]\nTotal $17.50"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use axum::extract::DefaultBodyLimit;
+use axum::middleware::from_fn_with_state;
+use axum::routing::{get, post};
+use axum::Router;
+
+use crate::api::email::email_controller::*;
+use crate::api::state::AppState;
+use crate::mw::authorization_mw;
+
+// 10MB, room for a few attachments encoded in base64
+const MAX_EMAIL_SIZE: usize = 10 * 1024 * 1024;
+
+pub struct EmailRouter(Router<AppState>);
+
+impl EmailRouter {
+    pub fn new(state: AppState) -> Self {
+        let routes = Router::new()
+            .route("/address", get(get_inbound_address))
+            .route_layer(from_fn_with_state(state.clone(), authorization_mw))
+            // called by the mail provider, checked with the inbound secret instead
+            .route(
+                "/inbound",
+                post(inbound_email).layer(DefaultBodyLimit::max(MAX_EMAIL_SIZE)),
+            );
+
+        Self(routes)
+    }
+}
+
+impl From<EmailRouter> for Router<AppState> {
+    fn from(router: EmailRouter) -> Self {
+        router.0
+    }
+}
@@ -0,0 +1,14 @@
+pub(crate) use constants::*;
+pub(crate) use dto::*;
+#[allow(unused_imports)]
+pub use email_controller::EmailApiDoc;
+pub(crate) use email_parser::*;
+pub(crate) use email_pipeline::*;
+pub use email_router::*;
+
+mod constants;
+mod dto;
+mod email_controller;
+mod email_parser;
+mod email_pipeline;
+mod email_router;
@@ -0,0 +1,44 @@
+use crate::common::errors::ErrorResponse;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GroupError {
+    #[error("group not found")]
+    NotFound,
+    #[error("split not found")]
+    SplitNotFound,
+    #[error("no user with email {0}")]
+    UserNotFound(String),
+    #[error("{0} is already a member of the group")]
+    AlreadyMember(String),
+    #[error("{0} is not a member of the group")]
+    NotAMember(String),
+    #[error("the receipt is already split")]
+    AlreadySplit,
+    #[error("invalid split: {0}")]
+    InvalidSplit(String),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for GroupError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::SplitNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::UserNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::AlreadyMember(_) => (StatusCode::CONFLICT, self.to_string()),
+            Self::NotAMember(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::AlreadySplit => (StatusCode::CONFLICT, self.to_string()),
+            Self::InvalidSplit(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
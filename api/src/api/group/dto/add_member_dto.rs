@@ -0,0 +1,18 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub struct AddMemberInput {
+    pub group_id: ObjectId,
+    pub user_id: ObjectId,
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct AddMemberBody {
+    #[schema(example = "lan@test.com")]
+    #[validate(length(min = 1))]
+    pub email: String,
+}
@@ -0,0 +1,34 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::group::GroupMemberEntity;
+
+pub struct CreateGroupData {
+    pub owner_id: ObjectId,
+    pub name: String,
+    pub members: Vec<GroupMemberEntity>,
+}
+
+pub struct CreateGroupInput {
+    pub owner_id: ObjectId,
+    pub owner_name: String,
+    pub name: String,
+    pub emails: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGroupBody {
+    #[schema(example = "Roommates")]
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    /// Emails of the other members, the user creating the group is always a member
+    #[schema(example = json!(["minh@test.com"]))]
+    #[serde(default)]
+    #[validate(length(max = 20))]
+    pub emails: Vec<String>,
+}
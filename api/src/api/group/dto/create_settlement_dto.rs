@@ -0,0 +1,51 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub struct CreateSettlementData {
+    pub group_id: ObjectId,
+    pub from_user_id: ObjectId,
+    pub to_user_id: ObjectId,
+    pub amount: f64,
+    pub currency: String,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub struct CreateSettlementInput {
+    pub group_id: ObjectId,
+    pub from_user_id: ObjectId,
+    pub to_user_id: ObjectId,
+    pub amount: f64,
+    pub currency: String,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<CreateSettlementInput> for CreateSettlementData {
+    fn from(value: CreateSettlementInput) -> Self {
+        Self {
+            group_id: value.group_id,
+            from_user_id: value.from_user_id,
+            to_user_id: value.to_user_id,
+            amount: value.amount,
+            currency: value.currency.to_uppercase(),
+            issued_at: value.issued_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSettlementBody {
+    /// Member the user paid back
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub to_user_id: String,
+    #[schema(example = 300000.0)]
+    #[validate(range(exclusive_min = 0.0))]
+    pub amount: f64,
+    #[schema(example = "VND")]
+    #[validate(length(equal = 3))]
+    pub currency: String,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub issued_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -0,0 +1,84 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::group::{AllocationEntity, ShareEntity, SplitMethod};
+
+pub struct CreateSplitData {
+    pub group_id: ObjectId,
+    pub invoice_id: ObjectId,
+    pub paid_by: ObjectId,
+    pub title: String,
+    pub method: SplitMethod,
+    pub total: f64,
+    pub currency: String,
+    pub shares: Vec<ShareEntity>,
+    pub allocations: Vec<AllocationEntity>,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub struct CreateSplitInput {
+    pub group_id: ObjectId,
+    pub user_id: ObjectId,
+    pub invoice_id: ObjectId,
+    pub method: SplitMethod,
+    pub members: Vec<SplitMemberInput>,
+    pub items: Vec<SplitItemInput>,
+}
+
+pub struct SplitMemberInput {
+    pub user_id: ObjectId,
+    pub weight: Option<f64>,
+    pub amount: Option<f64>,
+}
+
+pub struct SplitItemInput {
+    pub transaction_id: ObjectId,
+    pub user_ids: Vec<ObjectId>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSplitBody {
+    /// Receipt of the user to share, its expenses are split
+    #[schema(example = "669e5f02b781150b9a578205")]
+    pub invoice_id: String,
+    #[schema(example = "shares")]
+    pub method: SplitMethod,
+    /// Members sharing the bill, every member of the group when empty
+    #[serde(default)]
+    #[validate(nested)]
+    pub members: Vec<SplitMemberBody>,
+    /// Who had each line of the receipt, with the items method
+    #[serde(default)]
+    #[validate(nested)]
+    pub items: Vec<SplitItemBody>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitMemberBody {
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    /// Share of the member, with the shares method
+    #[schema(example = 2.0)]
+    #[validate(range(exclusive_min = 0.0))]
+    pub weight: Option<f64>,
+    /// Amount the member pays, with the exact method
+    #[schema(example = 300000.0)]
+    #[validate(range(min = 0.0))]
+    pub amount: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitItemBody {
+    #[schema(example = "669fb456ce6a5cbb87195a60")]
+    pub transaction_id: String,
+    #[schema(example = json!(["66990b1947d76ec3781adc9d"]))]
+    #[validate(length(min = 1))]
+    pub user_ids: Vec<String>,
+}
@@ -0,0 +1,9 @@
+mod add_member_dto;
+mod create_group_dto;
+mod create_settlement_dto;
+mod create_split_dto;
+
+pub use add_member_dto::*;
+pub use create_group_dto::*;
+pub use create_settlement_dto::*;
+pub use create_split_dto::*;
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use bson::oid::ObjectId;
+
+use crate::api::group::{
+    GroupBalance, GroupEntity, MemberBalance, Payment, SettlementEntity, SplitEntity,
+};
+
+// below a cent, the member is settled
+const SETTLED: f64 = 0.005;
+
+/// What the group owes each member, or each member owes the group, per currency. Fully
+/// settled currencies are left out.
+pub fn group_balances(
+    group: &GroupEntity,
+    splits: &[SplitEntity],
+    settlements: &[SettlementEntity],
+) -> Vec<GroupBalance> {
+    let mut amounts: HashMap<&str, HashMap<ObjectId, f64>> = HashMap::new();
+    for split in splits {
+        let amounts = amounts.entry(&split.currency).or_default();
+        *amounts.entry(split.paid_by).or_default() += split.total;
+        for share in &split.shares {
+            *amounts.entry(share.user_id).or_default() -= share.amount;
+        }
+    }
+    for settlement in settlements {
+        let amounts = amounts.entry(&settlement.currency).or_default();
+        *amounts.entry(settlement.from_user_id).or_default() += settlement.amount;
+        *amounts.entry(settlement.to_user_id).or_default() -= settlement.amount;
+    }
+
+    let mut balances = amounts
+        .into_iter()
+        .filter(|(_, amounts)| amounts.values().any(|amount| amount.abs() >= SETTLED))
+        .map(|(currency, amounts)| {
+            let members = group
+                .members
+                .iter()
+                .map(|member| MemberBalance {
+                    user_id: member.user_id.to_hex(),
+                    name: member.name.clone(),
+                    amount: amounts.get(&member.user_id).copied().unwrap_or_default(),
+                })
+                .collect::<Vec<MemberBalance>>();
+
+            GroupBalance {
+                currency: currency.to_string(),
+                settle_up: settle_up(&members),
+                members,
+            }
+        })
+        .collect::<Vec<GroupBalance>>();
+    balances.sort_by(|a, b| a.currency.cmp(&b.currency));
+
+    balances
+}
+
+/// Payments that settle every member. The member owing the most pays the member owed the
+/// most until one of them is settled, which needs fewer payments than members.
+fn settle_up(balances: &[MemberBalance]) -> Vec<Payment> {
+    let mut creditors = balances
+        .iter()
+        .filter(|balance| balance.amount >= SETTLED)
+        .map(|balance| (balance.user_id.clone(), balance.amount))
+        .collect::<Vec<(String, f64)>>();
+    let mut debtors = balances
+        .iter()
+        .filter(|balance| balance.amount <= -SETTLED)
+        .map(|balance| (balance.user_id.clone(), -balance.amount))
+        .collect::<Vec<(String, f64)>>();
+
+    let mut payments = vec![];
+    loop {
+        creditors.sort_by(|a, b| b.1.total_cmp(&a.1));
+        debtors.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let (Some(creditor), Some(debtor)) = (creditors.first_mut(), debtors.first_mut()) else {
+            break;
+        };
+
+        let amount = creditor.1.min(debtor.1);
+        payments.push(Payment {
+            from_user_id: debtor.0.clone(),
+            to_user_id: creditor.0.clone(),
+            amount,
+        });
+        creditor.1 -= amount;
+        debtor.1 -= amount;
+
+        creditors.retain(|(_, amount)| *amount >= SETTLED);
+        debtors.retain(|(_, amount)| *amount >= SETTLED);
+    }
+
+    payments
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::group::{GroupMemberEntity, ShareEntity, SplitMethod};
+
+    use super::*;
+
+    fn group(names: &[&str]) -> GroupEntity {
+        GroupEntity {
+            id: ObjectId::new(),
+            owner_id: ObjectId::new(),
+            name: "Trip".to_string(),
+            members: names
+                .iter()
+                .map(|name| GroupMemberEntity {
+                    user_id: ObjectId::new(),
+                    name: name.to_string(),
+                })
+                .collect(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn split(paid_by: ObjectId, shares: &[(ObjectId, f64)]) -> SplitEntity {
+        SplitEntity {
+            id: ObjectId::new(),
+            group_id: ObjectId::new(),
+            invoice_id: ObjectId::new(),
+            paid_by,
+            title: "Dinner".to_string(),
+            method: SplitMethod::Exact,
+            total: shares.iter().map(|(_, amount)| amount).sum(),
+            currency: "USD".to_string(),
+            shares: shares
+                .iter()
+                .map(|(user_id, amount)| ShareEntity {
+                    user_id: *user_id,
+                    amount: *amount,
+                })
+                .collect(),
+            allocations: vec![],
+            issued_at: chrono::Utc::now(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_group_balances() {
+        let group = group(&["An", "Binh", "Chi"]);
+        let [an, binh, chi] = [0, 1, 2].map(|i| group.members[i].user_id);
+        let splits = vec![
+            split(an, &[(an, 30.0), (binh, 30.0), (chi, 30.0)]),
+            split(binh, &[(an, 10.0), (binh, 10.0)]),
+        ];
+        let settlements = vec![SettlementEntity {
+            id: ObjectId::new(),
+            group_id: group.id,
+            from_user_id: chi,
+            to_user_id: an,
+            amount: 10.0,
+            currency: "USD".to_string(),
+            issued_at: chrono::Utc::now(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }];
+
+        let balances = group_balances(&group, &splits, &settlements);
+
+        let amounts = balances[0]
+            .members
+            .iter()
+            .map(|member| member.amount)
+            .collect::<Vec<f64>>();
+        assert_eq!(amounts, vec![40.0, -20.0, -20.0]);
+        assert_eq!(
+            balances[0].settle_up,
+            vec![
+                Payment {
+                    from_user_id: binh.to_hex(),
+                    to_user_id: an.to_hex(),
+                    amount: 20.0,
+                },
+                Payment {
+                    from_user_id: chi.to_hex(),
+                    to_user_id: an.to_hex(),
+                    amount: 20.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_balances_settled() {
+        let group = group(&["An", "Binh"]);
+        let [an, binh] = [0, 1].map(|i| group.members[i].user_id);
+        let splits = vec![split(an, &[(binh, 25.0)]), split(binh, &[(an, 25.0)])];
+
+        assert!(group_balances(&group, &splits, &[]).is_empty());
+    }
+}
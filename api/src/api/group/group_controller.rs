@@ -0,0 +1,311 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use utoipa::OpenApi;
+
+use crate::api::group::{
+    AddMemberBody, AddMemberInput, Allocation, CreateGroupBody, CreateGroupInput,
+    CreateSettlementBody, CreateSettlementInput, CreateSplitBody, CreateSplitInput, Group,
+    GroupBalance, GroupError, GroupMember, MemberBalance, Payment, Settlement, Share, Split,
+    SplitItemBody, SplitItemInput, SplitMemberBody, SplitMemberInput, SplitMethod,
+};
+use crate::api::state::AppState;
+use crate::api::user::User;
+use crate::api::workspace::Scope;
+use crate::common::errors::AppError;
+use crate::common::hooks::ValidJson;
+use crate::macros::object_id;
+
+#[utoipa::path(
+    get,
+    path = "",
+    responses(
+        (status = 200, description = "List groups of the user successfully", body = [Group]),
+    )
+)]
+pub async fn list_groups(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+) -> Result<Json<Vec<Group>>, AppError> {
+    let groups = state.group_service.list(scope.personal_owner()?).await?;
+
+    Ok(Json(groups))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body = CreateGroupBody,
+    responses(
+        (status = 201, description = "Create group successfully", body = Group),
+        (status = 404, description = "No user has one of the emails"),
+    )
+)]
+pub async fn create_group(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
+    ValidJson(body): ValidJson<CreateGroupBody>,
+) -> Result<(StatusCode, Json<Group>), AppError> {
+    let group = state
+        .group_service
+        .create(CreateGroupInput {
+            owner_id: scope.personal_owner()?,
+            owner_name: user.full_name,
+            name: body.name,
+            emails: body.emails,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{group_id}",
+    responses(
+        (status = 200, description = "Get group successfully", body = Group),
+    ),
+    params(
+        ("group_id" = String, Path, description = "Group database id"),
+    )
+)]
+pub async fn get_group(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(group_id): Path<String>,
+) -> Result<Json<Group>, AppError> {
+    let group = state
+        .group_service
+        .find_by_id(object_id!(&group_id), scope.personal_owner()?)
+        .await?;
+
+    Ok(Json(group))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{group_id}/members",
+    request_body = AddMemberBody,
+    responses(
+        (status = 200, description = "Add member successfully", body = Group),
+        (status = 409, description = "The user is already a member"),
+    ),
+    params(
+        ("group_id" = String, Path, description = "Group database id"),
+    )
+)]
+pub async fn add_member(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(group_id): Path<String>,
+    ValidJson(body): ValidJson<AddMemberBody>,
+) -> Result<Json<Group>, AppError> {
+    let group = state
+        .group_service
+        .add_member(AddMemberInput {
+            group_id: object_id!(&group_id),
+            user_id: scope.personal_owner()?,
+            email: body.email,
+        })
+        .await?;
+
+    Ok(Json(group))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{group_id}/splits",
+    responses(
+        (status = 200, description = "List splits of the group successfully, the latest first", body = [Split]),
+    ),
+    params(
+        ("group_id" = String, Path, description = "Group database id"),
+    )
+)]
+pub async fn list_splits(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(group_id): Path<String>,
+) -> Result<Json<Vec<Split>>, AppError> {
+    let splits = state
+        .group_service
+        .list_splits(object_id!(&group_id), scope.personal_owner()?)
+        .await?;
+
+    Ok(Json(splits))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{group_id}/splits",
+    request_body = CreateSplitBody,
+    responses(
+        (status = 201, description = "Split receipt successfully", body = Split),
+        (status = 409, description = "The receipt is already split"),
+        (status = 422, description = "The split does not add up"),
+    ),
+    params(
+        ("group_id" = String, Path, description = "Group database id"),
+    )
+)]
+pub async fn create_split(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(group_id): Path<String>,
+    ValidJson(body): ValidJson<CreateSplitBody>,
+) -> Result<(StatusCode, Json<Split>), AppError> {
+    let split = state
+        .group_service
+        .create_split(CreateSplitInput {
+            group_id: object_id!(&group_id),
+            user_id: scope.personal_owner()?,
+            invoice_id: object_id!(&body.invoice_id),
+            method: body.method,
+            members: body
+                .members
+                .into_iter()
+                .map(|member| SplitMemberInput {
+                    user_id: object_id!(&member.user_id),
+                    weight: member.weight,
+                    amount: member.amount,
+                })
+                .collect(),
+            items: body
+                .items
+                .into_iter()
+                .map(|item| SplitItemInput {
+                    transaction_id: object_id!(&item.transaction_id),
+                    user_ids: item.user_ids.iter().map(|id| object_id!(id)).collect(),
+                })
+                .collect(),
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(split)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{group_id}/splits/{split_id}",
+    responses(
+        (status = 204, description = "Delete split successfully"),
+    ),
+    params(
+        ("group_id" = String, Path, description = "Group database id"),
+        ("split_id" = String, Path, description = "Split database id"),
+    )
+)]
+pub async fn delete_split(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path((group_id, split_id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    let deleted = state
+        .group_service
+        .delete_split(
+            object_id!(&group_id),
+            object_id!(&split_id),
+            scope.personal_owner()?,
+        )
+        .await?;
+    if !deleted {
+        return Err(GroupError::SplitNotFound.into());
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/{group_id}/balances",
+    responses(
+        (status = 200, description = "Get balances and settle-up plan per currency successfully", body = [GroupBalance]),
+    ),
+    params(
+        ("group_id" = String, Path, description = "Group database id"),
+    )
+)]
+pub async fn get_balances(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(group_id): Path<String>,
+) -> Result<Json<Vec<GroupBalance>>, AppError> {
+    let balances = state
+        .group_service
+        .get_balances(object_id!(&group_id), scope.personal_owner()?)
+        .await?;
+
+    Ok(Json(balances))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{group_id}/settlements",
+    request_body = CreateSettlementBody,
+    responses(
+        (status = 201, description = "Record payment to another member successfully", body = Settlement),
+    ),
+    params(
+        ("group_id" = String, Path, description = "Group database id"),
+    )
+)]
+pub async fn create_settlement(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(group_id): Path<String>,
+    ValidJson(body): ValidJson<CreateSettlementBody>,
+) -> Result<(StatusCode, Json<Settlement>), AppError> {
+    let settlement = state
+        .group_service
+        .create_settlement(CreateSettlementInput {
+            group_id: object_id!(&group_id),
+            from_user_id: scope.personal_owner()?,
+            to_user_id: object_id!(&body.to_user_id),
+            amount: body.amount,
+            currency: body.currency,
+            issued_at: body.issued_at.unwrap_or_else(chrono::Utc::now),
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(settlement)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_groups,
+        create_group,
+        get_group,
+        add_member,
+        list_splits,
+        create_split,
+        delete_split,
+        get_balances,
+        create_settlement,
+    ),
+    components(
+        schemas(
+            Group,
+            GroupMember,
+            Split,
+            SplitMethod,
+            Share,
+            Allocation,
+            Settlement,
+            GroupBalance,
+            MemberBalance,
+            Payment,
+            CreateGroupBody,
+            AddMemberBody,
+            CreateSplitBody,
+            SplitMemberBody,
+            SplitItemBody,
+            CreateSettlementBody,
+        )
+    ),
+    tags(
+        (name = "crate::api::group", description = "Group API")
+    )
+)]
+pub struct GroupApiDoc;
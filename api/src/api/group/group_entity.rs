@@ -0,0 +1,93 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::group::SplitMethod;
+
+/// Users sharing bills, like roommates or friends on a trip.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupEntity {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub owner_id: ObjectId,
+    pub name: String,
+    pub members: Vec<GroupMemberEntity>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl GroupEntity {
+    pub fn member(&self, user_id: ObjectId) -> Option<&GroupMemberEntity> {
+        self.members.iter().find(|member| member.user_id == user_id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMemberEntity {
+    pub user_id: ObjectId,
+    // full name when the member joined
+    pub name: String,
+}
+
+/// A receipt paid by one member and shared with others. The transactions stay with the
+/// member who paid, each member gets a part of every line.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitEntity {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub group_id: ObjectId,
+    pub invoice_id: ObjectId,
+    pub paid_by: ObjectId,
+    pub title: String,
+    pub method: SplitMethod,
+    pub total: f64,
+    pub currency: String,
+    // what each member owes for the bill, the member who paid included
+    pub shares: Vec<ShareEntity>,
+    pub allocations: Vec<AllocationEntity>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareEntity {
+    pub user_id: ObjectId,
+    pub amount: f64,
+}
+
+/// Part of a transaction a member pays for, reports count it in their spending.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocationEntity {
+    pub transaction_id: ObjectId,
+    pub user_id: ObjectId,
+    pub amount: f64,
+}
+
+/// Money a member paid another member back, outside of any bill.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SettlementEntity {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub group_id: ObjectId,
+    pub from_user_id: ObjectId,
+    pub to_user_id: ObjectId,
+    pub amount: f64,
+    pub currency: String,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
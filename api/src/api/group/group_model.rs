@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::api::group::{
+    AllocationEntity, GroupEntity, GroupMemberEntity, SettlementEntity, ShareEntity, SplitEntity,
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SplitMethod {
+    /// Every member pays the same
+    Equal,
+    /// Members pay in proportion to their shares, like 2 for a couple and 1 for a single
+    Shares,
+    /// Members pay the amounts given, which add up to the total
+    Exact,
+    /// Members pay for the lines of the receipt they had, unassigned lines are split equally
+    Items,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    #[schema(example = "66a3d2e1ce6a5cbb87195d20")]
+    pub id: String,
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub owner_id: String,
+    #[schema(example = "Roommates")]
+    pub name: String,
+    pub members: Vec<GroupMember>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<GroupEntity> for Group {
+    fn from(value: GroupEntity) -> Self {
+        Self {
+            id: value.id.to_hex(),
+            owner_id: value.owner_id.to_hex(),
+            name: value.name,
+            members: value.members.into_iter().map(Into::into).collect(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMember {
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    #[schema(example = "Test User")]
+    pub name: String,
+}
+
+impl From<GroupMemberEntity> for GroupMember {
+    fn from(value: GroupMemberEntity) -> Self {
+        Self {
+            user_id: value.user_id.to_hex(),
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Split {
+    #[schema(example = "66a3d2e1ce6a5cbb87195d30")]
+    pub id: String,
+    #[schema(example = "66a3d2e1ce6a5cbb87195d20")]
+    pub group_id: String,
+    #[schema(example = "669e5f02b781150b9a578205")]
+    pub invoice_id: String,
+    /// Member who paid the bill
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub paid_by: String,
+    #[schema(example = "Pizza 4P's")]
+    pub title: String,
+    #[schema(example = "equal")]
+    pub method: SplitMethod,
+    #[schema(example = 900000.0)]
+    pub total: f64,
+    #[schema(example = "VND")]
+    pub currency: String,
+    /// What each member owes for the bill, the member who paid included
+    pub shares: Vec<Share>,
+    /// Part of each transaction every member pays for
+    pub allocations: Vec<Allocation>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<SplitEntity> for Split {
+    fn from(value: SplitEntity) -> Self {
+        Self {
+            id: value.id.to_hex(),
+            group_id: value.group_id.to_hex(),
+            invoice_id: value.invoice_id.to_hex(),
+            paid_by: value.paid_by.to_hex(),
+            title: value.title,
+            method: value.method,
+            total: value.total,
+            currency: value.currency,
+            shares: value.shares.into_iter().map(Into::into).collect(),
+            allocations: value.allocations.into_iter().map(Into::into).collect(),
+            issued_at: value.issued_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Share {
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    #[schema(example = 300000.0)]
+    pub amount: f64,
+}
+
+impl From<ShareEntity> for Share {
+    fn from(value: ShareEntity) -> Self {
+        Self {
+            user_id: value.user_id.to_hex(),
+            amount: value.amount,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Allocation {
+    #[schema(example = "669fb456ce6a5cbb87195a60")]
+    pub transaction_id: String,
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    #[schema(example = 150000.0)]
+    pub amount: f64,
+}
+
+impl From<AllocationEntity> for Allocation {
+    fn from(value: AllocationEntity) -> Self {
+        Self {
+            transaction_id: value.transaction_id.to_hex(),
+            user_id: value.user_id.to_hex(),
+            amount: value.amount,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Settlement {
+    #[schema(example = "66a3d2e1ce6a5cbb87195d40")]
+    pub id: String,
+    #[schema(example = "66a3d2e1ce6a5cbb87195d20")]
+    pub group_id: String,
+    #[schema(example = "66990b1947d76ec3781adc9e")]
+    pub from_user_id: String,
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub to_user_id: String,
+    #[schema(example = 300000.0)]
+    pub amount: f64,
+    #[schema(example = "VND")]
+    pub currency: String,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<SettlementEntity> for Settlement {
+    fn from(value: SettlementEntity) -> Self {
+        Self {
+            id: value.id.to_hex(),
+            group_id: value.group_id.to_hex(),
+            from_user_id: value.from_user_id.to_hex(),
+            to_user_id: value.to_user_id.to_hex(),
+            amount: value.amount,
+            currency: value.currency,
+            issued_at: value.issued_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberBalance {
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    #[schema(example = "Test User")]
+    pub name: String,
+    /// Positive when the group owes the member, negative when the member owes the group
+    #[schema(example = 600000.0)]
+    pub amount: f64,
+}
+
+/// A payment of the settle-up plan.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Payment {
+    #[schema(example = "66990b1947d76ec3781adc9e")]
+    pub from_user_id: String,
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub to_user_id: String,
+    #[schema(example = 300000.0)]
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupBalance {
+    #[schema(example = "VND")]
+    pub currency: String,
+    pub members: Vec<MemberBalance>,
+    /// Fewest payments that settle every member
+    pub settle_up: Vec<Payment>,
+}
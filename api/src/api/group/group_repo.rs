@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::doc;
+use bson::oid::ObjectId;
+use futures::StreamExt;
+use mongodb::options::ReturnDocument;
+use mongodb::{ClientSession, Collection};
+
+use crate::api::group::*;
+
+#[async_trait]
+pub trait GroupRepoExt: Send + Sync {
+    async fn insert_one(&self, data: CreateGroupData) -> Result<GroupEntity, GroupError>;
+    async fn find_by_id(&self, id: ObjectId) -> Result<Option<GroupEntity>, GroupError>;
+    async fn find_by_member(&self, user_id: ObjectId) -> Result<Vec<GroupEntity>, GroupError>;
+    async fn add_member(
+        &self,
+        id: ObjectId,
+        member: GroupMemberEntity,
+    ) -> Result<Option<GroupEntity>, GroupError>;
+    async fn insert_split_with_session(
+        &self,
+        data: &CreateSplitData,
+        session: &mut ClientSession,
+    ) -> Result<SplitEntity, GroupError>;
+    async fn find_split_by_id(&self, id: ObjectId) -> Result<Option<SplitEntity>, GroupError>;
+    async fn find_splits(&self, group_id: ObjectId) -> Result<Vec<SplitEntity>, GroupError>;
+    async fn delete_split_with_session(
+        &self,
+        id: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<bool, GroupError>;
+    async fn insert_settlement(
+        &self,
+        data: CreateSettlementData,
+    ) -> Result<SettlementEntity, GroupError>;
+    async fn find_settlements(
+        &self,
+        group_id: ObjectId,
+    ) -> Result<Vec<SettlementEntity>, GroupError>;
+}
+
+pub type GroupRepoDyn = Arc<dyn GroupRepoExt + Send + Sync>;
+
+pub struct GroupRepo {
+    pub collection: Collection<GroupEntity>,
+    pub split_col: Collection<SplitEntity>,
+    pub settlement_col: Collection<SettlementEntity>,
+}
+
+#[async_trait]
+impl GroupRepoExt for GroupRepo {
+    async fn insert_one(&self, data: CreateGroupData) -> Result<GroupEntity, GroupError> {
+        let document = GroupEntity {
+            id: ObjectId::new(),
+            owner_id: data.owner_id,
+            name: data.name,
+            members: data.members,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        self.collection
+            .insert_one(&document)
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))?;
+
+        Ok(document)
+    }
+
+    async fn find_by_id(&self, id: ObjectId) -> Result<Option<GroupEntity>, GroupError> {
+        self.collection
+            .find_one(doc! { "_id": id })
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))
+    }
+
+    async fn find_by_member(&self, user_id: ObjectId) -> Result<Vec<GroupEntity>, GroupError> {
+        let mut cursor = self
+            .collection
+            .find(doc! { "members.userId": user_id })
+            .sort(doc! { "name": 1 })
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+
+    async fn add_member(
+        &self,
+        id: ObjectId,
+        member: GroupMemberEntity,
+    ) -> Result<Option<GroupEntity>, GroupError> {
+        let member = bson::to_bson(&member).map_err(|e| GroupError::Unknown(e.into()))?;
+
+        self.collection
+            .find_one_and_update(
+                doc! { "_id": id },
+                doc! {
+                    "$push": { "members": member },
+                    "$set": { "updatedAt": chrono::Utc::now() },
+                },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))
+    }
+
+    async fn insert_split_with_session(
+        &self,
+        data: &CreateSplitData,
+        session: &mut ClientSession,
+    ) -> Result<SplitEntity, GroupError> {
+        let document = SplitEntity {
+            id: ObjectId::new(),
+            group_id: data.group_id,
+            invoice_id: data.invoice_id,
+            paid_by: data.paid_by,
+            title: data.title.clone(),
+            method: data.method,
+            total: data.total,
+            currency: data.currency.clone(),
+            shares: data.shares.clone(),
+            allocations: data.allocations.clone(),
+            issued_at: data.issued_at,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        self.split_col
+            .insert_one(&document)
+            .session(session)
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))?;
+
+        Ok(document)
+    }
+
+    async fn find_split_by_id(&self, id: ObjectId) -> Result<Option<SplitEntity>, GroupError> {
+        self.split_col
+            .find_one(doc! { "_id": id })
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))
+    }
+
+    async fn find_splits(&self, group_id: ObjectId) -> Result<Vec<SplitEntity>, GroupError> {
+        let mut cursor = self
+            .split_col
+            .find(doc! { "groupId": group_id })
+            .sort(doc! { "issuedAt": -1 })
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+
+    async fn delete_split_with_session(
+        &self,
+        id: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<bool, GroupError> {
+        self.split_col
+            .delete_one(doc! { "_id": id })
+            .session(session)
+            .await
+            .map(|v| v.deleted_count == 1)
+            .map_err(|e| GroupError::Unknown(e.into()))
+    }
+
+    async fn insert_settlement(
+        &self,
+        data: CreateSettlementData,
+    ) -> Result<SettlementEntity, GroupError> {
+        let document = SettlementEntity {
+            id: ObjectId::new(),
+            group_id: data.group_id,
+            from_user_id: data.from_user_id,
+            to_user_id: data.to_user_id,
+            amount: data.amount,
+            currency: data.currency,
+            issued_at: data.issued_at,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        self.settlement_col
+            .insert_one(&document)
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))?;
+
+        Ok(document)
+    }
+
+    async fn find_settlements(
+        &self,
+        group_id: ObjectId,
+    ) -> Result<Vec<SettlementEntity>, GroupError> {
+        let mut cursor = self
+            .settlement_col
+            .find(doc! { "groupId": group_id })
+            .sort(doc! { "issuedAt": -1 })
+            .await
+            .map_err(|e| GroupError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+}
@@ -0,0 +1,31 @@
+use axum::middleware::from_fn_with_state;
+use axum::routing::{delete, get, post};
+use axum::Router;
+
+use crate::api::group::group_controller::*;
+use crate::api::state::AppState;
+use crate::mw::authorization_mw;
+
+pub struct GroupRouter(Router<AppState>);
+
+impl GroupRouter {
+    pub fn new(state: AppState) -> Self {
+        let routes = Router::new()
+            .route("/", get(list_groups).post(create_group))
+            .route("/:group_id", get(get_group))
+            .route("/:group_id/members", post(add_member))
+            .route("/:group_id/splits", get(list_splits).post(create_split))
+            .route("/:group_id/splits/:split_id", delete(delete_split))
+            .route("/:group_id/balances", get(get_balances))
+            .route("/:group_id/settlements", post(create_settlement))
+            .route_layer(from_fn_with_state(state.clone(), authorization_mw));
+
+        Self(routes)
+    }
+}
+
+impl From<GroupRouter> for Router<AppState> {
+    fn from(router: GroupRouter) -> Self {
+        router.0
+    }
+}
@@ -0,0 +1,366 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use futures::FutureExt;
+use itertools::Itertools;
+use mongodb::Client;
+
+use crate::api::asset::format_money;
+use crate::api::group::*;
+use crate::api::invoice::{InvoiceError, InvoiceServiceDyn};
+use crate::api::message::{InsertMessageInput, MessageServiceDyn};
+use crate::api::transaction::TransactionServiceDyn;
+use crate::api::user::UserServiceDyn;
+use crate::common::errors::AppError;
+use crate::object_id;
+
+#[async_trait]
+pub trait GroupServiceExt: Send + Sync {
+    async fn create(&self, input: CreateGroupInput) -> Result<Group, AppError>;
+    async fn list(&self, user_id: ObjectId) -> Result<Vec<Group>, AppError>;
+    async fn find_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<Group, AppError>;
+    async fn add_member(&self, input: AddMemberInput) -> Result<Group, AppError>;
+    /// Shares a receipt of the user with the group. Each member gets a message with their
+    /// share in their thread, linked to the transactions of the user.
+    async fn create_split(&self, input: CreateSplitInput) -> Result<Split, AppError>;
+    async fn list_splits(
+        &self,
+        group_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<Split>, AppError>;
+    async fn delete_split(
+        &self,
+        group_id: ObjectId,
+        id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<bool, AppError>;
+    async fn create_settlement(&self, input: CreateSettlementInput)
+        -> Result<Settlement, AppError>;
+    async fn get_balances(
+        &self,
+        group_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<GroupBalance>, AppError>;
+}
+
+pub type GroupServiceDyn = Arc<dyn GroupServiceExt + Send + Sync>;
+
+pub struct GroupService {
+    pub repo: GroupRepoDyn,
+    pub mongo_client: Client,
+    pub user_service: UserServiceDyn,
+    pub invoice_service: InvoiceServiceDyn,
+    pub transaction_service: TransactionServiceDyn,
+    pub message_service: MessageServiceDyn,
+}
+
+impl GroupService {
+    /// The group, only when the user is one of its members.
+    async fn member_group(&self, id: ObjectId, user_id: ObjectId) -> Result<GroupEntity, AppError> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .filter(|group| group.member(user_id).is_some())
+            .ok_or(GroupError::NotFound.into())
+    }
+}
+
+#[async_trait]
+impl GroupServiceExt for GroupService {
+    async fn create(&self, input: CreateGroupInput) -> Result<Group, AppError> {
+        let mut members = vec![GroupMemberEntity {
+            user_id: input.owner_id,
+            name: input.owner_name,
+        }];
+        for email in input.emails {
+            let user = self
+                .user_service
+                .find_by_email(email.trim().to_string())
+                .await?
+                .ok_or(GroupError::UserNotFound(email))?;
+            let user_id = object_id!(&user.id);
+            if members.iter().all(|member| member.user_id != user_id) {
+                members.push(GroupMemberEntity {
+                    user_id,
+                    name: user.full_name,
+                });
+            }
+        }
+
+        self.repo
+            .insert_one(CreateGroupData {
+                owner_id: input.owner_id,
+                name: input.name.trim().to_string(),
+                members,
+            })
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    async fn list(&self, user_id: ObjectId) -> Result<Vec<Group>, AppError> {
+        self.repo
+            .find_by_member(user_id)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn find_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<Group, AppError> {
+        self.member_group(id, user_id).await.map(Into::into)
+    }
+
+    async fn add_member(&self, input: AddMemberInput) -> Result<Group, AppError> {
+        let group = self.member_group(input.group_id, input.user_id).await?;
+        let user = self
+            .user_service
+            .find_by_email(input.email.trim().to_string())
+            .await?
+            .ok_or(GroupError::UserNotFound(input.email.clone()))?;
+        let user_id = object_id!(&user.id);
+        if group.member(user_id).is_some() {
+            return Err(GroupError::AlreadyMember(input.email).into());
+        }
+
+        self.repo
+            .add_member(
+                group.id,
+                GroupMemberEntity {
+                    user_id,
+                    name: user.full_name,
+                },
+            )
+            .await?
+            .map(Into::into)
+            .ok_or(GroupError::NotFound.into())
+    }
+
+    async fn create_split(&self, input: CreateSplitInput) -> Result<Split, AppError> {
+        let group = self.member_group(input.group_id, input.user_id).await?;
+        let invoice = self
+            .invoice_service
+            .find_by_id(input.invoice_id)
+            .await?
+            .filter(|invoice| invoice.user_id == input.user_id.to_hex())
+            .ok_or(InvoiceError::NotFound)?;
+        // income and transfers on the receipt are not part of the bill
+        let transactions = self
+            .transaction_service
+            .find_by_invoice_id(input.invoice_id)
+            .await?
+            .into_iter()
+            .filter(|tx| tx.r#type == "outcome")
+            .collect::<Vec<_>>();
+        if transactions.iter().any(|tx| tx.split_id.is_some()) {
+            return Err(GroupError::AlreadySplit.into());
+        }
+
+        let members = if input.members.is_empty() {
+            group
+                .members
+                .iter()
+                .map(|member| SplitMemberInput {
+                    user_id: member.user_id,
+                    weight: None,
+                    amount: None,
+                })
+                .collect()
+        } else {
+            input.members
+        };
+        if let Some(member) = members
+            .iter()
+            .find(|member| group.member(member.user_id).is_none())
+        {
+            return Err(GroupError::NotAMember(member.user_id.to_hex()).into());
+        }
+
+        let lines = transactions
+            .iter()
+            .map(|tx| SplitLine {
+                transaction_id: object_id!(&tx.id),
+                amount: tx.amount,
+            })
+            .collect::<Vec<SplitLine>>();
+        let allocations = allocate(input.method, &lines, &members, &input.items)?;
+        let data = &CreateSplitData {
+            group_id: group.id,
+            invoice_id: input.invoice_id,
+            paid_by: input.user_id,
+            title: invoice
+                .seller
+                .clone()
+                .or_else(|| transactions.first().map(|tx| tx.title.clone()))
+                .unwrap_or_default(),
+            method: input.method,
+            total: lines.iter().map(|line| line.amount).sum(),
+            currency: invoice.currency.clone(),
+            shares: shares(&members, &allocations),
+            allocations,
+            issued_at: invoice.issued_at,
+        };
+        let payer = group
+            .member(input.user_id)
+            .map(|member| member.name.clone())
+            .unwrap_or_default();
+
+        let mut session = self
+            .mongo_client
+            .start_session()
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))?;
+        let split = session
+            .start_transaction()
+            .and_run((&group, &payer), |session, (group, payer)| {
+                async move {
+                    let split = self
+                        .repo
+                        .insert_split_with_session(data, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+
+                    let transaction_ids = split
+                        .allocations
+                        .iter()
+                        .map(|allocation| allocation.transaction_id)
+                        .unique()
+                        .collect::<Vec<ObjectId>>();
+                    self.transaction_service
+                        .set_split_with_session(&transaction_ids, Some(split.id), session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+
+                    let messages = split
+                        .shares
+                        .iter()
+                        .filter(|share| share.user_id != split.paid_by)
+                        .map(|share| InsertMessageInput {
+                            id: ObjectId::new(),
+                            content: format!(
+                                "{} split {} in {}, your share is {}",
+                                payer,
+                                split.title,
+                                group.name,
+                                format_money(share.amount, &split.currency)
+                            ),
+                            from_id: split.paid_by,
+                            to_id: share.user_id,
+                            thread_id: share.user_id,
+                            reply_to_id: None,
+                            completion: None,
+                            split_id: Some(split.id),
+                            created_at: chrono::Utc::now(),
+                        })
+                        .collect::<Vec<InsertMessageInput>>();
+                    if !messages.is_empty() {
+                        self.message_service
+                            .insert_many_with_session(messages, session)
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
+                    }
+
+                    Ok(split)
+                }
+                .boxed()
+            })
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))?;
+
+        Ok(split.into())
+    }
+
+    async fn list_splits(
+        &self,
+        group_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<Split>, AppError> {
+        let group = self.member_group(group_id, user_id).await?;
+
+        self.repo
+            .find_splits(group.id)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn delete_split(
+        &self,
+        group_id: ObjectId,
+        id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<bool, AppError> {
+        // only the member who paid can take the bill back
+        let split = self
+            .repo
+            .find_split_by_id(id)
+            .await?
+            .filter(|split| split.group_id == group_id && split.paid_by == user_id)
+            .ok_or(GroupError::SplitNotFound)?;
+        let transaction_ids = split
+            .allocations
+            .iter()
+            .map(|allocation| allocation.transaction_id)
+            .unique()
+            .collect::<Vec<ObjectId>>();
+
+        let mut session = self
+            .mongo_client
+            .start_session()
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))?;
+        session
+            .start_transaction()
+            .and_run(&transaction_ids, |session, transaction_ids| {
+                async move {
+                    self.transaction_service
+                        .set_split_with_session(transaction_ids, None, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+                    self.message_service
+                        .delete_by_split_id_with_session(id, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+                    self.repo
+                        .delete_split_with_session(id, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)
+                }
+                .boxed()
+            })
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))
+    }
+
+    async fn create_settlement(
+        &self,
+        input: CreateSettlementInput,
+    ) -> Result<Settlement, AppError> {
+        let group = self
+            .member_group(input.group_id, input.from_user_id)
+            .await?;
+        if group.member(input.to_user_id).is_none() {
+            return Err(GroupError::NotAMember(input.to_user_id.to_hex()).into());
+        }
+
+        self.repo
+            .insert_settlement(input.into())
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    async fn get_balances(
+        &self,
+        group_id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Vec<GroupBalance>, AppError> {
+        let group = self.member_group(group_id, user_id).await?;
+        let (splits, settlements) = tokio::try_join!(
+            self.repo.find_splits(group.id),
+            self.repo.find_settlements(group.id)
+        )?;
+
+        Ok(group_balances(&group, &splits, &settlements))
+    }
+}
@@ -0,0 +1,239 @@
+use bson::oid::ObjectId;
+use itertools::Itertools;
+
+use crate::api::group::{
+    AllocationEntity, GroupError, ShareEntity, SplitItemInput, SplitMemberInput, SplitMethod,
+};
+
+// exact amounts typed by hand may be off by rounding
+const TOLERANCE: f64 = 0.01;
+
+/// An expense of the receipt being split.
+pub struct SplitLine {
+    pub transaction_id: ObjectId,
+    pub amount: f64,
+}
+
+fn invalid(reason: &str) -> GroupError {
+    GroupError::InvalidSplit(reason.to_string())
+}
+
+/// Part of every line each member pays for. Apart from the items method, each line is
+/// split in the same proportions as the bill, so reports keep the categories of the lines.
+pub fn allocate(
+    method: SplitMethod,
+    lines: &[SplitLine],
+    members: &[SplitMemberInput],
+    items: &[SplitItemInput],
+) -> Result<Vec<AllocationEntity>, GroupError> {
+    let total: f64 = lines.iter().map(|line| line.amount).sum();
+    if members.is_empty() || total <= 0.0 {
+        return Err(invalid("nothing to split"));
+    }
+    // a member listed twice would pay twice
+    if !members.iter().map(|member| member.user_id).all_unique() {
+        return Err(invalid("a member is listed twice"));
+    }
+
+    let weights = match method {
+        SplitMethod::Items => return allocate_items(lines, members, items),
+        SplitMethod::Equal => members.iter().map(|_| 1.0).collect::<Vec<f64>>(),
+        SplitMethod::Shares => members
+            .iter()
+            .map(|member| {
+                member
+                    .weight
+                    .ok_or_else(|| invalid("every member needs a weight"))
+            })
+            .collect::<Result<Vec<f64>, GroupError>>()?,
+        SplitMethod::Exact => {
+            let amounts = members
+                .iter()
+                .map(|member| {
+                    member
+                        .amount
+                        .ok_or_else(|| invalid("every member needs an amount"))
+                })
+                .collect::<Result<Vec<f64>, GroupError>>()?;
+            let sum: f64 = amounts.iter().sum();
+            if (sum - total).abs() > TOLERANCE {
+                return Err(GroupError::InvalidSplit(format!(
+                    "the amounts add up to {sum}, not {total}"
+                )));
+            }
+            amounts
+        }
+    };
+    let sum: f64 = weights.iter().sum();
+    if sum <= 0.0 {
+        return Err(invalid("nobody pays for the bill"));
+    }
+
+    Ok(lines
+        .iter()
+        .flat_map(|line| {
+            members
+                .iter()
+                .zip(&weights)
+                .filter(|(_, weight)| **weight > 0.0)
+                .map(move |(member, weight)| AllocationEntity {
+                    transaction_id: line.transaction_id,
+                    user_id: member.user_id,
+                    amount: line.amount * weight / sum,
+                })
+        })
+        .collect())
+}
+
+fn allocate_items(
+    lines: &[SplitLine],
+    members: &[SplitMemberInput],
+    items: &[SplitItemInput],
+) -> Result<Vec<AllocationEntity>, GroupError> {
+    for item in items {
+        if !lines
+            .iter()
+            .any(|line| line.transaction_id == item.transaction_id)
+        {
+            return Err(invalid("a line is not on the receipt"));
+        }
+        if !item.user_ids.iter().all_unique() {
+            return Err(invalid("a line lists a member twice"));
+        }
+        if item.user_ids.is_empty()
+            || item
+                .user_ids
+                .iter()
+                .any(|user_id| !members.iter().any(|member| member.user_id == *user_id))
+        {
+            return Err(invalid(
+                "a line is assigned to someone not sharing the bill",
+            ));
+        }
+    }
+
+    let everyone = members
+        .iter()
+        .map(|member| member.user_id)
+        .collect::<Vec<ObjectId>>();
+    Ok(lines
+        .iter()
+        .flat_map(|line| {
+            let user_ids = items
+                .iter()
+                .find(|item| item.transaction_id == line.transaction_id)
+                .map_or(&everyone, |item| &item.user_ids);
+            let amount = line.amount / user_ids.len() as f64;
+            user_ids.iter().map(move |user_id| AllocationEntity {
+                transaction_id: line.transaction_id,
+                user_id: *user_id,
+                amount,
+            })
+        })
+        .collect())
+}
+
+/// What each member owes for the bill, members without anything to pay are left out.
+pub fn shares(members: &[SplitMemberInput], allocations: &[AllocationEntity]) -> Vec<ShareEntity> {
+    members
+        .iter()
+        .map(|member| ShareEntity {
+            user_id: member.user_id,
+            amount: allocations
+                .iter()
+                .filter(|allocation| allocation.user_id == member.user_id)
+                .map(|allocation| allocation.amount)
+                .sum(),
+        })
+        .filter(|share| share.amount > 0.0)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(weight: Option<f64>, amount: Option<f64>) -> SplitMemberInput {
+        SplitMemberInput {
+            user_id: ObjectId::new(),
+            weight,
+            amount,
+        }
+    }
+
+    fn line(amount: f64) -> SplitLine {
+        SplitLine {
+            transaction_id: ObjectId::new(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_allocate_by_shares() {
+        let members = vec![member(Some(2.0), None), member(Some(1.0), None)];
+        let lines = vec![line(60.0), line(30.0)];
+
+        let allocations = allocate(SplitMethod::Shares, &lines, &members, &[]).unwrap();
+
+        assert_eq!(allocations.len(), 4);
+        assert_eq!(allocations[0].amount, 40.0);
+        assert_eq!(allocations[3].amount, 10.0);
+        let shares = shares(&members, &allocations);
+        assert_eq!(shares[0].amount, 60.0);
+        assert_eq!(shares[1].amount, 30.0);
+    }
+
+    #[test]
+    fn test_allocate_exact() {
+        let members = vec![member(None, Some(70.0)), member(None, Some(30.0))];
+        let lines = vec![line(100.0)];
+
+        let allocations = allocate(SplitMethod::Exact, &lines, &members, &[]).unwrap();
+        assert_eq!(allocations[1].amount, 30.0);
+
+        let members = vec![member(None, Some(70.0)), member(None, Some(20.0))];
+        assert!(allocate(SplitMethod::Exact, &lines, &members, &[]).is_err());
+    }
+
+    #[test]
+    fn test_allocate_duplicate_member() {
+        let members = vec![member(None, None), member(None, None)];
+        let lines = vec![line(30.0)];
+        let mut twice = vec![member(None, None), member(None, None)];
+        twice[1].user_id = twice[0].user_id;
+
+        assert!(matches!(
+            allocate(SplitMethod::Equal, &lines, &twice, &[]),
+            Err(GroupError::InvalidSplit(_))
+        ));
+
+        let items = vec![SplitItemInput {
+            transaction_id: lines[0].transaction_id,
+            user_ids: vec![members[0].user_id, members[0].user_id],
+        }];
+        assert!(allocate(SplitMethod::Items, &lines, &members, &items).is_err());
+    }
+
+    #[test]
+    fn test_allocate_items() {
+        let members = vec![member(None, None), member(None, None)];
+        let lines = vec![line(50.0), line(20.0)];
+        let items = vec![SplitItemInput {
+            transaction_id: lines[0].transaction_id,
+            user_ids: vec![members[0].user_id],
+        }];
+
+        let allocations = allocate(SplitMethod::Items, &lines, &members, &items).unwrap();
+        let shares = shares(&members, &allocations);
+
+        // the unassigned line is shared by everyone
+        assert_eq!(shares[0].amount, 60.0);
+        assert_eq!(shares[1].amount, 10.0);
+
+        let items = vec![SplitItemInput {
+            transaction_id: lines[0].transaction_id,
+            user_ids: vec![ObjectId::new()],
+        }];
+        assert!(allocate(SplitMethod::Items, &lines, &members, &items).is_err());
+    }
+}
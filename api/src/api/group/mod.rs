@@ -0,0 +1,22 @@
+pub(crate) use constants::*;
+pub(crate) use dto::*;
+#[allow(unused_imports)]
+pub use group_controller::GroupApiDoc;
+pub(crate) use group_balance::*;
+pub(crate) use group_entity::*;
+pub use group_model::*;
+pub(crate) use group_repo::*;
+pub use group_router::*;
+pub use group_service::*;
+pub(crate) use group_split::*;
+
+mod constants;
+mod dto;
+mod group_balance;
+mod group_controller;
+mod group_entity;
+mod group_model;
+mod group_repo;
+mod group_router;
+mod group_service;
+mod group_split;
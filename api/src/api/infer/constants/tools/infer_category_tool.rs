@@ -29,8 +29,8 @@ pub fn make_infer_category_tool(categories: Vec<Category>) -> FunctionObject {
                 },
                 "type": {
                     "type": "string",
-                    "enum": ["income", "outcome", "debt", "other"],
-                    "description": "The type of the transaction",
+                    "enum": ["income", "outcome", "transfer", "debt", "other"],
+                    "description": "The type of the transaction, transfer when the money moves between accounts of the user e.g. withdrawing cash from an ATM",
                 },
             },
         }))
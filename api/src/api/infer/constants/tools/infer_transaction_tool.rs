@@ -13,6 +13,10 @@ pub struct TransactionToolRaw {
     pub quantity: Option<f64>,
     pub unit: Option<String>,
     pub date: Option<String>,
+    pub from_account: Option<String>,
+    pub to_account: Option<String>,
+    pub counterparty: Option<String>,
+    pub debt: Option<String>,
 }
 
 pub fn parse_amount_string(amount: String) -> f64 {
@@ -165,6 +169,25 @@ pub fn make_infer_transaction_tool(currencies: Vec<String>) -> FunctionObject {
                     "type": "string",
                     "examples": ["1 hour ago", "yesterday", "2 days ago", "last week", "3 weeks ago", "last month", "2 months ago", "30/04"],
                     "description": "The timestamp of the transaction in format DD/MM or 1 hour ago, yesterday",
+                },
+                "from_account": {
+                    "type": "string",
+                    "enum": ["cash", "bank", "credit_card", "e_wallet"],
+                    "description": "Only when the money moves between accounts of the user, the kind of account it leaves e.g. bank for withdrew 2tr from ATM",
+                },
+                "to_account": {
+                    "type": "string",
+                    "enum": ["cash", "bank", "credit_card", "e_wallet"],
+                    "description": "Only when the money moves between accounts of the user, the kind of account it goes to e.g. cash for withdrew 2tr from ATM, credit_card for paid off my credit card",
+                },
+                "counterparty": {
+                    "type": "string",
+                    "description": "Only when money is lent, borrowed or paid back, the name of the other person e.g. Minh for lent Minh 500k",
+                },
+                "debt": {
+                    "type": "string",
+                    "enum": ["lent", "borrowed", "repaid_to_me", "repaid_by_me"],
+                    "description": "Only when money is lent, borrowed or paid back, from the point of view of the user e.g. lent for lent Minh 500k, repaid_to_me for Minh paid me back 200k, repaid_by_me for paid Lan back 100k",
                 }
             }
         }))
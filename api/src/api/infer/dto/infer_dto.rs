@@ -1,7 +1,17 @@
 use crate::api::category::Category;
+use crate::api::message::{MessageEvent, MessageEventSender};
+use crate::services::llm::ChatImage;
 
 #[derive(Clone, Debug)]
 pub struct InferOptions {
     pub currencies: Vec<String>,
     pub categories: Vec<Category>,
+    pub events: Option<MessageEventSender>,
+    pub images: Vec<ChatImage>,
+}
+
+impl InferOptions {
+    pub fn emit(&self, event: MessageEvent) {
+        event.send(self.events.as_ref());
+    }
 }
@@ -0,0 +1,135 @@
+use crate::api::infer::einvoice::{make_transaction, parse_date, EInvoiceError, XmlNode};
+use crate::api::infer::models::{DiscountTool, InvoiceTool, TaxTool};
+
+/// UN/CEFACT Cross Industry Invoice, the syntax behind ZUGFeRD and Factur-X.
+pub fn parse(root: &XmlNode) -> Result<InvoiceTool, EInvoiceError> {
+    let issued_at = parse_date(root.text("ExchangedDocument/IssueDateTime/DateTimeString"));
+    let trade = root
+        .find("SupplyChainTradeTransaction")
+        .ok_or(EInvoiceError::Invalid("missing trade transaction"))?;
+    let settlement = trade
+        .find("ApplicableHeaderTradeSettlement")
+        .ok_or(EInvoiceError::Invalid("missing trade settlement"))?;
+    let currency = settlement
+        .text("InvoiceCurrencyCode")
+        .ok_or(EInvoiceError::Invalid("missing currency"))?;
+    let seller = trade.text("ApplicableHeaderTradeAgreement/SellerTradeParty/Name");
+
+    let transactions = trade
+        .children("IncludedSupplyChainTradeLineItem")
+        .map(|line| {
+            make_transaction(
+                line.text("SpecifiedTradeProduct/Name"),
+                line.number("SpecifiedLineTradeDelivery/BilledQuantity"),
+                line.find("SpecifiedLineTradeDelivery/BilledQuantity")
+                    .and_then(|quantity| quantity.attribute("unitCode")),
+                line.number(
+                    "SpecifiedLineTradeSettlement/SpecifiedTradeSettlementLineMonetarySummation/LineTotalAmount",
+                ),
+                &currency,
+                issued_at,
+            )
+        })
+        .collect();
+
+    let taxes = settlement
+        .children("ApplicableTradeTax")
+        .map(|tax| TaxTool {
+            rate: tax.number("RateApplicablePercent").unwrap_or_default() as f32,
+            amount: tax.number("CalculatedAmount").unwrap_or_default() as f32,
+        })
+        .filter(|tax| tax.amount != 0.0)
+        .collect();
+
+    let discounts = settlement
+        .children("SpecifiedTradeAllowanceCharge")
+        .filter(|allowance| allowance.text("ChargeIndicator/Indicator").as_deref() == Some("false"))
+        .map(|allowance| DiscountTool {
+            name: allowance.text("Reason").unwrap_or("Discount".to_string()),
+            rate: allowance.number("CalculationPercent").unwrap_or_default() as f32,
+            amount: allowance.number("ActualAmount").unwrap_or_default() as f32,
+        })
+        .filter(|discount| discount.amount != 0.0)
+        .collect();
+
+    let summation = settlement
+        .find("SpecifiedTradeSettlementHeaderMonetarySummation")
+        .ok_or(EInvoiceError::Invalid("missing monetary summation"))?;
+    let total = summation
+        .number("DuePayableAmount")
+        .or_else(|| summation.number("GrandTotalAmount"))
+        .ok_or(EInvoiceError::Invalid("missing total"))?;
+
+    Ok(InvoiceTool {
+        issued_at,
+        transactions,
+        discounts,
+        taxes,
+        subtotal: summation.number("LineTotalAmount"),
+        total,
+        currency,
+        card_number: None,
+        seller,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::infer::einvoice::parse_einvoice;
+
+    const CII: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rsm:CrossIndustryInvoice xmlns:rsm="urn:un:unece:uncefact:data:standard:CrossIndustryInvoice:100"
+    xmlns:ram="urn:un:unece:uncefact:data:standard:ReusableAggregateBusinessInformationEntity:100"
+    xmlns:udt="urn:un:unece:uncefact:data:standard:UnqualifiedDataType:100">
+  <rsm:ExchangedDocument>
+    <ram:ID>471102</ram:ID>
+    <ram:IssueDateTime><udt:DateTimeString format="102">20240305</udt:DateTimeString></ram:IssueDateTime>
+  </rsm:ExchangedDocument>
+  <rsm:SupplyChainTradeTransaction>
+    <ram:IncludedSupplyChainTradeLineItem>
+      <ram:SpecifiedTradeProduct><ram:Name>Office chair</ram:Name></ram:SpecifiedTradeProduct>
+      <ram:SpecifiedLineTradeDelivery>
+        <ram:BilledQuantity unitCode="H87">2.0000</ram:BilledQuantity>
+      </ram:SpecifiedLineTradeDelivery>
+      <ram:SpecifiedLineTradeSettlement>
+        <ram:SpecifiedTradeSettlementLineMonetarySummation>
+          <ram:LineTotalAmount>300.00</ram:LineTotalAmount>
+        </ram:SpecifiedTradeSettlementLineMonetarySummation>
+      </ram:SpecifiedLineTradeSettlement>
+    </ram:IncludedSupplyChainTradeLineItem>
+    <ram:ApplicableHeaderTradeAgreement>
+      <ram:SellerTradeParty><ram:Name>Möbel AG</ram:Name></ram:SellerTradeParty>
+    </ram:ApplicableHeaderTradeAgreement>
+    <ram:ApplicableHeaderTradeSettlement>
+      <ram:InvoiceCurrencyCode>EUR</ram:InvoiceCurrencyCode>
+      <ram:ApplicableTradeTax>
+        <ram:CalculatedAmount>57.00</ram:CalculatedAmount>
+        <ram:TypeCode>VAT</ram:TypeCode>
+        <ram:RateApplicablePercent>19.00</ram:RateApplicablePercent>
+      </ram:ApplicableTradeTax>
+      <ram:SpecifiedTradeSettlementHeaderMonetarySummation>
+        <ram:LineTotalAmount>300.00</ram:LineTotalAmount>
+        <ram:TaxBasisTotalAmount>300.00</ram:TaxBasisTotalAmount>
+        <ram:GrandTotalAmount>357.00</ram:GrandTotalAmount>
+        <ram:DuePayableAmount>357.00</ram:DuePayableAmount>
+      </ram:SpecifiedTradeSettlementHeaderMonetarySummation>
+    </ram:ApplicableHeaderTradeSettlement>
+  </rsm:SupplyChainTradeTransaction>
+</rsm:CrossIndustryInvoice>"#;
+
+    #[test]
+    fn test_parse_cii() {
+        let invoice = parse_einvoice(CII).unwrap();
+
+        assert_eq!(invoice.issued_at.to_rfc3339(), "2024-03-05T00:00:00+00:00");
+        assert_eq!(invoice.currency, "EUR");
+        assert_eq!(invoice.seller.as_deref(), Some("Möbel AG"));
+        assert_eq!(invoice.transactions.len(), 1);
+        assert_eq!(invoice.transactions[0].title, "Office chair");
+        assert_eq!(invoice.transactions[0].quantity, 2.0);
+        assert_eq!(invoice.transactions[0].amount, 300.0);
+        assert_eq!(invoice.taxes[0].amount, 57.0);
+        assert_eq!(invoice.subtotal, Some(300.0));
+        assert_eq!(invoice.total, 357.0);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::api::infer::einvoice::{make_transaction, parse_date, EInvoiceError, XmlNode};
+use crate::api::infer::models::{DiscountTool, InvoiceTool, TaxTool};
+
+// `TChat` of a line: 1 goods or service, 2 promotion, 3 trade discount, 4 note
+const LINE_DISCOUNT: &str = "3";
+const LINE_NOTE: &str = "4";
+
+/// Finds the `HDon` element, either as the root or wrapped in a transmission message.
+pub fn find_invoice(node: &XmlNode) -> Option<&XmlNode> {
+    if node.name == "HDon" {
+        return Some(node);
+    }
+
+    node.children.iter().find_map(find_invoice)
+}
+
+/// Vietnam General Department of Taxation e-invoice (Decree 123/2020, Circular 78/2021).
+pub fn parse(invoice: &XmlNode) -> Result<InvoiceTool, EInvoiceError> {
+    let data = invoice
+        .find("DLHDon")
+        .ok_or(EInvoiceError::Invalid("missing invoice data"))?;
+    let issued_at = parse_date(data.text("TTChung/NLap"));
+    let currency = data.text("TTChung/DVTTe").unwrap_or("VND".to_string());
+    let seller = data.text("NDHDon/NBan/Ten");
+
+    let mut transactions = vec![];
+    let mut discounts = vec![];
+    for line in data.find_all("NDHDon/DSHHDVu/HHDVu") {
+        let title = line.text("THHDVu");
+        match line.text("TChat").as_deref() {
+            Some(LINE_NOTE) => continue,
+            Some(LINE_DISCOUNT) => discounts.push(DiscountTool {
+                name: title.unwrap_or("Chiết khấu".to_string()),
+                rate: line.number("TLCKhau").unwrap_or_default() as f32,
+                amount: line.number("ThTien").unwrap_or_default().abs() as f32,
+            }),
+            _ => {
+                let amount = line
+                    .number("ThTien")
+                    .or_else(|| Some(line.number("SLuong").unwrap_or(1.0) * line.number("DGia")?));
+                if let Some(discount) = line.number("STCKhau").filter(|amount| *amount != 0.0) {
+                    discounts.push(DiscountTool {
+                        name: title.clone().unwrap_or_default(),
+                        rate: line.number("TLCKhau").unwrap_or_default() as f32,
+                        amount: discount.abs() as f32,
+                    });
+                }
+                transactions.push(make_transaction(
+                    title,
+                    line.number("SLuong"),
+                    line.find("DVTinh").map(|unit| unit.text.trim()),
+                    amount,
+                    &currency,
+                    issued_at,
+                ));
+            }
+        }
+    }
+
+    // rates are written as `10%`, or codes such as `KCT` for goods outside VAT
+    let taxes = data
+        .find_all("NDHDon/TToan/THTTLTSuat/LTSuat")
+        .into_iter()
+        .map(|tax| TaxTool {
+            rate: tax.number("TSuat").unwrap_or_default() as f32,
+            amount: tax.number("TThue").unwrap_or_default() as f32,
+        })
+        .filter(|tax| tax.amount != 0.0)
+        .collect();
+
+    let total = data
+        .number("NDHDon/TToan/TgTTTBSo")
+        .ok_or(EInvoiceError::Invalid("missing total"))?;
+
+    Ok(InvoiceTool {
+        issued_at,
+        transactions,
+        discounts,
+        taxes,
+        subtotal: data.number("NDHDon/TToan/TgTCThue"),
+        total,
+        currency,
+        card_number: None,
+        seller,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::infer::einvoice::parse_einvoice;
+
+    const GDT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<HDon>
+  <DLHDon Id="data">
+    <TTChung>
+      <PBan>2.0.0</PBan>
+      <KHHDon>C24TAA</KHHDon>
+      <SHDon>123</SHDon>
+      <NLap>2024-02-20</NLap>
+      <DVTTe>VND</DVTTe>
+    </TTChung>
+    <NDHDon>
+      <NBan><Ten>CÔNG TY TNHH ĐIỆN LỰC</Ten><MST>0101234567</MST></NBan>
+      <NMua><Ten>Nguyễn Văn A</Ten></NMua>
+      <DSHHDVu>
+        <HHDVu>
+          <TChat>1</TChat>
+          <STT>1</STT>
+          <THHDVu>Điện sinh hoạt</THHDVu>
+          <DVTinh>kWh</DVTinh>
+          <SLuong>150</SLuong>
+          <DGia>2000</DGia>
+          <ThTien>300000</ThTien>
+          <TSuat>8%</TSuat>
+        </HHDVu>
+        <HHDVu>
+          <TChat>3</TChat>
+          <STT>2</STT>
+          <THHDVu>Chiết khấu thương mại</THHDVu>
+          <ThTien>20000</ThTien>
+        </HHDVu>
+        <HHDVu>
+          <TChat>4</TChat>
+          <THHDVu>Kỳ hóa đơn tháng 02/2024</THHDVu>
+        </HHDVu>
+      </DSHHDVu>
+      <TToan>
+        <THTTLTSuat>
+          <LTSuat><TSuat>8%</TSuat><ThTien>280000</ThTien><TThue>22400</TThue></LTSuat>
+        </THTTLTSuat>
+        <TgTCThue>280000</TgTCThue>
+        <TgTThue>22400</TgTThue>
+        <TgTTTBSo>302400</TgTTTBSo>
+      </TToan>
+    </NDHDon>
+  </DLHDon>
+</HDon>"#;
+
+    #[test]
+    fn test_parse_gdt() {
+        let invoice = parse_einvoice(GDT).unwrap();
+
+        assert_eq!(invoice.issued_at.to_rfc3339(), "2024-02-20T00:00:00+00:00");
+        assert_eq!(invoice.currency, "VND");
+        assert_eq!(invoice.seller.as_deref(), Some("CÔNG TY TNHH ĐIỆN LỰC"));
+        assert_eq!(invoice.transactions.len(), 1);
+        assert_eq!(invoice.transactions[0].title, "Điện sinh hoạt");
+        assert_eq!(invoice.transactions[0].unit.as_deref(), Some("kWh"));
+        assert_eq!(invoice.transactions[0].quantity, 150.0);
+        assert_eq!(invoice.transactions[0].amount, 300000.0);
+        assert_eq!(invoice.discounts[0].amount, 20000.0);
+        assert_eq!(invoice.taxes[0].rate, 8.0);
+        assert_eq!(invoice.taxes[0].amount, 22400.0);
+        assert_eq!(invoice.total, 302400.0);
+    }
+
+    #[test]
+    fn test_parse_unsupported() {
+        assert!(parse_einvoice("<Order><ID>1</ID></Order>").is_err());
+        assert!(parse_einvoice("not xml").is_err());
+    }
+}
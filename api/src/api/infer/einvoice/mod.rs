@@ -0,0 +1,73 @@
+mod cii;
+mod gdt;
+mod ubl;
+mod xml_node;
+
+pub use xml_node::*;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use thiserror::Error;
+
+use crate::api::infer::models::{InvoiceTool, TransactionTool};
+
+#[derive(Error, Debug)]
+pub enum EInvoiceError {
+    #[error("unsupported e-invoice format: {0}")]
+    UnsupportedFormat(String),
+    #[error("invalid e-invoice: {0}")]
+    Invalid(&'static str),
+    #[error(transparent)]
+    Xml(#[from] quick_xml::Error),
+}
+
+/// Parses a structured e-invoice into an `InvoiceTool`. Supported are UBL 2.1 invoices,
+/// UN/CEFACT CII (also ZUGFeRD / Factur-X) and Vietnam GDT e-invoices.
+pub fn parse_einvoice(xml: &str) -> Result<InvoiceTool, EInvoiceError> {
+    let root = XmlNode::parse(xml)?;
+
+    let invoice_tool = match root.name.as_str() {
+        "Invoice" => ubl::parse(&root)?,
+        "CrossIndustryInvoice" => cii::parse(&root)?,
+        _ => match gdt::find_invoice(&root) {
+            Some(invoice) => gdt::parse(invoice)?,
+            None => return Err(EInvoiceError::UnsupportedFormat(root.name)),
+        },
+    };
+
+    if invoice_tool.transactions.is_empty() {
+        return Err(EInvoiceError::Invalid("no line items"));
+    }
+
+    Ok(invoice_tool)
+}
+
+/// Accepts `2024-01-31` and the CII `102` format `20240131`.
+fn parse_date(text: Option<String>) -> chrono::DateTime<Utc> {
+    text.and_then(|text| {
+        NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+            .or_else(|_| NaiveDate::parse_from_str(&text, "%Y%m%d"))
+            .ok()
+    })
+    .and_then(|date| date.and_hms_opt(0, 0, 0))
+    .map(|date| Utc.from_utc_datetime(&date))
+    .unwrap_or_else(Utc::now)
+}
+
+fn make_transaction(
+    title: Option<String>,
+    quantity: Option<f64>,
+    unit: Option<&str>,
+    amount: Option<f64>,
+    currency: &str,
+    issued_at: chrono::DateTime<Utc>,
+) -> TransactionTool {
+    TransactionTool {
+        title: title.unwrap_or_default(),
+        currency: currency.to_string(),
+        amount: amount.unwrap_or_default(),
+        quantity: quantity.unwrap_or(1.0),
+        unit: unit.map(str::to_string),
+        issued_at,
+        ..Default::default()
+    }
+}
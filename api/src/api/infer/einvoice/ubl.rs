@@ -0,0 +1,142 @@
+use crate::api::infer::einvoice::{make_transaction, parse_date, EInvoiceError, XmlNode};
+use crate::api::infer::models::{DiscountTool, InvoiceTool, TaxTool};
+
+/// OASIS UBL 2.1 `Invoice`, also used by PEPPOL BIS.
+pub fn parse(root: &XmlNode) -> Result<InvoiceTool, EInvoiceError> {
+    let issued_at = parse_date(root.text("IssueDate"));
+    let currency = root
+        .text("DocumentCurrencyCode")
+        .ok_or(EInvoiceError::Invalid("missing currency"))?;
+    let seller = root
+        .text("AccountingSupplierParty/Party/PartyName/Name")
+        .or_else(|| root.text("AccountingSupplierParty/Party/PartyLegalEntity/RegistrationName"));
+
+    let transactions = root
+        .children("InvoiceLine")
+        .map(|line| {
+            make_transaction(
+                line.text("Item/Name")
+                    .or_else(|| line.text("Item/Description")),
+                line.number("InvoicedQuantity"),
+                line.find("InvoicedQuantity")
+                    .and_then(|quantity| quantity.attribute("unitCode")),
+                line.number("LineExtensionAmount"),
+                &currency,
+                issued_at,
+            )
+        })
+        .collect();
+
+    let taxes = root
+        .children("TaxTotal")
+        .flat_map(|total| total.children("TaxSubtotal"))
+        .map(|subtotal| TaxTool {
+            rate: subtotal.number("TaxCategory/Percent").unwrap_or_default() as f32,
+            amount: subtotal.number("TaxAmount").unwrap_or_default() as f32,
+        })
+        .filter(|tax| tax.amount != 0.0)
+        .collect();
+
+    let discounts = root
+        .children("AllowanceCharge")
+        .filter(|allowance| allowance.text("ChargeIndicator").as_deref() == Some("false"))
+        .map(|allowance| DiscountTool {
+            name: allowance
+                .text("AllowanceChargeReason")
+                .unwrap_or("Discount".to_string()),
+            rate: allowance
+                .number("MultiplierFactorNumeric")
+                .unwrap_or_default() as f32,
+            amount: allowance.number("Amount").unwrap_or_default() as f32,
+        })
+        .filter(|discount| discount.amount != 0.0)
+        .collect();
+
+    let total = root
+        .number("LegalMonetaryTotal/PayableAmount")
+        .or_else(|| root.number("LegalMonetaryTotal/TaxInclusiveAmount"))
+        .ok_or(EInvoiceError::Invalid("missing total"))?;
+
+    Ok(InvoiceTool {
+        issued_at,
+        transactions,
+        discounts,
+        taxes,
+        subtotal: root.number("LegalMonetaryTotal/LineExtensionAmount"),
+        total,
+        currency,
+        card_number: None,
+        seller,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::infer::einvoice::parse_einvoice;
+
+    const UBL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Invoice xmlns="urn:oasis:names:specification:ubl:schema:xsd:Invoice-2"
+         xmlns:cac="urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2"
+         xmlns:cbc="urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2">
+  <cbc:ID>INV-1001</cbc:ID>
+  <cbc:IssueDate>2024-03-15</cbc:IssueDate>
+  <cbc:DocumentCurrencyCode>EUR</cbc:DocumentCurrencyCode>
+  <cac:AccountingSupplierParty>
+    <cac:Party>
+      <cac:PartyName><cbc:Name>Hosting GmbH</cbc:Name></cac:PartyName>
+    </cac:Party>
+  </cac:AccountingSupplierParty>
+  <cac:AllowanceCharge>
+    <cbc:ChargeIndicator>false</cbc:ChargeIndicator>
+    <cbc:AllowanceChargeReason>Loyalty</cbc:AllowanceChargeReason>
+    <cbc:MultiplierFactorNumeric>10</cbc:MultiplierFactorNumeric>
+    <cbc:Amount currencyID="EUR">5.00</cbc:Amount>
+  </cac:AllowanceCharge>
+  <cac:TaxTotal>
+    <cbc:TaxAmount currencyID="EUR">8.55</cbc:TaxAmount>
+    <cac:TaxSubtotal>
+      <cbc:TaxableAmount currencyID="EUR">45.00</cbc:TaxableAmount>
+      <cbc:TaxAmount currencyID="EUR">8.55</cbc:TaxAmount>
+      <cac:TaxCategory><cbc:Percent>19</cbc:Percent></cac:TaxCategory>
+    </cac:TaxSubtotal>
+  </cac:TaxTotal>
+  <cac:LegalMonetaryTotal>
+    <cbc:LineExtensionAmount currencyID="EUR">50.00</cbc:LineExtensionAmount>
+    <cbc:TaxInclusiveAmount currencyID="EUR">53.55</cbc:TaxInclusiveAmount>
+    <cbc:PayableAmount currencyID="EUR">53.55</cbc:PayableAmount>
+  </cac:LegalMonetaryTotal>
+  <cac:InvoiceLine>
+    <cbc:ID>1</cbc:ID>
+    <cbc:InvoicedQuantity unitCode="MON">2</cbc:InvoicedQuantity>
+    <cbc:LineExtensionAmount currencyID="EUR">40.00</cbc:LineExtensionAmount>
+    <cac:Item><cbc:Name>VPS &amp; backups</cbc:Name></cac:Item>
+  </cac:InvoiceLine>
+  <cac:InvoiceLine>
+    <cbc:ID>2</cbc:ID>
+    <cbc:InvoicedQuantity unitCode="C62">1</cbc:InvoicedQuantity>
+    <cbc:LineExtensionAmount currencyID="EUR">10.00</cbc:LineExtensionAmount>
+    <cac:Item><cbc:Name>Domain</cbc:Name></cac:Item>
+  </cac:InvoiceLine>
+</Invoice>"#;
+
+    #[test]
+    fn test_parse_ubl() {
+        let invoice = parse_einvoice(UBL).unwrap();
+
+        assert_eq!(invoice.issued_at.to_rfc3339(), "2024-03-15T00:00:00+00:00");
+        assert_eq!(invoice.currency, "EUR");
+        assert_eq!(invoice.seller.as_deref(), Some("Hosting GmbH"));
+        assert_eq!(invoice.transactions.len(), 2);
+        assert_eq!(invoice.transactions[0].title, "VPS & backups");
+        assert_eq!(invoice.transactions[0].quantity, 2.0);
+        assert_eq!(invoice.transactions[0].unit.as_deref(), Some("MON"));
+        assert_eq!(invoice.transactions[0].amount, 40.0);
+        assert_eq!(invoice.transactions[1].currency, "EUR");
+        assert_eq!(invoice.taxes[0].rate, 19.0);
+        assert_eq!(invoice.taxes[0].amount, 8.55);
+        assert_eq!(invoice.discounts[0].name, "Loyalty");
+        assert_eq!(invoice.discounts[0].amount, 5.0);
+        assert_eq!(invoice.subtotal, Some(50.0));
+        assert_eq!(invoice.total, 53.55);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::api::infer::einvoice::EInvoiceError;
+
+/// Minimal element tree of an xml document. Namespace prefixes are dropped so lookups work
+/// whatever prefixes the issuer picked.
+#[derive(Debug, Clone, Default)]
+pub struct XmlNode {
+    pub name: String,
+    pub attributes: HashMap<String, String>,
+    pub text: String,
+    pub children: Vec<XmlNode>,
+}
+
+fn local_name(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).to_string()
+}
+
+fn to_node(start: &BytesStart) -> Result<XmlNode, EInvoiceError> {
+    let mut attributes = HashMap::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        attributes.insert(
+            local_name(attribute.key.local_name().as_ref()),
+            attribute.unescape_value()?.to_string(),
+        );
+    }
+
+    Ok(XmlNode {
+        name: local_name(start.local_name().as_ref()),
+        attributes,
+        ..Default::default()
+    })
+}
+
+impl XmlNode {
+    pub fn parse(xml: &str) -> Result<XmlNode, EInvoiceError> {
+        let mut reader = Reader::from_str(xml);
+        reader.trim_text(true);
+
+        let mut stack: Vec<XmlNode> = vec![];
+        loop {
+            match reader.read_event()? {
+                Event::Start(start) => stack.push(to_node(&start)?),
+                Event::Empty(start) => {
+                    let node = to_node(&start)?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(node),
+                        None => return Ok(node),
+                    }
+                }
+                Event::Text(text) => {
+                    if let Some(node) = stack.last_mut() {
+                        node.text.push_str(&text.unescape()?);
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(node) = stack.last_mut() {
+                        node.text.push_str(&String::from_utf8_lossy(&data));
+                    }
+                }
+                Event::End(_) => {
+                    let node = stack
+                        .pop()
+                        .ok_or(EInvoiceError::Invalid("unbalanced tags"))?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(node),
+                        None => return Ok(node),
+                    }
+                }
+                Event::Eof => return Err(EInvoiceError::Invalid("unexpected end of document")),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn child(&self, name: &str) -> Option<&XmlNode> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlNode> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Follows a `/` separated path of element names, e.g. `Party/PartyName/Name`.
+    pub fn find(&self, path: &str) -> Option<&XmlNode> {
+        path.split('/')
+            .try_fold(self, |node, name| node.child(name))
+    }
+
+    /// Every element at the end of the path, e.g. all `InvoiceLine` of `.../InvoiceLine`.
+    pub fn find_all<'a>(&'a self, path: &'a str) -> Vec<&'a XmlNode> {
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => (self.find(parent), name),
+            None => (Some(self), path),
+        };
+
+        parent
+            .map(|parent| parent.children(name).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn text(&self, path: &str) -> Option<String> {
+        self.find(path)
+            .map(|node| node.text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    }
+
+    pub fn number(&self, path: &str) -> Option<f64> {
+        self.text(path).and_then(|text| parse_number(&text))
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+}
+
+/// Parses amounts and rates such as `1234.50`, `10%` or `-5`.
+pub fn parse_number(text: &str) -> Option<f64> {
+    text.trim().trim_end_matches('%').trim().parse::<f64>().ok()
+}
@@ -1,15 +1,20 @@
+use crate::api::account::AccountType;
+use crate::api::debt::DebtKind;
 use crate::api::infer::constants::tools::{
     parse_amount_string, parse_issued_at_string, InvoiceToolRaw, TransactionToolRaw,
 };
 use crate::api::infer::tools::{
     CategoryToolRaw, DiscountToolRaw, PurchasedItemToolRaw, TaxToolRaw,
 };
+use crate::api::invoice::card_last_four;
 use serde::{Deserialize, Serialize};
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum InferMode {
     Text,
     Invoice,
+    InvoiceImage,
+    EInvoice,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -46,6 +51,8 @@ pub struct InvoiceTool {
     pub total: f64,
     pub currency: String,
     pub card_number: Option<i16>,
+    #[serde(default)]
+    pub seller: Option<String>,
 }
 
 impl From<InvoiceToolRaw> for InvoiceTool {
@@ -72,7 +79,8 @@ impl From<InvoiceToolRaw> for InvoiceTool {
             subtotal: raw.subtotal,
             total: raw.total,
             currency: raw.currency,
-            card_number: raw.card_number,
+            card_number: raw.card_number.map(card_last_four),
+            seller: None,
         }
     }
 }
@@ -119,6 +127,16 @@ pub struct TransactionTool {
     pub quantity: f64,
     pub unit: Option<String>,
     pub issued_at: chrono::DateTime<chrono::Utc>,
+    // accounts of a transfer, by kind
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_account: Option<AccountType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_account: Option<AccountType>,
+    // the other person of a debt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debt_kind: Option<DebtKind>,
 }
 
 impl Default for TransactionTool {
@@ -132,6 +150,10 @@ impl Default for TransactionTool {
             quantity: 1.0,
             unit: None,
             issued_at: chrono::Utc::now(),
+            from_account: None,
+            to_account: None,
+            counterparty: None,
+            debt_kind: None,
         }
     }
 }
@@ -146,6 +168,13 @@ impl From<TransactionToolRaw> for TransactionTool {
             quantity: raw.quantity.unwrap_or(1.0),
             unit: raw.unit,
             issued_at: parse_issued_at_string(now, raw.date),
+            from_account: raw.from_account.and_then(|v| v.parse().ok()),
+            to_account: raw.to_account.and_then(|v| v.parse().ok()),
+            counterparty: raw
+                .counterparty
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
+            debt_kind: raw.debt.and_then(|v| v.parse().ok()),
             ..Default::default()
         }
     }
@@ -25,6 +25,8 @@ pub type InferServiceFactoryDyn = Arc<dyn InferServiceFactoryExt + Send + Sync>;
 pub struct InferServiceFactory {
     pub text_infer_service: InferServiceDyn,
     pub invoice_infer_service: InferServiceDyn,
+    pub invoice_image_infer_service: InferServiceDyn,
+    pub einvoice_infer_service: InferServiceDyn,
 }
 
 impl InferServiceFactoryExt for InferServiceFactory {
@@ -32,6 +34,8 @@ impl InferServiceFactoryExt for InferServiceFactory {
         match provider {
             InferMode::Text => self.text_infer_service.clone(),
             InferMode::Invoice => self.invoice_infer_service.clone(),
+            InferMode::InvoiceImage => self.invoice_image_infer_service.clone(),
+            InferMode::EInvoice => self.einvoice_infer_service.clone(),
         }
     }
 }
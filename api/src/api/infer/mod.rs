@@ -1,11 +1,13 @@
 mod constants;
 mod dto;
+mod einvoice;
 mod infer_model;
 mod infer_service;
 mod services;
 
 pub(crate) use constants::*;
 pub(crate) use dto::*;
+pub(crate) use einvoice::*;
 pub use infer_service::*;
 pub use services::*;
 
@@ -0,0 +1,36 @@
+use crate::api::infer::models::*;
+use crate::api::infer::{parse_einvoice, InferOptions, InferServiceExt, InvoiceInferService};
+use crate::api::invoice::InvoiceError;
+use crate::api::message::MessageEvent;
+use crate::common::errors::AppError;
+use async_trait::async_trait;
+
+/// Reads structured e-invoice xml directly, only the categories are inferred.
+#[derive(Clone)]
+pub struct EInvoiceInferService {
+    pub invoice_infer_service: InvoiceInferService,
+}
+
+#[async_trait]
+impl InferServiceExt for EInvoiceInferService {
+    async fn infer(
+        &self,
+        prompt: String,
+        options: InferOptions,
+    ) -> Result<(InvoiceTool, String), AppError> {
+        let mut invoice_tool =
+            parse_einvoice(&prompt).map_err(|e| InvoiceError::InvalidEInvoice(e.to_string()))?;
+        options.emit(MessageEvent::Extracted(invoice_tool.clone()));
+
+        self.invoice_infer_service
+            .categorize(&mut invoice_tool, &options)
+            .await?;
+        options.emit(MessageEvent::Categorized(invoice_tool.clone()));
+
+        // there is no model completion, the parsed invoice is kept instead
+        let completion =
+            serde_json::to_string(&invoice_tool).map_err(|e| AppError::Unknown(e.into()))?;
+
+        Ok((invoice_tool, completion))
+    }
+}
@@ -0,0 +1,82 @@
+use crate::api::infer::models::*;
+use crate::api::infer::tools::{make_infer_invoice_tool, InvoiceToolRaw};
+use crate::api::infer::{InferOptions, InferServiceExt, InvoiceInferService};
+use crate::api::message::MessageEvent;
+use crate::common::errors::AppError;
+use crate::services::llm::LLMServiceDyn;
+use anyhow::anyhow;
+use async_trait::async_trait;
+
+const INVOICE_IMAGE_PROMPT: &str =
+    "Extract the purchased items, discounts, taxes and totals from the receipt in the image.";
+
+/// Any text already read off the image is passed along as a hint.
+fn image_prompt(text: &str) -> String {
+    if text.is_empty() {
+        INVOICE_IMAGE_PROMPT.to_string()
+    } else {
+        format!("{INVOICE_IMAGE_PROMPT}\nText read from the receipt:\n{text}")
+    }
+}
+
+/// Reads the invoice straight from the image with a multimodal model instead of OCR text.
+#[derive(Clone)]
+pub struct InvoiceImageInferService {
+    pub llm_service: LLMServiceDyn,
+    pub invoice_infer_service: InvoiceInferService,
+}
+
+#[async_trait]
+impl InferServiceExt for InvoiceImageInferService {
+    async fn infer(
+        &self,
+        prompt: String,
+        options: InferOptions,
+    ) -> Result<(InvoiceTool, String), AppError> {
+        if options.images.is_empty() {
+            return Err(AppError::Unknown(anyhow!("no image")));
+        }
+
+        let prompt = image_prompt(&prompt);
+        let fn_obj = make_infer_invoice_tool(options.currencies.clone());
+        let (contents, completion) = self
+            .llm_service
+            .chat_with_fn_and_images(prompt, options.images.clone(), fn_obj)
+            .await?;
+
+        let invoice_tool = contents
+            .first()
+            .cloned()
+            .ok_or(AppError::Unknown(anyhow!("no invoice")))?;
+
+        let mut invoice_tool: InvoiceTool = serde_json::from_str::<InvoiceToolRaw>(&invoice_tool)
+            .map_err(|e| AppError::Unknown(e.into()))?
+            .into();
+
+        if invoice_tool.transactions.is_empty() {
+            return Err(AppError::Unknown(anyhow!("no transactions")));
+        }
+        options.emit(MessageEvent::Extracted(invoice_tool.clone()));
+
+        self.invoice_infer_service
+            .categorize(&mut invoice_tool, &options)
+            .await?;
+        options.emit(MessageEvent::Categorized(invoice_tool.clone()));
+
+        Ok((invoice_tool, completion))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_prompt() {
+        assert_eq!(image_prompt(""), INVOICE_IMAGE_PROMPT);
+
+        let prompt = image_prompt("Banh mi 25000\nTra da 5000");
+        assert!(prompt.starts_with(INVOICE_IMAGE_PROMPT));
+        assert!(prompt.ends_with("Text read from the receipt:\nBanh mi 25000\nTra da 5000"));
+    }
+}
@@ -4,6 +4,8 @@ use crate::api::infer::tools::{
     make_infer_category_tool, make_infer_invoice_tool, CategoryToolRaw, InvoiceToolRaw,
 };
 use crate::api::infer::{InferOptions, InferServiceExt};
+use crate::api::invoice::redact_text;
+use crate::api::message::MessageEvent;
 use crate::common::errors::AppError;
 use crate::services::llm::LLMServiceDyn;
 use anyhow::anyhow;
@@ -40,6 +42,35 @@ impl InvoiceInferService {
         Ok(args)
     }
 
+    /// Assigns the category of the first purchased item to every transaction of the invoice.
+    pub(crate) async fn categorize(
+        &self,
+        invoice_tool: &mut InvoiceTool,
+        options: &InferOptions,
+    ) -> Result<(), AppError> {
+        let tx_title = invoice_tool
+            .transactions
+            .first()
+            .cloned()
+            .map(|tx| redact_text(&tx.title))
+            .unwrap_or_default();
+
+        let category_tool = self
+            .infer_categories(tx_title, options.categories.clone())
+            .await?
+            .first()
+            .cloned()
+            .unwrap_or_default();
+
+        for tx in invoice_tool.transactions.iter_mut() {
+            let category_tool = category_tool.clone();
+            tx.category_id = category_tool.category_id;
+            tx.r#type = category_tool.r#type;
+        }
+
+        Ok(())
+    }
+
     async fn infer_invoice(
         &self,
         prompt: String,
@@ -72,31 +103,17 @@ impl InferServiceExt for InvoiceInferService {
             return Err(AppError::Unknown(anyhow!("empty prompt")));
         }
 
-        let (mut invoice_tool, completion) = self.infer_invoice(prompt, options.currencies).await?;
+        let (mut invoice_tool, completion) = self
+            .infer_invoice(prompt, options.currencies.clone())
+            .await?;
 
         if invoice_tool.transactions.is_empty() {
             return Err(AppError::Unknown(anyhow!("no transactions")));
         }
+        options.emit(MessageEvent::Extracted(invoice_tool.clone()));
 
-        let tx_title = invoice_tool
-            .transactions
-            .first()
-            .cloned()
-            .map(|tx| tx.title)
-            .unwrap_or_default();
-
-        let category_tool = self
-            .infer_categories(tx_title, options.categories)
-            .await?
-            .first()
-            .cloned()
-            .unwrap_or_default();
-
-        for tx in invoice_tool.transactions.iter_mut() {
-            let category_tool = category_tool.clone();
-            tx.category_id = category_tool.category_id;
-            tx.r#type = category_tool.r#type;
-        }
+        self.categorize(&mut invoice_tool, &options).await?;
+        options.emit(MessageEvent::Categorized(invoice_tool.clone()));
 
         Ok((invoice_tool, completion))
     }
@@ -1,5 +1,9 @@
+mod einvoice_infer_service;
+mod invoice_image_infer_service;
 mod invoice_infer_service;
 mod text_infer_service;
 
+pub use einvoice_infer_service::*;
+pub use invoice_image_infer_service::*;
 pub use invoice_infer_service::*;
 pub use text_infer_service::*;
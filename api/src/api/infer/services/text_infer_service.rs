@@ -8,6 +8,7 @@ use crate::api::infer::tools::{
     make_infer_category_tool, make_infer_transaction_tool, CategoryToolRaw, TransactionToolRaw,
 };
 use crate::api::infer::{InferOptions, InferServiceExt};
+use crate::api::message::MessageEvent;
 use crate::common::errors::AppError;
 use crate::services::llm::LLMServiceDyn;
 
@@ -72,6 +73,7 @@ impl TextInferService {
             card_number: None,
             discounts: vec![],
             taxes: vec![],
+            seller: None,
         };
 
         Ok((invoice_tool, completion))
@@ -86,8 +88,14 @@ impl InferServiceExt for TextInferService {
         options: InferOptions,
     ) -> Result<(InvoiceTool, String), AppError> {
         let ((invoice_tool, completion), category_tools) = tokio::try_join!(
-            self.infer_transactions(prompt.clone(), options.currencies),
-            self.infer_categories(prompt, options.categories)
+            async {
+                let result = self
+                    .infer_transactions(prompt.clone(), options.currencies.clone())
+                    .await?;
+                options.emit(MessageEvent::Extracted(result.0.clone()));
+                Ok(result)
+            },
+            self.infer_categories(prompt.clone(), options.categories.clone())
         )?;
 
         let transaction_tools = invoice_tool.transactions;
@@ -108,8 +116,17 @@ impl InferServiceExt for TextInferService {
                     quantity: tx.quantity,
                     unit: tx.unit,
                     issued_at: tx.issued_at,
+                    from_account: tx.from_account,
+                    to_account: tx.to_account,
                     category_id: category.category_id,
-                    r#type: category.r#type,
+                    // the transaction tool is the one told who owes whom
+                    r#type: if tx.debt_kind.is_some() {
+                        "debt".to_string()
+                    } else {
+                        category.r#type
+                    },
+                    counterparty: tx.counterparty,
+                    debt_kind: tx.debt_kind,
                 },
             })
             .collect::<Vec<TransactionTool>>();
@@ -118,6 +135,7 @@ impl InferServiceExt for TextInferService {
             transactions: transaction_tools,
             ..invoice_tool
         };
+        options.emit(MessageEvent::Categorized(invoice_tool.clone()));
 
         Ok((invoice_tool, completion))
     }
@@ -12,6 +12,20 @@ pub enum InvoiceError {
     UnsupportedContentType,
     #[error("no attachment")]
     NoAttachment,
+    #[error("at most {0} attachments are allowed")]
+    TooManyAttachments(usize),
+    #[error("{0}")]
+    InvalidEInvoice(String),
+    #[error("invalid image: {0}")]
+    InvalidImage(String),
+    #[error("unsupported image {0}, send a jpeg, png, webp or gif")]
+    UnsupportedImage(String),
+    #[error("invalid totals: {0}")]
+    InvalidTotals(String),
+    #[error("invalid currency code")]
+    InvalidCurrencyCode,
+    #[error("files larger than {0} bytes are not allowed")]
+    FileTooLarge(usize),
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -22,6 +36,13 @@ impl IntoResponse for InvoiceError {
             Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
             Self::UnsupportedContentType => (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string()),
             Self::NoAttachment => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::TooManyAttachments(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::InvalidEInvoice(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::InvalidImage(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::UnsupportedImage(_) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string()),
+            Self::InvalidTotals(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::InvalidCurrencyCode => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::FileTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
             Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
         };
 
@@ -1,4 +1,4 @@
-use crate::api::invoice::{Discount, Tax};
+use crate::api::invoice::{Discount, Media, Tax};
 use bson::oid::ObjectId;
 
 pub struct CreateInvoiceData {
@@ -11,8 +11,10 @@ pub struct CreateInvoiceData {
     pub total: f64,
     pub currency: String,
     pub card_number: Option<i16>,
-    pub media_path: Option<String>,
-    pub media_type: Option<String>,
+    pub seller: Option<String>,
+    pub media: Vec<Media>,
+    pub fingerprint: Option<String>,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
 }
 
 pub struct CreateInvoiceInput {
@@ -25,7 +27,7 @@ pub struct CreateInvoiceInput {
     pub total: f64,
     pub currency: String,
     pub card_number: Option<i16>,
-    pub media_path: Option<String>,
-    pub media_type: Option<String>,
+    pub seller: Option<String>,
+    pub media: Vec<Media>,
     pub issued_at: chrono::DateTime<chrono::Utc>,
 }
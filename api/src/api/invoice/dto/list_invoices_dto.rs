@@ -0,0 +1,50 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::common::mongo::Cursor;
+
+pub struct ListInvoicesData {
+    pub user_id: ObjectId,
+    pub cursor: Option<Cursor>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub currency: Option<String>,
+    pub seller: Option<String>,
+}
+
+pub struct ListInvoicesInput {
+    pub user_id: ObjectId,
+    pub cursor: Option<Cursor>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub currency: Option<String>,
+    pub seller: Option<String>,
+}
+
+impl From<ListInvoicesInput> for ListInvoicesData {
+    fn from(value: ListInvoicesInput) -> Self {
+        Self {
+            user_id: value.user_id,
+            cursor: value.cursor,
+            from: value.from,
+            to: value.to,
+            currency: value.currency,
+            seller: value.seller,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ListInvoicesQuery {
+    /// Id of the last invoice of the previous page
+    pub after: Option<String>,
+    pub limit: Option<i64>,
+    /// Issued at or after
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Issued before
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub currency: Option<String>,
+    /// Part of the seller name, case insensitive
+    pub seller: Option<String>,
+}
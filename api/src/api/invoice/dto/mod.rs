@@ -1,7 +1,15 @@
 mod create_invoice_dto;
+mod list_invoices_dto;
 mod presign_get_dto;
+mod replace_invoice_dto;
+mod update_invoice_dto;
 mod upload_image_dto;
+mod upload_slot_dto;
 
 pub use create_invoice_dto::*;
+pub use list_invoices_dto::*;
 pub use presign_get_dto::*;
+pub use replace_invoice_dto::*;
+pub use update_invoice_dto::*;
 pub use upload_image_dto::*;
+pub use upload_slot_dto::*;
@@ -1,8 +1,17 @@
 use serde::Serialize;
+#[allow(unused_imports)]
+use serde_json::json;
 use utoipa::ToSchema;
 
 #[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
 pub struct PresignGetPayload {
     #[schema(example = "https://example.com/presigned/image.jpg")]
     pub url: String,
+    /// One url per stored file, in upload order
+    #[schema(example = json!(["https://example.com/presigned/0.jpg", "https://example.com/presigned/1.jpg"]))]
+    pub urls: Vec<String>,
+    /// Preview of each file for the chat list, the file itself when it has no thumbnail
+    #[schema(example = json!(["https://example.com/presigned/0.thumb.jpg", "https://example.com/presigned/1.thumb.jpg"]))]
+    pub thumbnail_urls: Vec<String>,
 }
@@ -0,0 +1,30 @@
+use bson::oid::ObjectId;
+
+use crate::api::invoice::{Discount, Tax};
+
+pub struct ReplaceInvoiceData {
+    pub id: ObjectId,
+    pub taxes: Vec<Tax>,
+    pub discounts: Vec<Discount>,
+    pub subtotal: Option<f64>,
+    pub total: f64,
+    pub currency: String,
+    pub card_number: Option<i16>,
+    pub seller: Option<String>,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    pub fingerprint: Option<String>,
+}
+
+/// Extracted fields of an invoice read again from its receipt.
+#[derive(Clone)]
+pub struct ReplaceInvoiceInput {
+    pub id: ObjectId,
+    pub taxes: Vec<Tax>,
+    pub discounts: Vec<Discount>,
+    pub subtotal: Option<f64>,
+    pub total: f64,
+    pub currency: String,
+    pub card_number: Option<i16>,
+    pub seller: Option<String>,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
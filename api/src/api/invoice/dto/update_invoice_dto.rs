@@ -0,0 +1,67 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::invoice::{Discount, Invoice, Tax};
+use crate::api::transaction::Transaction;
+
+pub struct UpdateInvoiceData {
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub taxes: Vec<Tax>,
+    pub discounts: Vec<Discount>,
+    pub subtotal: Option<f64>,
+    pub total: f64,
+    pub currency: String,
+    pub card_number: Option<i16>,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    pub fingerprint: Option<String>,
+    pub edited_fields: Vec<String>,
+}
+
+pub struct UpdateInvoiceInput {
+    pub id: ObjectId,
+    pub user_id: ObjectId,
+    pub taxes: Option<Vec<Tax>>,
+    pub discounts: Option<Vec<Discount>>,
+    pub subtotal: Option<f64>,
+    pub total: Option<f64>,
+    pub currency: Option<String>,
+    pub card_number: Option<i16>,
+    pub issued_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInvoiceBody {
+    #[schema(example = json!([{"amount": 8.0, "rate": 8.0}]))]
+    pub taxes: Option<Vec<Tax>>,
+    #[schema(example = json!([]))]
+    pub discounts: Option<Vec<Discount>>,
+    #[schema(example = 100.0)]
+    #[validate(range(min = 0.0))]
+    pub subtotal: Option<f64>,
+    #[schema(example = 108.0)]
+    #[validate(range(min = 0.0))]
+    pub total: Option<f64>,
+    #[schema(example = "USD")]
+    #[validate(length(equal = 3))]
+    pub currency: Option<String>,
+    #[schema(example = 8432)]
+    #[validate(range(min = 0, max = 9999))]
+    pub card_number: Option<i16>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub issued_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// An invoice with its line items.
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceDetailPayload {
+    #[serde(flatten)]
+    pub invoice: Invoice,
+    pub transactions: Vec<Transaction>,
+}
@@ -1,3 +1,5 @@
+use crate::api::invoice::Media;
+use crate::services::llm::ChatImage;
 use bson::oid::ObjectId;
 use serde::Deserialize;
 use utoipa::ToSchema;
@@ -5,17 +7,34 @@ use utoipa::ToSchema;
 #[allow(unused)]
 #[derive(Deserialize, ToSchema)]
 pub struct UploadImageBody {
+    /// Receipt photos (`image/*`) in reading order, or a single pdf invoice (`application/pdf`)
+    #[schema(value_type = Vec<String>, format = Binary)]
+    pub file: Vec<Vec<u8>>,
+}
+
+#[allow(unused)]
+#[derive(Deserialize, ToSchema)]
+pub struct ImportInvoiceBody {
+    /// UBL 2.1, UN/CEFACT CII (ZUGFeRD, Factur-X) or Vietnam GDT e-invoice xml
     #[schema(value_type = String, format = Binary)]
     pub file: Vec<u8>,
 }
 
-pub struct UploadImageInput {
-    pub user_id: ObjectId,
+pub struct ImageFile {
+    pub content: Vec<u8>,
     pub content_type: String,
 }
 
+pub struct UploadImageInput {
+    // the user, or the workspace the upload is made in
+    pub owner_id: ObjectId,
+    pub files: Vec<ImageFile>,
+}
+
 pub struct UploadedImage {
-    pub path: String,
-    pub content_type: String,
+    pub media: Vec<Media>,
+    // text read by OCR or embedded in a pdf, stitched across all files
     pub content: String,
+    // pages sent to the model instead of OCR
+    pub images: Vec<ChatImage>,
 }
@@ -0,0 +1,46 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+use validator::Validate;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUploadBody {
+    /// `image/*` for a receipt photo or `application/pdf`
+    #[schema(example = "image/jpeg")]
+    pub content_type: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSlotPayload {
+    /// Object key to send to `/invoices/process` once the file is uploaded
+    #[schema(
+        example = "66990b1947d76ec3781adc9d/uploads/ae7441fd-1515-4f78-85c9-cbafa7149301.jpg"
+    )]
+    pub key: String,
+    /// Presigned url, the file is sent with a `PUT` request
+    #[schema(example = "https://example.com/presigned/image.jpg")]
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessUploadBody {
+    /// Keys of the uploaded photos in reading order, or of a single pdf
+    #[validate(length(min = 1))]
+    #[schema(example = json!(["66990b1947d76ec3781adc9d/uploads/ae7441fd-1515-4f78-85c9-cbafa7149301.jpg"]))]
+    pub keys: Vec<String>,
+}
+
+pub struct CreateUploadInput {
+    pub user_id: ObjectId,
+    pub content_type: String,
+}
+
+pub struct ReadUploadsInput {
+    pub user_id: ObjectId,
+    pub keys: Vec<String>,
+}
@@ -1,111 +1,424 @@
 use std::io;
 
 use anyhow::anyhow;
-use axum::extract::{Multipart, Path, State};
+use axum::extract::{Multipart, Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::sse::{Event, Sse};
 use axum::{Extension, Json};
-use futures::TryStreamExt;
+use bson::oid::ObjectId;
+use futures::future::try_join_all;
+use futures::{Stream, TryStreamExt};
 use tokio::io::BufWriter;
+use tokio::sync::mpsc;
 use tokio_util::io::StreamReader;
 use utoipa::OpenApi;
 
-use crate::api::infer::models::InferMode;
-use crate::api::infer::InferOptions;
 use crate::api::invoice::{
-    Discount, Invoice, InvoiceError, PresignGetPayload, Tax, UploadImageBody, UploadImageInput,
-    UploadedImage,
+    create_einvoice_message, create_invoice_message, is_receipt_content_type, CreateUploadBody,
+    CreateUploadInput, Discount, ImageFile, ImportInvoiceBody, Invoice, InvoiceDetailPayload,
+    InvoiceError, ListInvoicesInput, ListInvoicesQuery, Media, PresignGetPayload,
+    ProcessUploadBody, ReadUploadsInput, Tax, UpdateInvoiceBody, UpdateInvoiceInput,
+    UploadImageBody, UploadImageInput, UploadSlotPayload,
 };
-use crate::api::message::{CreateMessageInput, Message};
+use crate::api::job::{CreateJobInput, Job, JobError, JobPayload, JobStatus};
+use crate::api::message::{message_event_stream, Message, MessageEvent, MessageEventSender};
 use crate::api::state::AppState;
+use crate::api::transaction::Transaction;
 use crate::api::user::User;
+use crate::api::workspace::Scope;
 use crate::common::errors::AppError;
+use crate::common::hooks::ValidJson;
+use crate::common::mongo::{Cursor, FindOptions};
 use crate::macros::object_id;
+use crate::services::pdf::PDF_CONTENT_TYPE;
+
+// photos of a single long receipt
+pub const MAX_UPLOAD_FILES: usize = 3;
+
+#[utoipa::path(
+    get,
+    path = "",
+    params(
+        ListInvoicesQuery,
+    ),
+    responses(
+        (status = 200, description = "List invoices successfully", body = [Invoice]),
+    )
+)]
+pub async fn list_invoices(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Query(query): Query<ListInvoicesQuery>,
+) -> Result<Json<Vec<Invoice>>, AppError> {
+    let invoices = state
+        .invoice_service
+        .list(
+            ListInvoicesInput {
+                user_id: scope.owner_id,
+                cursor: query.after.map(Cursor::try_from).transpose()?,
+                from: query.from,
+                to: query.to,
+                currency: query.currency,
+                seller: query.seller,
+            },
+            FindOptions {
+                limit: query.limit,
+                skip: None,
+            },
+        )
+        .await?;
+
+    Ok(Json(invoices))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{invoice_id}",
+    responses(
+        (status = 200, description = "Get invoice successfully", body = InvoiceDetailPayload),
+    ),
+    params(
+        ("invoice_id" = String, Path, description = "Invoice database id"),
+    )
+)]
+pub async fn get_invoice(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(invoice_id): Path<String>,
+) -> Result<Json<InvoiceDetailPayload>, AppError> {
+    let invoice = state
+        .invoice_service
+        .find_by_id(object_id!(&invoice_id))
+        .await?
+        .filter(|invoice| invoice.user_id == scope.owner_id.to_hex())
+        .ok_or(InvoiceError::NotFound)?;
+
+    let transactions = state
+        .transaction_service
+        .find_by_invoice_id(object_id!(&invoice.id))
+        .await?;
+
+    Ok(Json(InvoiceDetailPayload {
+        invoice,
+        transactions,
+    }))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/{invoice_id}",
+    request_body = UpdateInvoiceBody,
+    responses(
+        (status = 200, description = "Update invoice successfully", body = Invoice),
+        (status = 422, description = "Totals do not add up"),
+    ),
+    params(
+        ("invoice_id" = String, Path, description = "Invoice database id"),
+    )
+)]
+pub async fn update_invoice(
+    State(state): State<AppState>,
+    Extension(scope): Extension<Scope>,
+    Path(invoice_id): Path<String>,
+    ValidJson(body): ValidJson<UpdateInvoiceBody>,
+) -> Result<Json<Invoice>, AppError> {
+    let invoice = state
+        .invoice_service
+        .update_by_id(UpdateInvoiceInput {
+            id: object_id!(&invoice_id),
+            user_id: scope.owner_id,
+            taxes: body.taxes,
+            discounts: body.discounts,
+            subtotal: body.subtotal,
+            total: body.total,
+            currency: body.currency,
+            card_number: body.card_number,
+            issued_at: body.issued_at,
+        })
+        .await?
+        .ok_or(InvoiceError::NotFound)?;
+
+    Ok(Json(invoice))
+}
 
 #[utoipa::path(
     post,
     path = "/upload",
     request_body(content = UploadImageBody, content_type = "multipart/form-data"),
     responses(
-        (status = 200, description = "Upload successfully", body = [Message]),
+        (status = 202, description = "Upload successfully, the invoice is processed in the background", body = Job),
     )
 )]
 pub async fn upload_invoice(
     State(state): State<AppState>,
     Extension(user): Extension<User>,
-    mut multipart: Multipart,
-) -> Result<Json<Vec<Message>>, AppError> {
-    let invoice_service = state.invoice_service;
-    let infer_service = state
-        .infer_service_factory
-        .create_service(InferMode::Invoice);
-    let message_service = state.message_service;
+    Extension(scope): Extension<Scope>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<Job>), AppError> {
+    let files = read_images(multipart).await?;
+    let user_id = object_id!(&user.id);
 
-    let field = multipart
-        .next_field()
-        .await
-        .map_err(|e| AppError::Unknown(anyhow!(e)))?;
+    let media = state
+        .invoice_service
+        .store_images(UploadImageInput {
+            owner_id: scope.owner_id,
+            files,
+        })
+        .await?;
 
-    let Some(field) = field else {
-        return Err(InvoiceError::NoAttachment.into());
-    };
+    let job = state
+        .job_service
+        .enqueue(CreateJobInput {
+            user_id,
+            workspace_id: scope.workspace_id,
+            payload: JobPayload::ProcessInvoice { media },
+        })
+        .await?;
 
-    let content_type = field
-        .content_type()
-        .ok_or::<AppError>(InvoiceError::UnsupportedContentType.into())?
-        .to_string();
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
 
-    if !content_type.starts_with("image/") {
-        return Err(InvoiceError::UnsupportedContentType.into());
-    }
+#[utoipa::path(
+    post,
+    path = "/uploads",
+    request_body = CreateUploadBody,
+    responses(
+        (status = 200, description = "Create upload slot successfully, the file is sent to the url with a PUT request", body = UploadSlotPayload),
+    )
+)]
+pub async fn create_upload(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Json(body): Json<CreateUploadBody>,
+) -> Result<Json<UploadSlotPayload>, AppError> {
+    let slot = state
+        .invoice_service
+        .create_upload(CreateUploadInput {
+            user_id: object_id!(&user.id),
+            content_type: body.content_type,
+        })
+        .await?;
 
-    let body_with_io_error = field.map_err(|err| io::Error::new(io::ErrorKind::Other, err));
-    let body_reader = StreamReader::new(body_with_io_error);
-    futures::pin_mut!(body_reader);
+    Ok(Json(slot))
+}
 
-    let mut file = BufWriter::new(vec![]);
-    tokio::io::copy(&mut body_reader, &mut file)
-        .await
-        .map_err(|e| AppError::Unknown(anyhow!(e)))?;
-
-    let UploadedImage {
-        content,
-        content_type,
-        path,
-    } = invoice_service
-        .upload_image(
-            file.into_inner(),
-            UploadImageInput {
-                user_id: object_id!(&user.id),
-                content_type: content_type.to_string(),
-            },
-        )
-        .await?;
+#[utoipa::path(
+    post,
+    path = "/process",
+    request_body = ProcessUploadBody,
+    responses(
+        (status = 202, description = "The uploaded files are processed in the background", body = Job),
+    )
+)]
+pub async fn process_upload(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
+    ValidJson(body): ValidJson<ProcessUploadBody>,
+) -> Result<(StatusCode, Json<Job>), AppError> {
+    let user_id = object_id!(&user.id);
+    check_files(body.keys.len(), || {
+        body.keys
+            .iter()
+            .any(|key| mime_guess::from_path(key).first_raw() == Some(PDF_CONTENT_TYPE))
+    })?;
 
-    let categories = state.category_service.find().await?;
-    let (invoice_tool, completion) = infer_service
-        .infer(
-            content.clone(),
-            InferOptions {
-                currencies: vec![user.currency.clone()],
-                categories,
-            },
-        )
+    let files = state
+        .invoice_service
+        .read_uploads(ReadUploadsInput {
+            user_id,
+            keys: body.keys,
+        })
+        .await?;
+    let media = state
+        .invoice_service
+        .store_images(UploadImageInput {
+            owner_id: scope.owner_id,
+            files,
+        })
         .await?;
 
-    let messages = message_service
-        .create(CreateMessageInput {
-            prompt: content,
-            currencies: vec![user.currency],
-            user_id: object_id!(&user.id),
-            invoice_tool,
-            completion,
-            media_path: Some(path),
-            media_type: Some(content_type),
+    let job = state
+        .job_service
+        .enqueue(CreateJobInput {
+            user_id,
+            workspace_id: scope.workspace_id,
+            payload: JobPayload::ProcessInvoice { media },
         })
         .await?;
 
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/import",
+    request_body(content = ImportInvoiceBody, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Import e-invoice successfully", body = [Message]),
+    )
+)]
+pub async fn import_invoice(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
+    multipart: Multipart,
+) -> Result<Json<Vec<Message>>, AppError> {
+    let files = read_files(multipart, |content_type| {
+        matches!(content_type, "application/xml" | "text/xml") || content_type.ends_with("+xml")
+    })
+    .await?;
+    let [file] =
+        <[ImageFile; 1]>::try_from(files).map_err(|_| InvoiceError::TooManyAttachments(1))?;
+
+    let messages = create_einvoice_message(&state, user, scope.owner_id, file).await?;
+
     Ok(Json(messages))
 }
 
+#[utoipa::path(
+    get,
+    path = "/jobs/{job_id}",
+    responses(
+        (status = 200, description = "Get job successfully", body = Job),
+    ),
+    params(
+        ("job_id" = String, Path, description = "Job id returned by the upload"),
+    )
+)]
+pub async fn get_job(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(job_id): Path<String>,
+) -> Result<Json<Job>, AppError> {
+    let job = state
+        .job_service
+        .find_by_id(&job_id)
+        .await?
+        .filter(|job| job.user_id == user.id)
+        .ok_or(JobError::NotFound)?;
+
+    Ok(Json(job))
+}
+
+#[utoipa::path(
+    post,
+    path = "/upload/stream",
+    request_body(content = UploadImageBody, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Stream invoice processing events (ocr_done, extracted, categorized, saved, failed)", content_type = "text/event-stream"),
+    )
+)]
+pub async fn upload_invoice_stream(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
+    multipart: Multipart,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, AppError> {
+    let files = read_images(multipart).await?;
+    let (events, receiver) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let event = match process_invoice(&state, user, scope.owner_id, files, Some(events.clone()))
+            .await
+        {
+            Ok(messages) => MessageEvent::Saved(messages),
+            Err(e) => MessageEvent::Failed {
+                message: e.to_string(),
+            },
+        };
+        event.send(Some(&events));
+    });
+
+    Ok(message_event_stream(receiver))
+}
+
+/// Reads every file part of the upload, in order. Several photos of one receipt are accepted,
+/// a pdf has to be uploaded on its own.
+async fn read_images(multipart: Multipart) -> Result<Vec<ImageFile>, AppError> {
+    let files = read_files(multipart, is_receipt_content_type).await?;
+    check_files(files.len(), || {
+        files
+            .iter()
+            .any(|file| file.content_type == PDF_CONTENT_TYPE)
+    })?;
+
+    Ok(files)
+}
+
+fn check_files(count: usize, has_pdf: impl FnOnce() -> bool) -> Result<(), InvoiceError> {
+    if count > MAX_UPLOAD_FILES {
+        return Err(InvoiceError::TooManyAttachments(MAX_UPLOAD_FILES));
+    }
+    if count > 1 && has_pdf() {
+        return Err(InvoiceError::UnsupportedContentType);
+    }
+
+    Ok(())
+}
+
+async fn read_files(
+    mut multipart: Multipart,
+    is_supported: impl Fn(&str) -> bool,
+) -> Result<Vec<ImageFile>, AppError> {
+    let mut files = vec![];
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Unknown(anyhow!(e)))?
+    {
+        let content_type = field
+            .content_type()
+            .ok_or::<AppError>(InvoiceError::UnsupportedContentType.into())?
+            .to_string();
+
+        if !is_supported(&content_type) {
+            return Err(InvoiceError::UnsupportedContentType.into());
+        }
+
+        let body_with_io_error = field.map_err(|err| io::Error::new(io::ErrorKind::Other, err));
+        let body_reader = StreamReader::new(body_with_io_error);
+        futures::pin_mut!(body_reader);
+
+        let mut file = BufWriter::new(vec![]);
+        tokio::io::copy(&mut body_reader, &mut file)
+            .await
+            .map_err(|e| AppError::Unknown(anyhow!(e)))?;
+
+        files.push(ImageFile {
+            content: file.into_inner(),
+            content_type,
+        });
+    }
+
+    if files.is_empty() {
+        return Err(InvoiceError::NoAttachment.into());
+    }
+
+    Ok(files)
+}
+
+async fn process_invoice(
+    state: &AppState,
+    user: User,
+    owner_id: ObjectId,
+    files: Vec<ImageFile>,
+    events: Option<MessageEventSender>,
+) -> Result<Vec<Message>, AppError> {
+    let image = state
+        .invoice_service
+        .upload_images(UploadImageInput { owner_id, files })
+        .await?;
+    if !image.content.is_empty() {
+        MessageEvent::OcrDone {
+            content: image.content.clone(),
+        }
+        .send(events.as_ref());
+    }
+
+    create_invoice_message(state, user, owner_id, image, events).await
+}
+
 #[utoipa::path(
     get,
     path = "/presigned",
@@ -118,7 +431,7 @@ pub async fn upload_invoice(
 )]
 pub async fn presigned(
     State(state): State<AppState>,
-    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
     Path(invoice_id): Path<String>,
 ) -> Result<Json<PresignGetPayload>, AppError> {
     let invoice = state
@@ -127,32 +440,69 @@ pub async fn presigned(
         .await?
         .ok_or(InvoiceError::NotFound)?;
 
-    if invoice.user_id != user.id {
+    if invoice.user_id != scope.owner_id.to_hex() {
         return Err(InvoiceError::NotFound.into());
     }
 
-    let path = invoice
-        .media_path
-        .ok_or(InvoiceError::NoAttachment)?
-        .replace("invoices/", "");
-    let presigned_post = state.r2_service.presign_get(path).await?;
+    if invoice.media.is_empty() {
+        return Err(InvoiceError::NoAttachment.into());
+    }
+
+    let prefix = format!("{}/", state.settings.invoice.bucket);
+    let presign = |path: &str| {
+        let key = path.strip_prefix(&prefix).unwrap_or(path).to_string();
+        state.r2_service.presign_get(key)
+    };
+    let (urls, thumbnail_urls) = tokio::try_join!(
+        try_join_all(invoice.media.iter().map(|media| presign(&media.path))),
+        try_join_all(
+            invoice
+                .media
+                .iter()
+                .map(|media| { presign(media.thumbnail_path.as_deref().unwrap_or(&media.path)) })
+        ),
+    )?;
 
     Ok(Json(PresignGetPayload {
-        url: presigned_post,
+        url: urls[0].clone(),
+        urls,
+        thumbnail_urls,
     }))
 }
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(upload_invoice, presigned),
+    paths(
+        list_invoices,
+        get_invoice,
+        update_invoice,
+        upload_invoice,
+        upload_invoice_stream,
+        create_upload,
+        process_upload,
+        import_invoice,
+        get_job,
+        presigned
+    ),
     components(
         schemas(
             UploadImageBody,
+            ImportInvoiceBody,
+            CreateUploadBody,
+            UploadSlotPayload,
+            ProcessUploadBody,
             Message,
+            Job,
+            JobPayload,
+            JobStatus,
             Tax,
             Discount,
             Invoice,
+            Media,
             PresignGetPayload,
+            InvoiceDetailPayload,
+            UpdateInvoiceBody,
+            Transaction,
         )
     ),
     tags(
@@ -1,6 +1,7 @@
 use crate::api::infer::models::{DiscountTool, TaxTool};
 use bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -42,6 +43,29 @@ impl From<DiscountTool> for Discount {
     }
 }
 
+/// A stored image or document of an invoice.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Media {
+    #[schema(
+        example = "invoices/66990b1947d76ec3781adc9d/ae7441fd-1515-4f78-85c9-cbafa7149301/0.jpg"
+    )]
+    pub path: String,
+    #[schema(example = "image/jpeg")]
+    pub content_type: String,
+    /// Small jpeg preview of a photo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(
+        example = "invoices/66990b1947d76ec3781adc9d/ae7441fd-1515-4f78-85c9-cbafa7149301/0.thumb.jpg"
+    )]
+    pub thumbnail_path: Option<String>,
+    /// Perceptual hash of a photo, in hex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "f0e4c2d8a1b3c5e7")]
+    pub hash: Option<String>,
+}
+
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct InvoiceEntity {
@@ -55,10 +79,45 @@ pub struct InvoiceEntity {
     pub total: f64,
     pub currency: String,
     pub card_number: Option<i16>,
+    #[serde(default)]
+    pub seller: Option<String>,
+    #[serde(default)]
+    pub media: Vec<Media>,
+    // total, currency, date and card, to find the same receipt uploaded twice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+    // not stored on invoices created before it, `createdAt` is used instead
+    #[serde(default)]
+    #[serde_as(as = "Option<bson::DateTime>")]
+    pub issued_at: Option<chrono::DateTime<chrono::Utc>>,
+    // changed by the user, kept when the receipt is extracted again
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edited_fields: Vec<String>,
+    // single attachment of invoices stored before `media`, only read
+    #[serde(default, skip_serializing)]
     pub media_path: Option<String>,
+    #[serde(default, skip_serializing)]
     pub media_type: Option<String>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
+
+impl InvoiceEntity {
+    pub fn media(&self) -> Vec<Media> {
+        if !self.media.is_empty() {
+            return self.media.clone();
+        }
+
+        match (&self.media_path, &self.media_type) {
+            (Some(path), Some(content_type)) => vec![Media {
+                path: path.clone(),
+                content_type: content_type.clone(),
+                thumbnail_path: None,
+                hash: None,
+            }],
+            _ => vec![],
+        }
+    }
+}
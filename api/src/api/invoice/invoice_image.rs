@@ -0,0 +1,277 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+
+use crate::api::invoice::{ImageFile, InvoiceError, Region};
+
+pub const JPEG_CONTENT_TYPE: &str = "image/jpeg";
+
+// thumbnails are only shown small in the chat list
+const THUMBNAIL_QUALITY: u8 = 70;
+
+/// A photo ready to be stored and read, with the preview shown in the chat list.
+pub struct PreparedFile {
+    pub file: ImageFile,
+    pub thumbnail: Option<Vec<u8>>,
+    pub hash: Option<u64>,
+}
+
+/// Prepares one file of an upload. Other files than photos are kept as they are, a photo
+/// the decoder does not read, e.g. heic, is refused: stored as is, it would keep its EXIF
+/// metadata and GPS location.
+pub fn prepare_file(
+    file: ImageFile,
+    max_side: u32,
+    thumbnail_side: u32,
+    quality: u8,
+) -> Result<PreparedFile, InvoiceError> {
+    if !file.content_type.starts_with("image/") {
+        return Ok(PreparedFile {
+            file,
+            thumbnail: None,
+            hash: None,
+        });
+    }
+
+    prepare_image(&file, max_side, thumbnail_side, quality)
+        .map_err(|_| InvoiceError::UnsupportedImage(file.content_type))
+}
+
+/// Turns the photo upright, downscales it so its longest side fits `max_side` and
+/// re-encodes it as jpeg. Re-encoding drops the EXIF metadata, including the GPS location.
+pub fn prepare_image(
+    file: &ImageFile,
+    max_side: u32,
+    thumbnail_side: u32,
+    quality: u8,
+) -> Result<PreparedFile, InvoiceError> {
+    let image = decode_upright(&file.content)?;
+    let image = if image.width().max(image.height()) > max_side {
+        image.resize(max_side, max_side, FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let thumbnail = encode_jpeg(
+        &image.thumbnail(thumbnail_side, thumbnail_side),
+        THUMBNAIL_QUALITY,
+    )?;
+
+    Ok(PreparedFile {
+        file: ImageFile {
+            content: encode_jpeg(&image, quality)?,
+            content_type: JPEG_CONTENT_TYPE.to_string(),
+        },
+        thumbnail: Some(thumbnail),
+        hash: Some(dhash(&image)),
+    })
+}
+
+/// Blurs the regions of a photo and makes its thumbnail again from the blurred photo.
+pub fn redact_image(
+    prepared: &PreparedFile,
+    regions: &[Region],
+    thumbnail_side: u32,
+    quality: u8,
+) -> Result<PreparedFile, InvoiceError> {
+    let mut image = image::load_from_memory(&prepared.file.content)
+        .map_err(|e| InvoiceError::InvalidImage(e.to_string()))?;
+
+    for region in regions {
+        let x = region.x.min(image.width());
+        let y = region.y.min(image.height());
+        let width = region.width.min(image.width() - x);
+        let height = region.height.min(image.height() - y);
+        if width == 0 || height == 0 {
+            continue;
+        }
+
+        // strong enough that no digit can be read back
+        let sigma = (height as f32 / 2.0).max(4.0);
+        let blurred = image.crop_imm(x, y, width, height).blur(sigma);
+        image::imageops::replace(&mut image, &blurred, x as i64, y as i64);
+    }
+
+    let thumbnail = encode_jpeg(
+        &image.thumbnail(thumbnail_side, thumbnail_side),
+        THUMBNAIL_QUALITY,
+    )?;
+
+    Ok(PreparedFile {
+        file: ImageFile {
+            content: encode_jpeg(&image, quality)?,
+            content_type: JPEG_CONTENT_TYPE.to_string(),
+        },
+        thumbnail: Some(thumbnail),
+        hash: prepared.hash,
+    })
+}
+
+/// Difference hash: each bit tells whether a pixel of a 9x8 grayscale copy is brighter
+/// than its right neighbour. Re-taken or re-compressed photos of a receipt differ by
+/// only a few bits.
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let bit = small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | bit as u64;
+        }
+    }
+
+    hash
+}
+
+fn decode_upright(content: &[u8]) -> Result<DynamicImage, InvoiceError> {
+    let mut decoder = ImageReader::new(Cursor::new(content))
+        .with_guessed_format()
+        .map_err(|e| InvoiceError::InvalidImage(e.to_string()))?
+        .into_decoder()
+        .map_err(|e| InvoiceError::InvalidImage(e.to_string()))?;
+    let orientation = decoder
+        .orientation()
+        .map_err(|e| InvoiceError::InvalidImage(e.to_string()))?;
+
+    let mut image = DynamicImage::from_decoder(decoder)
+        .map_err(|e| InvoiceError::InvalidImage(e.to_string()))?;
+    image.apply_orientation(orientation);
+
+    Ok(image)
+}
+
+fn encode_jpeg(image: &DynamicImage, quality: u8) -> Result<Vec<u8>, InvoiceError> {
+    let mut content = vec![];
+    // jpeg has no alpha channel
+    image
+        .to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut content, quality))
+        .map_err(|e| InvoiceError::InvalidImage(e.to_string()))?;
+
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageFormat, RgbImage};
+
+    use super::*;
+
+    fn png(width: u32, height: u32) -> ImageFile {
+        let mut content = Cursor::new(vec![]);
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+            .write_to(&mut content, ImageFormat::Png)
+            .unwrap();
+
+        ImageFile {
+            content: content.into_inner(),
+            content_type: "image/png".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_prepare_image() {
+        let prepared = prepare_image(&png(600, 300), 400, 40, 85).unwrap();
+        let image = image::load_from_memory(&prepared.file.content).unwrap();
+        let thumbnail = image::load_from_memory(&prepared.thumbnail.unwrap()).unwrap();
+
+        assert_eq!(prepared.file.content_type, JPEG_CONTENT_TYPE);
+        assert_eq!((image.width(), image.height()), (400, 200));
+        assert_eq!((thumbnail.width(), thumbnail.height()), (40, 20));
+
+        let prepared = prepare_image(&png(300, 200), 400, 40, 85).unwrap();
+        let image = image::load_from_memory(&prepared.file.content).unwrap();
+        assert_eq!((image.width(), image.height()), (300, 200));
+    }
+
+    #[test]
+    fn test_dhash() {
+        let gradient = RgbImage::from_fn(90, 80, |x, _| {
+            let v = 255 - (x * 255 / 89) as u8;
+            image::Rgb([v, v, v])
+        });
+        let image = DynamicImage::ImageRgb8(gradient);
+
+        // brighter on the left, every pixel is brighter than its right neighbour
+        assert_eq!(dhash(&image), u64::MAX);
+        assert_eq!(dhash(&image.resize(45, 40, FilterType::Triangle)), u64::MAX);
+        assert_eq!(dhash(&DynamicImage::ImageRgb8(RgbImage::new(90, 80))), 0);
+    }
+
+    #[test]
+    fn test_redact_image() {
+        let checkered = RgbImage::from_fn(100, 100, |x, y| {
+            let v = if (x / 2 + y / 2) % 2 == 0 { 255 } else { 0 };
+            image::Rgb([v, v, v])
+        });
+        let mut content = Cursor::new(vec![]);
+        DynamicImage::ImageRgb8(checkered)
+            .write_to(&mut content, ImageFormat::Png)
+            .unwrap();
+        let prepared = PreparedFile {
+            file: ImageFile {
+                content: content.into_inner(),
+                content_type: "image/png".to_string(),
+            },
+            thumbnail: None,
+            hash: Some(1),
+        };
+        let region = Region {
+            x: 0,
+            y: 0,
+            width: 50,
+            height: 120,
+        };
+
+        let redacted = redact_image(&prepared, &[region], 40, 95).unwrap();
+        let image = image::load_from_memory(&redacted.file.content)
+            .unwrap()
+            .to_luma8();
+
+        // the blurred half is an even gray, the other half keeps its pattern
+        let spread = |range: std::ops::Range<u32>| {
+            let values = range.map(|x| image.get_pixel(x, 50)[0]).collect::<Vec<_>>();
+            values.iter().max().unwrap() - values.iter().min().unwrap()
+        };
+        assert!(spread(10..40) < 64);
+        assert!(spread(60..90) > 128);
+        assert_eq!(redacted.hash, Some(1));
+        assert!(redacted.thumbnail.is_some());
+    }
+
+    #[test]
+    fn test_prepare_image_invalid() {
+        let file = ImageFile {
+            content: b"not an image".to_vec(),
+            content_type: "image/heic".to_string(),
+        };
+
+        assert!(prepare_image(&file, 2000, 200, 85).is_err());
+    }
+
+    #[test]
+    fn test_prepare_file() {
+        let pdf = ImageFile {
+            content: b"%PDF-1.7".to_vec(),
+            content_type: "application/pdf".to_string(),
+        };
+        let prepared = prepare_file(pdf, 2000, 200, 85).unwrap();
+        assert_eq!(prepared.file.content, b"%PDF-1.7");
+        assert!(prepared.thumbnail.is_none());
+
+        let prepared = prepare_file(png(30, 20), 2000, 200, 85).unwrap();
+        assert_eq!(prepared.file.content_type, JPEG_CONTENT_TYPE);
+
+        let heic = ImageFile {
+            content: b"....ftypheic".to_vec(),
+            content_type: "image/heic".to_string(),
+        };
+        assert!(matches!(
+            prepare_file(heic, 2000, 200, 85),
+            Err(InvoiceError::UnsupportedImage(content_type)) if content_type == "image/heic"
+        ));
+    }
+}
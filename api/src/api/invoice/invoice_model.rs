@@ -1,4 +1,4 @@
-use crate::api::invoice::invoice_entity::{Discount, InvoiceEntity, Tax};
+use crate::api::invoice::invoice_entity::{Discount, InvoiceEntity, Media, Tax};
 use serde::{Deserialize, Serialize};
 #[allow(unused_imports)]
 use serde_json::json;
@@ -25,12 +25,14 @@ pub struct Invoice {
     pub currency: String,
     #[schema(example = 8432)]
     pub card_number: Option<i16>,
-    #[schema(
-        example = "/invoices/ae7441fd-1515-4f78-85c9-cbafa7149301/ae7441fd-1515-4f78-85c9-cbafa7149301.jpg"
-    )]
-    pub media_path: Option<String>,
-    #[schema(example = "image/jpeg")]
-    pub media_type: Option<String>,
+    #[schema(example = "Hosting GmbH")]
+    pub seller: Option<String>,
+    pub media: Vec<Media>,
+    /// Fields changed by the user
+    #[schema(example = json!(["total"]))]
+    pub edited_fields: Vec<String>,
+    #[schema(example = "2024-07-22T13:30:42.246014Z")]
+    pub issued_at: chrono::DateTime<chrono::Utc>,
     #[schema(example = "2024-07-22T13:30:42.246014Z")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[schema(example = "2024-07-22T13:30:42.246014Z")]
@@ -40,6 +42,7 @@ pub struct Invoice {
 impl From<InvoiceEntity> for Invoice {
     fn from(value: InvoiceEntity) -> Self {
         Self {
+            media: value.media(),
             id: value.id.to_hex(),
             user_id: value.user_id.to_hex(),
             message_id: value.message_id.to_hex(),
@@ -48,9 +51,10 @@ impl From<InvoiceEntity> for Invoice {
             subtotal: value.subtotal,
             total: value.total,
             currency: value.currency,
-            media_path: value.media_path,
-            media_type: value.media_type,
             card_number: value.card_number,
+            seller: value.seller,
+            edited_fields: value.edited_fields,
+            issued_at: value.issued_at.unwrap_or(value.created_at),
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
@@ -0,0 +1,119 @@
+use bson::oid::ObjectId;
+
+use crate::api::infer::models::InferMode;
+use crate::api::infer::InferOptions;
+use crate::api::invoice::{ImageFile, InvoiceError, UploadImageInput, UploadedImage};
+use crate::api::message::{CreateMessageInput, Message, MessageEventSender};
+use crate::api::state::AppState;
+use crate::api::user::User;
+use crate::common::errors::AppError;
+
+/// Extracts the invoice from the uploaded images, either from their OCR text or from the
+/// images themselves, and saves it as a message in the ledger of the owner, the user or a
+/// workspace.
+pub async fn create_invoice_message(
+    state: &AppState,
+    user: User,
+    owner_id: ObjectId,
+    image: UploadedImage,
+    events: Option<MessageEventSender>,
+) -> Result<Vec<Message>, AppError> {
+    let mode = if image.images.is_empty() {
+        InferMode::Invoice
+    } else {
+        InferMode::InvoiceImage
+    };
+    let infer_service = state.infer_service_factory.create_service(mode);
+
+    let categories = state.category_service.find().await?;
+    let (invoice_tool, completion) = infer_service
+        .infer(
+            image.content.clone(),
+            InferOptions {
+                currencies: vec![user.currency.clone()],
+                categories,
+                events,
+                images: image.images,
+            },
+        )
+        .await?;
+
+    state
+        .message_service
+        .create(CreateMessageInput {
+            prompt: image.content,
+            currencies: vec![user.currency],
+            user_id: owner_id,
+            language: user.language,
+            invoice_tool,
+            completion,
+            media: image.media,
+        })
+        .await
+}
+
+/// Saves an e-invoice as a message. The xml is parsed as is, only the categories are inferred.
+/// The file is stored once it is read, an invalid e-invoice leaves nothing behind.
+pub async fn create_einvoice_message(
+    state: &AppState,
+    user: User,
+    owner_id: ObjectId,
+    file: ImageFile,
+) -> Result<Vec<Message>, AppError> {
+    let xml = String::from_utf8(file.content.clone())
+        .map_err(|e| InvoiceError::InvalidEInvoice(e.to_string()))?;
+    let infer_service = state
+        .infer_service_factory
+        .create_service(InferMode::EInvoice);
+
+    let categories = state.category_service.find().await?;
+    let (invoice_tool, completion) = infer_service
+        .infer(
+            xml,
+            InferOptions {
+                currencies: vec![user.currency.clone()],
+                categories,
+                events: None,
+                images: vec![],
+            },
+        )
+        .await?;
+
+    // the user message reads like a receipt instead of holding the raw xml
+    let prompt = invoice_tool
+        .seller
+        .iter()
+        .cloned()
+        .chain(
+            invoice_tool
+                .transactions
+                .iter()
+                .map(|tx| format!("{} ... {}", tx.title, tx.amount)),
+        )
+        .chain([format!(
+            "Total ... {} {}",
+            invoice_tool.total, invoice_tool.currency
+        )])
+        .collect::<Vec<_>>()
+        .join("\n");
+    let media = state
+        .invoice_service
+        .store_images(UploadImageInput {
+            owner_id,
+            files: vec![file],
+        })
+        .await?;
+
+    state
+        .message_service
+        .create(CreateMessageInput {
+            prompt,
+            currencies: vec![user.currency],
+            user_id: owner_id,
+            language: user.language,
+            invoice_tool,
+            completion,
+            media,
+        })
+        .await
+}
@@ -0,0 +1,231 @@
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::services::gcp::vision::types::TextAnnotation;
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b").unwrap());
+// 13 to 19 digits, grouped by spaces or dashes
+static PAN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d(?:[ -]?\d){12,18}\b").unwrap());
+// an international or a local number, amounts do not start with a zero
+static PHONE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:\+\d{1,3}[ .-]?|\b0|\(0)(?:[ .)-]{0,2}\d){8,11}\b").unwrap());
+static DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d{2}[./-]\d{2}[./-]\d{4}").unwrap());
+
+// blurred margin around a word, in pixels
+const REGION_PADDING: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SensitiveKind {
+    Email,
+    Pan,
+    Phone,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SensitiveMatch {
+    pub range: Range<usize>,
+    pub kind: SensitiveKind,
+}
+
+/// A rectangle of the image to blur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Finds emails, card numbers and phone numbers in the text, without overlaps and in order.
+pub fn find_sensitive(text: &str) -> Vec<SensitiveMatch> {
+    let mut matches: Vec<SensitiveMatch> = vec![];
+    let patterns = [
+        (&*EMAIL, SensitiveKind::Email),
+        (&*PAN, SensitiveKind::Pan),
+        (&*PHONE, SensitiveKind::Phone),
+    ];
+    for (pattern, kind) in patterns {
+        for found in pattern.find_iter(text) {
+            // `0`1.07.2024 12 looks like a phone number
+            if kind == SensitiveKind::Phone && DATE.is_match(found.as_str()) {
+                continue;
+            }
+            let range = found.range();
+            if matches
+                .iter()
+                .any(|other| other.range.start < range.end && range.start < other.range.end)
+            {
+                continue;
+            }
+            matches.push(SensitiveMatch { range, kind });
+        }
+    }
+    matches.sort_by_key(|m| m.range.start);
+
+    matches
+}
+
+/// Masks the sensitive data of the text sent to the model. Card numbers keep their last
+/// 4 digits, which are stored with the invoice.
+pub fn redact_text(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut end = 0;
+    for found in find_sensitive(text) {
+        redacted.push_str(&text[end..found.range.start]);
+        let value = &text[found.range.clone()];
+        match found.kind {
+            SensitiveKind::Email => redacted.push_str("[email]"),
+            SensitiveKind::Phone => redacted.push_str("[phone]"),
+            SensitiveKind::Pan => redacted.push_str(&mask_pan(value)),
+        }
+        end = found.range.end;
+    }
+    redacted.push_str(&text[end..]);
+
+    redacted
+}
+
+fn mask_pan(value: &str) -> String {
+    let digits = value.chars().filter(|c| c.is_ascii_digit()).count();
+    let mut seen = 0;
+
+    value
+        .chars()
+        .map(|c| {
+            if !c.is_ascii_digit() {
+                return c;
+            }
+            seen += 1;
+            if seen > digits.saturating_sub(4) {
+                c
+            } else {
+                '*'
+            }
+        })
+        .collect()
+}
+
+/// Boxes of the words that are part of sensitive data. A card number is often read as
+/// several words, so the words are matched joined in reading order.
+pub fn sensitive_regions(words: &[TextAnnotation]) -> Vec<Region> {
+    let mut text = String::new();
+    let mut ranges = vec![];
+    for word in words {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        let start = text.len();
+        text.push_str(word.description.trim());
+        ranges.push(start..text.len());
+    }
+
+    let matches = find_sensitive(&text);
+    words
+        .iter()
+        .zip(ranges)
+        .filter(|(_, range)| {
+            matches
+                .iter()
+                .any(|m| m.range.start < range.end && range.start < m.range.end)
+        })
+        .filter_map(|(word, _)| to_region(word))
+        .collect()
+}
+
+fn to_region(word: &TextAnnotation) -> Option<Region> {
+    let vertices = &word.bounding_poly.vertices;
+    let left = vertices.iter().map(|v| v.x).min()?.max(0) as u32;
+    let top = vertices.iter().map(|v| v.y).min()?.max(0) as u32;
+    let right = vertices.iter().map(|v| v.x).max()?.max(0) as u32;
+    let bottom = vertices.iter().map(|v| v.y).max()?.max(0) as u32;
+
+    let x = left.saturating_sub(REGION_PADDING);
+    let y = top.saturating_sub(REGION_PADDING);
+    Some(Region {
+        x,
+        y,
+        width: right + REGION_PADDING - x,
+        height: bottom + REGION_PADDING - y,
+    })
+}
+
+/// Only the last 4 digits of a card are kept.
+pub fn card_last_four(card_number: i16) -> i16 {
+    (card_number.unsigned_abs() % 10000) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::gcp::vision::types::{BoundingPoly, Vertex};
+
+    use super::*;
+
+    fn word(text: &str, x: i32) -> TextAnnotation {
+        TextAnnotation {
+            description: text.to_string(),
+            bounding_poly: BoundingPoly {
+                vertices: vec![
+                    Vertex { x, y: 10 },
+                    Vertex { x: x + 40, y: 10 },
+                    Vertex { x: x + 40, y: 30 },
+                    Vertex { x, y: 30 },
+                ],
+            },
+        }
+    }
+
+    #[test]
+    fn test_redact_text() {
+        assert_eq!(
+            redact_text("VISA 4111 1111 1111 1234\nTotal ... 150.000"),
+            "VISA **** **** **** 1234\nTotal ... 150.000"
+        );
+        assert_eq!(
+            redact_text("Hotline: 0912 345 678, mail cskh@shop.vn"),
+            "Hotline: [phone], mail [email]"
+        );
+        assert_eq!(redact_text("Tel (028) 3823-4567"), "Tel [phone]");
+        assert_eq!(redact_text("+84 912.345.678"), "[phone]");
+        // amounts, dates and short numbers are kept
+        assert_eq!(
+            redact_text("01.07.2024 12:30 ... 1.500.000\nBill 000123"),
+            "01.07.2024 12:30 ... 1.500.000\nBill 000123"
+        );
+    }
+
+    #[test]
+    fn test_sensitive_regions() {
+        let words = vec![
+            word("Card", 0),
+            word("4111", 50),
+            word("1111", 100),
+            word("1111", 150),
+            word("1234", 200),
+            word("Total", 0),
+            word("5.00", 300),
+        ];
+
+        let regions = sensitive_regions(&words);
+
+        assert_eq!(regions.len(), 4);
+        assert_eq!(
+            regions[0],
+            Region {
+                x: 46,
+                y: 6,
+                width: 48,
+                height: 28,
+            }
+        );
+    }
+
+    #[test]
+    fn test_card_last_four() {
+        assert_eq!(card_last_four(1234), 1234);
+        assert_eq!(card_last_four(21234), 1234);
+        assert_eq!(card_last_four(-1234), 1234);
+    }
+}
@@ -1,8 +1,11 @@
 use crate::api::invoice::constants::InvoiceError;
 use crate::api::invoice::*;
+use crate::common::mongo::FindOptions;
 use async_trait::async_trait;
 use bson::oid::ObjectId;
 use bson::{doc, Document};
+use futures::StreamExt;
+use mongodb::options::ReturnDocument;
 use mongodb::{ClientSession, Collection};
 use std::sync::Arc;
 
@@ -10,6 +13,26 @@ use std::sync::Arc;
 pub trait InvoiceRepoExt: Send + Sync {
     async fn find_one(&self, filter: Document) -> Result<Option<InvoiceEntity>, InvoiceError>;
     async fn find_by_id(&self, id: ObjectId) -> Result<Option<InvoiceEntity>, InvoiceError>;
+    async fn find(
+        &self,
+        filter: Document,
+        options: FindOptions,
+    ) -> Result<Vec<InvoiceEntity>, InvoiceError>;
+    async fn list(
+        &self,
+        data: ListInvoicesData,
+        options: FindOptions,
+    ) -> Result<Vec<InvoiceEntity>, InvoiceError>;
+    async fn update_by_id_with_session(
+        &self,
+        data: &UpdateInvoiceData,
+        session: &mut ClientSession,
+    ) -> Result<Option<InvoiceEntity>, InvoiceError>;
+    async fn replace_with_session(
+        &self,
+        data: ReplaceInvoiceData,
+        session: &mut ClientSession,
+    ) -> Result<Option<InvoiceEntity>, InvoiceError>;
     async fn insert_one_with_session(
         &self,
         data: CreateInvoiceData,
@@ -20,6 +43,13 @@ pub trait InvoiceRepoExt: Send + Sync {
         id: ObjectId,
         session: &mut ClientSession,
     ) -> Result<bool, InvoiceError>;
+    async fn change_owner_with_session(
+        &self,
+        ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, InvoiceError>;
 }
 
 pub type InvoiceRepoDyn = Arc<dyn InvoiceRepoExt + Send + Sync>;
@@ -29,6 +59,10 @@ pub struct InvoiceRepo {
     pub collection: Collection<InvoiceEntity>,
 }
 
+impl InvoiceRepo {
+    const DEFAULT_LIMIT: i64 = 20;
+}
+
 #[async_trait]
 impl InvoiceRepoExt for InvoiceRepo {
     async fn find_one(&self, filter: Document) -> Result<Option<InvoiceEntity>, InvoiceError> {
@@ -45,6 +79,134 @@ impl InvoiceRepoExt for InvoiceRepo {
             .map_err(|e| InvoiceError::Unknown(e.into()))
     }
 
+    async fn find(
+        &self,
+        filter: Document,
+        options: FindOptions,
+    ) -> Result<Vec<InvoiceEntity>, InvoiceError> {
+        let mut cursor = self
+            .collection
+            .find(filter)
+            .sort(doc! { "_id": -1 })
+            .limit(options.limit.unwrap_or(Self::DEFAULT_LIMIT))
+            .skip(options.skip.unwrap_or_default())
+            .await
+            .map_err(|e| InvoiceError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+
+    async fn list(
+        &self,
+        data: ListInvoicesData,
+        options: FindOptions,
+    ) -> Result<Vec<InvoiceEntity>, InvoiceError> {
+        let mut filter = doc! { "userId": data.user_id };
+        if let Some(cursor) = data.cursor {
+            let id: ObjectId = cursor.into();
+            filter.insert("_id", doc! { "$lt": id });
+        }
+        if let Some(currency) = data.currency {
+            filter.insert("currency", currency.to_uppercase());
+        }
+        if let Some(seller) = data.seller {
+            filter.insert(
+                "seller",
+                doc! { "$regex": regex::escape(&seller), "$options": "i" },
+            );
+        }
+        let mut issued_at = doc! {};
+        if let Some(from) = data.from {
+            issued_at.insert("$gte", from);
+        }
+        if let Some(to) = data.to {
+            issued_at.insert("$lt", to);
+        }
+        if !issued_at.is_empty() {
+            // invoices stored before `issuedAt` are dated by their creation
+            filter.insert(
+                "$or",
+                vec![
+                    doc! { "issuedAt": issued_at.clone() },
+                    doc! { "issuedAt": null, "createdAt": issued_at },
+                ],
+            );
+        }
+
+        self.find(filter, options).await
+    }
+
+    async fn update_by_id_with_session(
+        &self,
+        data: &UpdateInvoiceData,
+        session: &mut ClientSession,
+    ) -> Result<Option<InvoiceEntity>, InvoiceError> {
+        let taxes = bson::to_bson(&data.taxes).map_err(|e| InvoiceError::Unknown(e.into()))?;
+        let discounts =
+            bson::to_bson(&data.discounts).map_err(|e| InvoiceError::Unknown(e.into()))?;
+
+        self.collection
+            .find_one_and_update(
+                doc! { "_id": data.id, "userId": data.user_id },
+                doc! {
+                    "$set": {
+                        "taxes": taxes,
+                        "discounts": discounts,
+                        "subtotal": data.subtotal,
+                        "total": data.total,
+                        "currency": &data.currency,
+                        "cardNumber": data.card_number.map(i32::from),
+                        "issuedAt": data.issued_at,
+                        "fingerprint": &data.fingerprint,
+                        "updatedAt": chrono::Utc::now(),
+                    },
+                    "$addToSet": { "editedFields": { "$each": &data.edited_fields } },
+                },
+            )
+            .return_document(ReturnDocument::After)
+            .session(session)
+            .await
+            .map_err(|e| InvoiceError::Unknown(e.into()))
+    }
+
+    async fn replace_with_session(
+        &self,
+        data: ReplaceInvoiceData,
+        session: &mut ClientSession,
+    ) -> Result<Option<InvoiceEntity>, InvoiceError> {
+        let taxes = bson::to_bson(&data.taxes).map_err(|e| InvoiceError::Unknown(e.into()))?;
+        let discounts =
+            bson::to_bson(&data.discounts).map_err(|e| InvoiceError::Unknown(e.into()))?;
+
+        self.collection
+            .find_one_and_update(
+                doc! { "_id": data.id },
+                doc! {
+                    "$set": {
+                        "taxes": taxes,
+                        "discounts": discounts,
+                        "subtotal": data.subtotal,
+                        "total": data.total,
+                        "currency": data.currency,
+                        "cardNumber": data.card_number.map(i32::from),
+                        "seller": data.seller,
+                        "issuedAt": data.issued_at,
+                        "fingerprint": data.fingerprint,
+                        "updatedAt": chrono::Utc::now(),
+                    }
+                },
+            )
+            .session(session)
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| InvoiceError::Unknown(e.into()))
+    }
+
     async fn insert_one_with_session(
         &self,
         data: CreateInvoiceData,
@@ -60,8 +222,13 @@ impl InvoiceRepoExt for InvoiceRepo {
             total: data.total,
             currency: data.currency,
             card_number: data.card_number,
-            media_path: data.media_path,
-            media_type: data.media_type,
+            seller: data.seller,
+            media: data.media,
+            fingerprint: data.fingerprint,
+            issued_at: Some(data.issued_at),
+            edited_fields: vec![],
+            media_path: None,
+            media_type: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -87,4 +254,22 @@ impl InvoiceRepoExt for InvoiceRepo {
             .map(|v| v.deleted_count > 0)
             .map_err(|e| InvoiceError::Unknown(e.into()))
     }
+
+    async fn change_owner_with_session(
+        &self,
+        ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, InvoiceError> {
+        self.collection
+            .update_many(
+                doc! { "_id": { "$in": ids }, "userId": from },
+                doc! { "$set": { "userId": to, "updatedAt": chrono::Utc::now() } },
+            )
+            .session(session)
+            .await
+            .map(|v| v.modified_count)
+            .map_err(|e| InvoiceError::Unknown(e.into()))
+    }
 }
@@ -9,16 +9,30 @@ use crate::mw::authorization_mw;
 
 // 1MB
 const MAX_FILE_SIZE: usize = 1024 * 1024;
+const MAX_UPLOAD_SIZE: usize = MAX_FILE_SIZE * MAX_UPLOAD_FILES;
 
 pub struct InvoiceRouter(Router<AppState>);
 
 impl InvoiceRouter {
     pub fn new(state: AppState) -> Self {
         let routes = Router::new()
+            .route("/", get(list_invoices))
+            .route("/:invoice_id", get(get_invoice).patch(update_invoice))
             .route(
                 "/upload",
-                post(upload_invoice).layer(DefaultBodyLimit::max(MAX_FILE_SIZE)),
+                post(upload_invoice).layer(DefaultBodyLimit::max(MAX_UPLOAD_SIZE)),
             )
+            .route(
+                "/upload/stream",
+                post(upload_invoice_stream).layer(DefaultBodyLimit::max(MAX_UPLOAD_SIZE)),
+            )
+            .route("/uploads", post(create_upload))
+            .route("/process", post(process_upload))
+            .route(
+                "/import",
+                post(import_invoice).layer(DefaultBodyLimit::max(MAX_FILE_SIZE)),
+            )
+            .route("/jobs/:job_id", get(get_job))
             .route("/:invoice_id/presigned", get(presigned))
             .layer(from_fn_with_state(state.clone(), authorization_mw));
 
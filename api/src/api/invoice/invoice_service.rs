@@ -5,35 +5,86 @@ use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use bson::doc;
 use bson::oid::ObjectId;
-use mime2ext::mime2ext;
-use mongodb::ClientSession;
-use uuid::Uuid;
+use futures::future::try_join_all;
+use futures::FutureExt;
+use mongodb::{Client, ClientSession};
 
+use crate::api::asset::validate_currency_code;
+use crate::api::duplicate::invoice_fingerprint;
 use crate::api::invoice::*;
+use crate::api::transaction::{TransactionServiceDyn, UpdateInvoiceTransactionsData};
 use crate::common::errors::AppError;
+use crate::common::mongo::FindOptions;
 use crate::services::gcp::vision::VisionServiceDyn;
+use crate::services::llm::ChatImage;
+use crate::services::pdf::{PdfPage, PdfServiceDyn, PDF_CONTENT_TYPE};
 use crate::services::r2::R2ServiceDyn;
-use crate::settings::InvoiceConfig;
+use crate::settings::{InvoiceConfig, InvoiceExtraction};
 
 #[async_trait]
 pub trait InvoiceServiceExt: Send + Sync {
     async fn find_by_message_id(&self, message_id: ObjectId) -> Result<Option<Invoice>, AppError>;
     async fn find_by_id(&self, id: ObjectId) -> Result<Option<Invoice>, AppError>;
+    async fn list(
+        &self,
+        input: ListInvoicesInput,
+        options: FindOptions,
+    ) -> Result<Vec<Invoice>, AppError>;
+    async fn update_by_id(&self, input: UpdateInvoiceInput) -> Result<Option<Invoice>, AppError>;
+    async fn find_recent(
+        &self,
+        user_id: ObjectId,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Invoice>, AppError>;
+    async fn find_by_fingerprint(
+        &self,
+        user_id: ObjectId,
+        fingerprint: &str,
+    ) -> Result<Vec<Invoice>, AppError>;
+    async fn find_with_media(
+        &self,
+        user_id: Option<ObjectId>,
+        from: Option<chrono::DateTime<chrono::Utc>>,
+        to: Option<chrono::DateTime<chrono::Utc>>,
+        options: FindOptions,
+    ) -> Result<Vec<Invoice>, AppError>;
+    /// Invoices referring to any of the stored paths, by file or by thumbnail.
+    async fn find_by_media_paths(&self, paths: &[String]) -> Result<Vec<Invoice>, AppError>;
+    async fn find_by_ids(
+        &self,
+        ids: &[ObjectId],
+        user_id: ObjectId,
+    ) -> Result<Vec<Invoice>, AppError>;
     async fn insert_one_with_session(
         &self,
         input: CreateInvoiceInput,
         session: &mut ClientSession,
     ) -> Result<Invoice, AppError>;
-    async fn upload_image(
+    async fn replace_with_session(
         &self,
-        content: Vec<u8>,
-        input: UploadImageInput,
-    ) -> Result<UploadedImage, AppError>;
+        input: ReplaceInvoiceInput,
+        session: &mut ClientSession,
+    ) -> Result<Option<Invoice>, AppError>;
+    async fn upload_images(&self, input: UploadImageInput) -> Result<UploadedImage, AppError>;
+    async fn store_images(&self, input: UploadImageInput) -> Result<Vec<Media>, AppError>;
+    async fn read_images(&self, media: Vec<Media>) -> Result<UploadedImage, AppError>;
+    async fn read_media(&self, media: &Media) -> Result<Vec<u8>, AppError>;
+    /// Presigned url the client uploads a file to, without going through the API.
+    async fn create_upload(&self, input: CreateUploadInput) -> Result<UploadSlotPayload, AppError>;
+    async fn read_uploads(&self, input: ReadUploadsInput) -> Result<Vec<ImageFile>, AppError>;
     async fn delete_by_id_with_session(
         &self,
         id: ObjectId,
         session: &mut ClientSession,
     ) -> Result<bool, AppError>;
+    /// Hands the invoices of a user or workspace to another one.
+    async fn change_owner_with_session(
+        &self,
+        ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError>;
 }
 
 pub type InvoiceServiceDyn = Arc<dyn InvoiceServiceExt + Send + Sync>;
@@ -41,11 +92,254 @@ pub type InvoiceServiceDyn = Arc<dyn InvoiceServiceExt + Send + Sync>;
 #[derive(Clone)]
 pub struct InvoiceService {
     pub repo: InvoiceRepoDyn,
+    pub mongo_client: Client,
+    pub transaction_service: TransactionServiceDyn,
     pub r2_service: R2ServiceDyn,
     pub gcp_vision_service: VisionServiceDyn,
+    pub pdf_service: PdfServiceDyn,
     pub config: InvoiceConfig,
 }
 
+impl InvoiceService {
+    // enough to cover the invoices of the duplicate window
+    const MAX_RECENT: i64 = 200;
+
+    /// Stored paths start with the bucket, keys are relative to it.
+    fn key<'a>(&self, path: &'a str) -> &'a str {
+        path.strip_prefix(&format!("{}/", self.config.bucket))
+            .unwrap_or(path)
+    }
+
+    /// Prepares the photos of an upload before they are stored or read. Other files are kept
+    /// as they are, photos the decoder does not support are refused.
+    async fn prepare_all(&self, files: Vec<ImageFile>) -> Result<Vec<PreparedFile>, AppError> {
+        let config = self.config.clone();
+
+        tokio::task::spawn_blocking(move || {
+            files
+                .into_iter()
+                .map(|file| {
+                    prepare_file(
+                        file,
+                        config.max_image_side,
+                        config.thumbnail_side,
+                        config.jpeg_quality,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await
+        .map_err(|e| AppError::Unknown(e.into()))?
+        .map_err(Into::into)
+    }
+
+    async fn upload_file(&self, path: String, prepared: &PreparedFile) -> Result<Media, AppError> {
+        let file = &prepared.file;
+        let upload = self.r2_service.upload_object(
+            self.config.bucket.clone(),
+            path.clone(),
+            file.content.as_slice(),
+            &file.content_type,
+        );
+
+        let (path, thumbnail_path) = match &prepared.thumbnail {
+            Some(thumbnail) => {
+                let (stem, _) = path.rsplit_once('.').unwrap_or((&path, ""));
+                let (path, thumbnail_path) = tokio::try_join!(
+                    upload,
+                    self.r2_service.upload_object(
+                        self.config.bucket.clone(),
+                        format!("{stem}.thumb.jpg"),
+                        thumbnail.as_slice(),
+                        JPEG_CONTENT_TYPE,
+                    ),
+                )?;
+                (path, Some(thumbnail_path))
+            }
+            None => (upload.await?, None),
+        };
+
+        Ok(Media {
+            path,
+            content_type: file.content_type.clone(),
+            thumbnail_path,
+            hash: prepared.hash.map(|hash| format!("{hash:016x}")),
+        })
+    }
+
+    /// Extracts every file and stitches the text of consecutive photos into one receipt.
+    /// Sensitive data is masked in the text, the regions to blur are returned per file.
+    async fn extract_all(
+        &self,
+        files: impl Iterator<Item = &ImageFile>,
+    ) -> Result<(String, Vec<ChatImage>, Vec<Vec<Region>>), AppError> {
+        let extracted =
+            try_join_all(files.map(|file| self.extract(&file.content, &file.content_type))).await?;
+
+        let mut texts = vec![];
+        let mut images = vec![];
+        let mut regions = vec![];
+        for file in extracted {
+            if !file.text.is_empty() {
+                texts.push(file.text);
+            }
+            images.extend(file.images);
+            regions.push(file.regions);
+        }
+
+        Ok((redact_text(&stitch_texts(&texts)), images, regions))
+    }
+
+    /// Reads the text off the image, or keeps the image itself when it is sent to the model.
+    async fn extract_image(
+        &self,
+        content: &[u8],
+        content_type: &str,
+    ) -> Result<ExtractedFile, AppError> {
+        let encoded = BASE64_STANDARD.encode(content);
+
+        match self.config.extraction {
+            InvoiceExtraction::Ocr => {
+                let detected = self.gcp_vision_service.detect_text(encoded).await?;
+                Ok(ExtractedFile {
+                    regions: sensitive_regions(&detected.words),
+                    text: detected.text,
+                    images: vec![],
+                })
+            }
+            InvoiceExtraction::Vision => {
+                // the model sees the photo itself, so it is blurred like the stored copy
+                let detected = self.gcp_vision_service.detect_text(encoded.clone()).await?;
+                let regions = sensitive_regions(&detected.words);
+                let image = if regions.is_empty() {
+                    ChatImage {
+                        media_type: content_type.to_string(),
+                        data: encoded,
+                    }
+                } else {
+                    let prepared = PreparedFile {
+                        file: ImageFile {
+                            content: content.to_vec(),
+                            content_type: content_type.to_string(),
+                        },
+                        thumbnail: None,
+                        hash: None,
+                    };
+                    let redacted = self
+                        .redact_all(vec![prepared], vec![regions.clone()])
+                        .await?
+                        .remove(0)
+                        .file;
+                    ChatImage {
+                        media_type: redacted.content_type,
+                        data: BASE64_STANDARD.encode(redacted.content),
+                    }
+                };
+
+                Ok(ExtractedFile {
+                    text: String::new(),
+                    images: vec![image],
+                    regions,
+                })
+            }
+        }
+    }
+
+    /// Uses the embedded text of every pdf page, scanned pages are handled like images.
+    async fn extract_pdf(&self, content: &[u8]) -> Result<ExtractedFile, AppError> {
+        let pages = self.pdf_service.read_pages(content.to_vec()).await?;
+
+        let mut texts = vec![];
+        let mut images = vec![];
+        for page in pages {
+            match page {
+                PdfPage::Text(text) => texts.push(text),
+                PdfPage::Scanned(png) => {
+                    let extracted = self.extract_image(&png, "image/png").await?;
+                    if !extracted.text.is_empty() {
+                        texts.push(extracted.text);
+                    }
+                    images.extend(extracted.images);
+                }
+            }
+        }
+
+        // the stored pdf is kept as it is, only its text is masked
+        Ok(ExtractedFile {
+            text: texts.join("\n\n"),
+            images,
+            regions: vec![],
+        })
+    }
+
+    async fn extract(&self, content: &[u8], content_type: &str) -> Result<ExtractedFile, AppError> {
+        if content_type == PDF_CONTENT_TYPE {
+            return self.extract_pdf(content).await;
+        }
+
+        self.extract_image(content, content_type).await
+    }
+
+    /// Blurs the sensitive regions found in the photos, the other files are kept as they are.
+    /// A photo that cannot be blurred fails the upload rather than being kept unredacted.
+    async fn redact_all(
+        &self,
+        files: Vec<PreparedFile>,
+        regions: Vec<Vec<Region>>,
+    ) -> Result<Vec<PreparedFile>, AppError> {
+        let config = self.config.clone();
+
+        tokio::task::spawn_blocking(move || {
+            files
+                .into_iter()
+                .zip(regions)
+                .map(|(prepared, regions)| {
+                    if regions.is_empty() || !prepared.file.content_type.starts_with("image/") {
+                        return Ok(prepared);
+                    }
+
+                    redact_image(
+                        &prepared,
+                        &regions,
+                        config.thumbnail_side,
+                        config.jpeg_quality,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await
+        .map_err(|e| AppError::Unknown(e.into()))?
+        .map_err(Into::into)
+    }
+}
+
+/// What was read off one file.
+struct ExtractedFile {
+    text: String,
+    images: Vec<ChatImage>,
+    regions: Vec<Region>,
+}
+
+/// What the line items of the invoice take from the edit, `None` when the currency and the
+/// date are unchanged. The amounts are kept, an edited currency fixes a misread one.
+fn invoice_transactions_update(
+    invoice: &Invoice,
+    data: &UpdateInvoiceData,
+) -> Option<UpdateInvoiceTransactionsData> {
+    let currency = (data.currency != invoice.currency).then(|| data.currency.clone());
+    let issued_at = (data.issued_at != invoice.issued_at).then_some(data.issued_at);
+    if currency.is_none() && issued_at.is_none() {
+        return None;
+    }
+
+    Some(UpdateInvoiceTransactionsData {
+        invoice_id: data.id,
+        user_id: data.user_id,
+        currency,
+        issued_at,
+    })
+}
+
 #[async_trait]
 impl InvoiceServiceExt for InvoiceService {
     async fn find_by_message_id(&self, message_id: ObjectId) -> Result<Option<Invoice>, AppError> {
@@ -64,11 +358,218 @@ impl InvoiceServiceExt for InvoiceService {
             .map_err(|e| e.into())
     }
 
+    async fn list(
+        &self,
+        input: ListInvoicesInput,
+        options: FindOptions,
+    ) -> Result<Vec<Invoice>, AppError> {
+        self.repo
+            .list(input.into(), options)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn update_by_id(&self, input: UpdateInvoiceInput) -> Result<Option<Invoice>, AppError> {
+        let Some(invoice) = self
+            .repo
+            .find_one(doc! { "_id": input.id, "userId": input.user_id })
+            .await?
+            .map(Invoice::from)
+        else {
+            return Ok(None);
+        };
+
+        let edited_fields = [
+            ("taxes", input.taxes.is_some()),
+            ("discounts", input.discounts.is_some()),
+            ("subtotal", input.subtotal.is_some()),
+            ("total", input.total.is_some()),
+            ("currency", input.currency.is_some()),
+            ("cardNumber", input.card_number.is_some()),
+            ("issuedAt", input.issued_at.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, edited)| *edited)
+        .map(|(field, _)| field.to_string())
+        .collect();
+        let taxes = input.taxes.unwrap_or_else(|| invoice.taxes.clone());
+        let discounts = input.discounts.unwrap_or_else(|| invoice.discounts.clone());
+        let subtotal = input.subtotal.or(invoice.subtotal);
+        let total = input.total.unwrap_or(invoice.total);
+        let currency = match input.currency {
+            Some(currency) => validate_currency_code(&currency.to_uppercase())
+                .ok_or(InvoiceError::InvalidCurrencyCode)?
+                .currency
+                .to_string(),
+            None => invoice.currency.clone(),
+        };
+        let card_number = input
+            .card_number
+            .map(card_last_four)
+            .or(invoice.card_number);
+        let issued_at = input.issued_at.unwrap_or(invoice.issued_at);
+        check_totals(subtotal, &taxes, &discounts, total)?;
+
+        let data = UpdateInvoiceData {
+            id: input.id,
+            user_id: input.user_id,
+            fingerprint: invoice_fingerprint(total, &currency, issued_at, card_number),
+            taxes,
+            discounts,
+            subtotal,
+            total,
+            currency,
+            card_number,
+            issued_at,
+            edited_fields,
+        };
+        // reports and balances read the line items, they move with the invoice
+        let transactions = invoice_transactions_update(&invoice, &data);
+
+        let mut session = self
+            .mongo_client
+            .start_session()
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))?;
+        session
+            .start_transaction()
+            .and_run((&data, &transactions), |session, (data, transactions)| {
+                async move {
+                    let invoice = self
+                        .repo
+                        .update_by_id_with_session(data, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+                    if let Some(transactions) = transactions {
+                        self.transaction_service
+                            .update_by_invoice_id_with_session(transactions, session)
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
+                    }
+
+                    Ok(invoice.map(Into::into))
+                }
+                .boxed()
+            })
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))
+    }
+
+    async fn find_recent(
+        &self,
+        user_id: ObjectId,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Invoice>, AppError> {
+        self.repo
+            .find(
+                doc! { "userId": user_id, "createdAt": { "$gte": since } },
+                FindOptions::with_limit(Self::MAX_RECENT),
+            )
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn find_by_fingerprint(
+        &self,
+        user_id: ObjectId,
+        fingerprint: &str,
+    ) -> Result<Vec<Invoice>, AppError> {
+        self.repo
+            .find(
+                doc! { "userId": user_id, "fingerprint": fingerprint },
+                FindOptions::with_limit(Self::MAX_RECENT),
+            )
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn find_with_media(
+        &self,
+        user_id: Option<ObjectId>,
+        from: Option<chrono::DateTime<chrono::Utc>>,
+        to: Option<chrono::DateTime<chrono::Utc>>,
+        options: FindOptions,
+    ) -> Result<Vec<Invoice>, AppError> {
+        let mut filter = doc! {
+            "$or": [{ "media.0": { "$exists": true } }, { "mediaPath": { "$ne": null } }],
+        };
+        if let Some(user_id) = user_id {
+            filter.insert("userId", user_id);
+        }
+        let mut created_at = doc! {};
+        if let Some(from) = from {
+            created_at.insert("$gte", from);
+        }
+        if let Some(to) = to {
+            created_at.insert("$lt", to);
+        }
+        if !created_at.is_empty() {
+            filter.insert("createdAt", created_at);
+        }
+
+        self.repo
+            .find(filter, options)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn find_by_media_paths(&self, paths: &[String]) -> Result<Vec<Invoice>, AppError> {
+        if paths.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.repo
+            .find(
+                doc! {
+                    "$or": [
+                        { "media.path": { "$in": paths } },
+                        { "media.thumbnailPath": { "$in": paths } },
+                        { "mediaPath": { "$in": paths } },
+                    ],
+                },
+                // each upload has its own folder, a path belongs to one invoice
+                FindOptions::with_limit(paths.len() as i64),
+            )
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn find_by_ids(
+        &self,
+        ids: &[ObjectId],
+        user_id: ObjectId,
+    ) -> Result<Vec<Invoice>, AppError> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.repo
+            .find(
+                doc! { "_id": { "$in": ids }, "userId": user_id },
+                FindOptions::with_limit(ids.len() as i64),
+            )
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
     async fn insert_one_with_session(
         &self,
         input: CreateInvoiceInput,
         session: &mut ClientSession,
     ) -> Result<Invoice, AppError> {
+        let fingerprint = invoice_fingerprint(
+            input.total,
+            &input.currency,
+            input.issued_at,
+            input.card_number,
+        );
+
         self.repo
             .insert_one_with_session(
                 CreateInvoiceData {
@@ -81,8 +582,10 @@ impl InvoiceServiceExt for InvoiceService {
                     total: input.total,
                     currency: input.currency,
                     card_number: input.card_number,
-                    media_path: input.media_path,
-                    media_type: input.media_type,
+                    seller: input.seller,
+                    media: input.media,
+                    fingerprint,
+                    issued_at: input.issued_at,
                 },
                 session,
             )
@@ -91,39 +594,150 @@ impl InvoiceServiceExt for InvoiceService {
             .map_err(|e| e.into())
     }
 
-    async fn upload_image(
+    async fn replace_with_session(
         &self,
-        content: Vec<u8>,
-        input: UploadImageInput,
-    ) -> Result<UploadedImage, AppError> {
-        let invoice_id = Uuid::new_v4().to_string();
-        let extension =
-            mime2ext(&input.content_type).ok_or(InvoiceError::UnsupportedContentType)?;
-        let path = format!("{}/{}.{}", input.user_id, invoice_id, extension);
+        input: ReplaceInvoiceInput,
+        session: &mut ClientSession,
+    ) -> Result<Option<Invoice>, AppError> {
+        let fingerprint = invoice_fingerprint(
+            input.total,
+            &input.currency,
+            input.issued_at,
+            input.card_number,
+        );
 
-        let content = content.as_slice();
-        let encoded = BASE64_STANDARD.encode(content);
+        self.repo
+            .replace_with_session(
+                ReplaceInvoiceData {
+                    id: input.id,
+                    taxes: input.taxes,
+                    discounts: input.discounts,
+                    subtotal: input.subtotal,
+                    total: input.total,
+                    currency: input.currency,
+                    card_number: input.card_number,
+                    seller: input.seller,
+                    issued_at: input.issued_at,
+                    fingerprint,
+                },
+                session,
+            )
+            .await
+            .map(|v| v.map(Into::into))
+            .map_err(Into::into)
+    }
 
-        let (content, path) = tokio::join!(
-            self.gcp_vision_service.detect_text(encoded),
-            self.r2_service.upload_object(
-                self.config.bucket.clone(),
-                path,
-                content,
-                &input.content_type
-            ),
-        );
+    async fn upload_images(&self, input: UploadImageInput) -> Result<UploadedImage, AppError> {
+        let prepared = self.prepare_all(input.files).await?;
+        let paths = make_image_paths(input.owner_id, &prepared)?;
 
-        let path = path.map_err(|e| AppError::from(e))?;
-        let content = content.map_err(|e| AppError::from(e))?;
+        // the photos are only stored once the sensitive regions found by the OCR are blurred
+        let (content, images, regions) = self
+            .extract_all(prepared.iter().map(|prepared| &prepared.file))
+            .await?;
+        let prepared = self.redact_all(prepared, regions).await?;
+        let media = try_join_all(
+            paths
+                .into_iter()
+                .zip(prepared.iter())
+                .map(|(path, file)| self.upload_file(path, file)),
+        )
+        .await?;
 
         Ok(UploadedImage {
-            path,
-            content_type: input.content_type,
+            media,
+            content,
+            images,
+        })
+    }
+
+    async fn store_images(&self, input: UploadImageInput) -> Result<Vec<Media>, AppError> {
+        let prepared = self.prepare_all(input.files).await?;
+        let paths = make_image_paths(input.owner_id, &prepared)?;
+
+        try_join_all(
+            paths
+                .into_iter()
+                .zip(prepared.iter())
+                .map(|(path, file)| self.upload_file(path, file)),
+        )
+        .await
+    }
+
+    async fn read_images(&self, media: Vec<Media>) -> Result<UploadedImage, AppError> {
+        let files = try_join_all(media.iter().map(|media| async {
+            Ok::<_, AppError>(ImageFile {
+                content: self.read_media(media).await?,
+                content_type: media.content_type.clone(),
+            })
+        }))
+        .await?;
+
+        let (content, images, regions) = self.extract_all(files.iter()).await?;
+
+        // stored before they were read, the photos are replaced by their redacted copy
+        let redacted = media
+            .iter()
+            .zip(&regions)
+            .enumerate()
+            .filter(|(_, (media, regions))| {
+                !regions.is_empty() && media.content_type == JPEG_CONTENT_TYPE
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let prepared = files
+            .into_iter()
+            .map(|file| PreparedFile {
+                file,
+                thumbnail: None,
+                hash: None,
+            })
+            .collect();
+        let prepared = self.redact_all(prepared, regions).await?;
+        try_join_all(redacted.into_iter().map(|index| {
+            self.upload_file(self.key(&media[index].path).to_string(), &prepared[index])
+        }))
+        .await?;
+
+        Ok(UploadedImage {
+            media,
             content,
+            images,
         })
     }
 
+    async fn read_media(&self, media: &Media) -> Result<Vec<u8>, AppError> {
+        Ok(self
+            .r2_service
+            .get_object(self.key(&media.path).to_string())
+            .await?)
+    }
+
+    async fn create_upload(&self, input: CreateUploadInput) -> Result<UploadSlotPayload, AppError> {
+        let key = make_upload_key(input.user_id, &input.content_type)?;
+        let url = self.r2_service.presign_post(key.clone()).await?;
+
+        Ok(UploadSlotPayload { key, url })
+    }
+
+    async fn read_uploads(&self, input: ReadUploadsInput) -> Result<Vec<ImageFile>, AppError> {
+        let prefix = upload_prefix(input.user_id);
+        if input.keys.iter().any(|key| !key.starts_with(&prefix)) {
+            return Err(InvoiceError::NotFound.into());
+        }
+
+        try_join_all(input.keys.into_iter().map(|key| async move {
+            // the client wrote the object, its size is checked before it is downloaded
+            let size = self.r2_service.object_size(key.clone()).await?;
+            if size > self.config.max_upload_size as u64 {
+                return Err(InvoiceError::FileTooLarge(self.config.max_upload_size).into());
+            }
+            let content = self.r2_service.get_object(key.clone()).await?;
+            Ok::<_, AppError>(check_upload(&key, content, self.config.max_upload_size)?)
+        }))
+        .await
+    }
+
     async fn delete_by_id_with_session(
         &self,
         id: ObjectId,
@@ -134,4 +748,85 @@ impl InvoiceServiceExt for InvoiceService {
             .await
             .map_err(|e| e.into())
     }
+
+    async fn change_owner_with_session(
+        &self,
+        ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError> {
+        self.repo
+            .change_owner_with_session(ids, from, to, session)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_invoice_transactions_update() {
+        let date = |day| {
+            chrono::Utc
+                .with_ymd_and_hms(2024, 7, day, 12, 0, 0)
+                .unwrap()
+        };
+        let invoice = Invoice {
+            id: "669e5f02b781150b9a578205".to_string(),
+            user_id: "66990b1947d76ec3781adc9d".to_string(),
+            message_id: "669e5f02b781150b9a578203".to_string(),
+            taxes: vec![],
+            discounts: vec![],
+            subtotal: None,
+            total: 80000.0,
+            currency: "USD".to_string(),
+            card_number: None,
+            seller: None,
+            media: vec![],
+            edited_fields: vec![],
+            issued_at: date(22),
+            created_at: date(22),
+            updated_at: date(22),
+        };
+        let edit = |currency: &str, issued_at| UpdateInvoiceData {
+            id: ObjectId::parse_str(&invoice.id).unwrap(),
+            user_id: ObjectId::parse_str(&invoice.user_id).unwrap(),
+            taxes: vec![],
+            discounts: vec![],
+            subtotal: None,
+            total: 80000.0,
+            currency: currency.to_string(),
+            card_number: None,
+            issued_at,
+            fingerprint: None,
+            edited_fields: vec![],
+        };
+
+        assert_eq!(
+            invoice_transactions_update(&invoice, &edit("USD", date(22))),
+            None
+        );
+
+        // the receipt was in dong, the report reads the line items in dong from now on
+        let data = edit("VND", date(22));
+        assert_eq!(
+            invoice_transactions_update(&invoice, &data),
+            Some(UpdateInvoiceTransactionsData {
+                invoice_id: data.id,
+                user_id: data.user_id,
+                currency: Some("VND".to_string()),
+                issued_at: None,
+            })
+        );
+
+        // the line items are reported on the day of the invoice
+        let update = invoice_transactions_update(&invoice, &edit("USD", date(20))).unwrap();
+        assert_eq!(update.currency, None);
+        assert_eq!(update.issued_at, Some(date(20)));
+    }
 }
@@ -0,0 +1,95 @@
+// most lines two photos of the same receipt are expected to share
+const MAX_OVERLAP: usize = 12;
+// lines cut off at the edge of a photo, which never match exactly
+const MAX_CUT_LINES: usize = 2;
+
+fn normalize(line: &str) -> String {
+    line.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Returns how many trailing lines of `previous` were cut off and how many leading lines of
+/// `next` repeat the end of `previous`.
+fn overlap(previous: &[String], next: &[String]) -> (usize, usize) {
+    for size in (1..=MAX_OVERLAP.min(previous.len()).min(next.len())).rev() {
+        for cut_previous in 0..=MAX_CUT_LINES.min(previous.len() - size) {
+            for cut_next in 0..=MAX_CUT_LINES.min(next.len() - size) {
+                let tail = &previous[previous.len() - cut_previous - size..][..size];
+                let head = &next[cut_next..][..size];
+
+                // lines without content, e.g. separators, are not enough to match on
+                if tail == head && tail.iter().any(|line| line.len() >= 3) {
+                    return (cut_previous, cut_next + size);
+                }
+            }
+        }
+    }
+
+    (0, 0)
+}
+
+/// Joins the text of several photos of one receipt, taken top to bottom. Lines repeated at
+/// the top of a photo because they were already on the previous one are dropped.
+pub fn stitch_texts(texts: &[String]) -> String {
+    let mut lines: Vec<&str> = vec![];
+    let mut normalized: Vec<String> = vec![];
+
+    for text in texts {
+        let next = text.lines().collect::<Vec<_>>();
+        let next_normalized = next.iter().map(|line| normalize(line)).collect::<Vec<_>>();
+
+        // lines cut off at the bottom of a photo are on the next one in full
+        let (cut, skip) = overlap(&normalized, &next_normalized);
+        lines.truncate(lines.len() - cut);
+        normalized.truncate(normalized.len() - cut);
+        lines.extend(&next[skip..]);
+        normalized.extend(next_normalized.into_iter().skip(skip));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| text.to_string()).collect()
+    }
+
+    #[test]
+    fn test_stitch_texts() {
+        let stitched = stitch_texts(&texts(&[
+            "CO.OP MART\nMilk ... 32,000\nEggs ... 45,000\nBread ... 20,000",
+            "Eggs ... 45,000\nBread ... 20.000\nRice 5kg ... 120,000\nTotal ... 217,000",
+        ]));
+
+        assert_eq!(
+            stitched,
+            "CO.OP MART\nMilk ... 32,000\nEggs ... 45,000\nBread ... 20,000\nRice 5kg ... 120,000\nTotal ... 217,000"
+        );
+    }
+
+    #[test]
+    fn test_stitch_texts_with_cut_lines() {
+        let stitched = stitch_texts(&texts(&[
+            "Milk ... 32,000\nEggs ... 45,000\nBre",
+            "ad ... 20,000\nEggs ... 45,000\nBread ... 20,000\nTotal ... 97,000",
+        ]));
+
+        assert_eq!(
+            stitched,
+            "Milk ... 32,000\nEggs ... 45,000\nBread ... 20,000\nTotal ... 97,000"
+        );
+    }
+
+    #[test]
+    fn test_stitch_texts_without_overlap() {
+        let stitched = stitch_texts(&texts(&["Milk ... 32,000\n---", "---\nTotal ... 32,000"]));
+
+        assert_eq!(stitched, "Milk ... 32,000\n---\n---\nTotal ... 32,000");
+        assert_eq!(stitch_texts(&[]), "");
+    }
+}
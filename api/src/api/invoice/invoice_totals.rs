@@ -0,0 +1,79 @@
+use crate::api::invoice::{Discount, InvoiceError, Tax};
+
+// rounding on printed receipts, relative to the total
+const TOTAL_TOLERANCE: f64 = 0.005;
+const MIN_TOLERANCE: f64 = 0.01;
+
+/// Checks that the amounts of an edited invoice add up. Taxes may already be included in
+/// the subtotal, as on most VAT receipts, or added on top of it.
+pub fn check_totals(
+    subtotal: Option<f64>,
+    taxes: &[Tax],
+    discounts: &[Discount],
+    total: f64,
+) -> Result<(), InvoiceError> {
+    if total < 0.0 {
+        return Err(InvoiceError::InvalidTotals("total is negative".to_string()));
+    }
+    if taxes.iter().any(|tax| tax.amount < 0.0 || tax.rate < 0.0)
+        || discounts
+            .iter()
+            .any(|discount| discount.amount < 0.0 || discount.rate < 0.0)
+    {
+        return Err(InvoiceError::InvalidTotals(
+            "taxes and discounts must not be negative".to_string(),
+        ));
+    }
+
+    let Some(subtotal) = subtotal else {
+        return Ok(());
+    };
+    let tax = taxes.iter().map(|tax| tax.amount as f64).sum::<f64>();
+    let discount = discounts
+        .iter()
+        .map(|discount| discount.amount as f64)
+        .sum::<f64>();
+
+    let tolerance = (total * TOTAL_TOLERANCE).max(MIN_TOLERANCE);
+    let adds_up = |expected: f64| (expected - total).abs() <= tolerance;
+    if adds_up(subtotal - discount + tax) || adds_up(subtotal - discount) {
+        return Ok(());
+    }
+
+    Err(InvoiceError::InvalidTotals(format!(
+        "subtotal {subtotal} - discounts {discount} + taxes {tax} does not match total {total}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tax(amount: f32) -> Tax {
+        Tax { amount, rate: 8.0 }
+    }
+
+    fn discount(amount: f32) -> Discount {
+        Discount {
+            name: "Member".to_string(),
+            amount,
+            rate: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_check_totals() {
+        // tax on top
+        assert!(check_totals(Some(100.0), &[tax(8.0)], &[discount(10.0)], 98.0).is_ok());
+        // tax included
+        assert!(check_totals(Some(108.0), &[tax(8.0)], &[], 108.0).is_ok());
+        // rounding
+        assert!(check_totals(Some(33.33), &[tax(2.67)], &[], 36.0).is_ok());
+        // nothing to compare with
+        assert!(check_totals(None, &[tax(8.0)], &[], 50.0).is_ok());
+
+        assert!(check_totals(Some(100.0), &[tax(8.0)], &[], 120.0).is_err());
+        assert!(check_totals(Some(100.0), &[tax(-8.0)], &[], 92.0).is_err());
+        assert!(check_totals(None, &[], &[], -1.0).is_err());
+    }
+}
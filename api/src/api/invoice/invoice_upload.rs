@@ -0,0 +1,169 @@
+use bson::oid::ObjectId;
+use mime2ext::mime2ext;
+use uuid::Uuid;
+
+use crate::api::invoice::{ImageFile, InvoiceError, PreparedFile};
+use crate::services::pdf::PDF_CONTENT_TYPE;
+
+/// Folder of the files of an owner, the user or the workspace the files were uploaded in.
+/// The files of a workspace are kept under the workspace so they outlive the member who
+/// uploaded them.
+pub fn owner_prefix(owner_id: ObjectId) -> String {
+    format!("{}/", owner_id)
+}
+
+/// Files uploaded straight to storage wait in the user's `uploads` folder until processed.
+pub fn upload_prefix(user_id: ObjectId) -> String {
+    format!("{}uploads/", owner_prefix(user_id))
+}
+
+/// Files of one upload share a folder, e.g. `{owner_id}/{uuid}/0.jpg`, `{owner_id}/{uuid}/1.jpg`.
+pub fn make_image_paths(
+    owner_id: ObjectId,
+    files: &[PreparedFile],
+) -> Result<Vec<String>, InvoiceError> {
+    let invoice_id = Uuid::new_v4().to_string();
+
+    files
+        .iter()
+        .enumerate()
+        .map(|(index, prepared)| {
+            let extension = mime2ext(&prepared.file.content_type)
+                .ok_or(InvoiceError::UnsupportedContentType)?;
+            Ok(format!(
+                "{}{}/{}.{}",
+                owner_prefix(owner_id),
+                invoice_id,
+                index,
+                extension
+            ))
+        })
+        .collect()
+}
+
+pub fn make_upload_key(user_id: ObjectId, content_type: &str) -> Result<String, InvoiceError> {
+    if !is_receipt_content_type(content_type) {
+        return Err(InvoiceError::UnsupportedContentType);
+    }
+    let extension = mime2ext(content_type).ok_or(InvoiceError::UnsupportedContentType)?;
+
+    Ok(format!(
+        "{}{}.{}",
+        upload_prefix(user_id),
+        Uuid::new_v4(),
+        extension
+    ))
+}
+
+pub fn is_receipt_content_type(content_type: &str) -> bool {
+    content_type.starts_with("image/") || content_type == PDF_CONTENT_TYPE
+}
+
+/// Checks a file fetched from storage: the client picked the key but wrote the content, so
+/// the size and the type announced by the key are checked against the bytes themselves.
+pub fn check_upload(
+    key: &str,
+    content: Vec<u8>,
+    max_size: usize,
+) -> Result<ImageFile, InvoiceError> {
+    if content.len() > max_size {
+        return Err(InvoiceError::FileTooLarge(max_size));
+    }
+
+    let content_type = mime_guess::from_path(key)
+        .first()
+        .map(|mime| mime.essence_str().to_string())
+        .filter(|content_type| is_receipt_content_type(content_type))
+        .ok_or(InvoiceError::UnsupportedContentType)?;
+
+    let is_valid = if content_type == PDF_CONTENT_TYPE {
+        content.starts_with(b"%PDF-")
+    } else {
+        image::guess_format(&content).is_ok_and(|format| {
+            format
+                .to_mime_type()
+                .eq_ignore_ascii_case(content_type.as_str())
+        })
+    };
+    if !is_valid {
+        return Err(InvoiceError::UnsupportedContentType);
+    }
+
+    Ok(ImageFile {
+        content,
+        content_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use image::{DynamicImage, ImageFormat, RgbImage};
+
+    use super::*;
+
+    fn png() -> Vec<u8> {
+        let mut content = Cursor::new(vec![]);
+        DynamicImage::ImageRgb8(RgbImage::new(4, 4))
+            .write_to(&mut content, ImageFormat::Png)
+            .unwrap();
+
+        content.into_inner()
+    }
+
+    #[test]
+    fn test_make_upload_key() {
+        let user_id = ObjectId::new();
+        let key = make_upload_key(user_id, "image/png").unwrap();
+
+        assert!(key.starts_with(&format!("{user_id}/uploads/")));
+        assert!(key.ends_with(".png"));
+        assert!(make_upload_key(user_id, "text/html").is_err());
+    }
+
+    #[test]
+    fn test_make_image_paths() {
+        let (user_id, workspace_id) = (ObjectId::new(), ObjectId::new());
+        let files = ["image/jpeg", "image/png"].map(|content_type| PreparedFile {
+            file: ImageFile {
+                content: vec![],
+                content_type: content_type.to_string(),
+            },
+            thumbnail: None,
+            hash: None,
+        });
+
+        // uploaded in a workspace, the photos stay when the member deletes their account
+        let paths = make_image_paths(workspace_id, &files).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].starts_with(&owner_prefix(workspace_id)));
+        assert!(paths[0].ends_with("/0.jpeg"));
+        assert!(paths[1].ends_with("/1.png"));
+        assert_eq!(
+            paths[0].rsplit_once('/').unwrap().0,
+            paths[1].rsplit_once('/').unwrap().0
+        );
+        assert!(paths
+            .iter()
+            .all(|path| !path.starts_with(&owner_prefix(user_id))));
+    }
+
+    #[test]
+    fn test_check_upload() {
+        let file = check_upload("u/uploads/a.png", png(), 1024).unwrap();
+        assert_eq!(file.content_type, "image/png");
+
+        let file = check_upload("u/uploads/a.pdf", b"%PDF-1.7".to_vec(), 1024).unwrap();
+        assert_eq!(file.content_type, PDF_CONTENT_TYPE);
+
+        // content does not match the extension
+        assert!(check_upload("u/uploads/a.jpg", png(), 1024).is_err());
+        assert!(check_upload("u/uploads/a.pdf", png(), 1024).is_err());
+        assert!(check_upload("u/uploads/a.html", b"<html>".to_vec(), 1024).is_err());
+        assert!(matches!(
+            check_upload("u/uploads/a.png", png(), 8),
+            Err(InvoiceError::FileTooLarge(8))
+        ));
+    }
+}
@@ -3,16 +3,28 @@ pub(crate) use dto::*;
 #[allow(unused_imports)]
 pub use invoice_controller::InvoiceApiDoc;
 pub(crate) use invoice_entity::*;
+pub(crate) use invoice_image::*;
 pub use invoice_model::*;
+pub(crate) use invoice_pipeline::*;
+pub(crate) use invoice_redact::*;
 pub(crate) use invoice_repo::*;
 pub use invoice_router::*;
 pub use invoice_service::*;
+pub(crate) use invoice_stitch::*;
+pub(crate) use invoice_totals::*;
+pub(crate) use invoice_upload::*;
 
 mod constants;
 mod dto;
 mod invoice_controller;
 mod invoice_entity;
+mod invoice_image;
 mod invoice_model;
+mod invoice_pipeline;
+mod invoice_redact;
 mod invoice_repo;
 mod invoice_router;
 mod invoice_service;
+mod invoice_stitch;
+mod invoice_totals;
+mod invoice_upload;
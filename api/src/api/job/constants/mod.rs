@@ -0,0 +1,3 @@
+mod errors;
+
+pub use errors::*;
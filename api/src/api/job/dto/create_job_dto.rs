@@ -0,0 +1,10 @@
+use bson::oid::ObjectId;
+
+use crate::api::job::JobPayload;
+
+pub struct CreateJobInput {
+    pub user_id: ObjectId,
+    // the workspace the job writes to, the ledger of the user otherwise
+    pub workspace_id: Option<ObjectId>,
+    pub payload: JobPayload,
+}
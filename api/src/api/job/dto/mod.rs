@@ -0,0 +1,3 @@
+mod create_job_dto;
+
+pub use create_job_dto::*;
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// How long a job stays with its worker without a heartbeat. Workers renew the lease while
+/// they run the job, so only jobs of a stopped instance run out of it.
+pub const LEASE: Duration = Duration::from_secs(5 * 60);
+/// How often a running job renews its lease.
+pub const HEARTBEAT: Duration = Duration::from_secs(60);
+
+const RETRY_BASE: Duration = Duration::from_secs(5);
+const RETRY_MAX: Duration = Duration::from_secs(10 * 60);
+
+/// What recovery does with a job found in processing.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Recovery {
+    /// A worker holds the lease
+    Keep,
+    /// No lease yet, the worker may have just picked it up. It gets a full lease before it
+    /// counts as lost.
+    Grant,
+    /// The lease ran out, the worker is gone
+    Requeue,
+}
+
+pub fn recovery(lease_until: Option<i64>, now: i64) -> Recovery {
+    match lease_until {
+        None => Recovery::Grant,
+        Some(until) if until < now => Recovery::Requeue,
+        Some(_) => Recovery::Keep,
+    }
+}
+
+/// Wait before the next attempt, doubling from 5 seconds up to 10 minutes.
+pub fn retry_delay(attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+
+    RETRY_BASE.saturating_mul(factor).min(RETRY_MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovery() {
+        let now = 1_700_000_000;
+
+        assert_eq!(recovery(None, now), Recovery::Grant);
+        assert_eq!(recovery(Some(now + 1), now), Recovery::Keep);
+        assert_eq!(recovery(Some(now), now), Recovery::Keep);
+        assert_eq!(recovery(Some(now - 1), now), Recovery::Requeue);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1), Duration::from_secs(5));
+        assert_eq!(retry_delay(2), Duration::from_secs(10));
+        assert_eq!(retry_delay(4), Duration::from_secs(40));
+        assert_eq!(retry_delay(12), RETRY_MAX);
+        assert_eq!(retry_delay(u32::MAX), RETRY_MAX);
+    }
+}
@@ -0,0 +1,151 @@
+use redis_macros::{FromRedisValue, ToRedisArgs};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::invoice::Media;
+use crate::api::message::Message;
+use crate::api::reprocess::InvoiceDiff;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Processing,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(
+    tag = "type",
+    rename_all = "snake_case",
+    rename_all_fields = "camelCase"
+)]
+pub enum JobPayload {
+    ProcessInvoice {
+        media: Vec<Media>,
+    },
+    ProcessEmail {
+        media: Media,
+    },
+    /// A voice note, transcribed then logged as an expense
+    ProcessVoice {
+        media: Media,
+    },
+    ReprocessInvoice {
+        invoice_id: String,
+        dry_run: bool,
+    },
+    /// Stored files left behind by a deletion, retried until they are gone
+    DeleteObjects {
+        paths: Vec<String>,
+    },
+    /// Every stored file of a deleted user
+    DeleteUserObjects,
+}
+
+/// Payload as stored in redis, jobs queued before an upload held several files keep their
+/// single file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredJobPayload {
+    Payload(JobPayload),
+    #[serde(rename_all = "camelCase")]
+    ProcessInvoice {
+        path: String,
+        content_type: String,
+    },
+}
+
+fn deserialize_payload<'de, D>(deserializer: D) -> Result<JobPayload, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match StoredJobPayload::deserialize(deserializer)? {
+        StoredJobPayload::Payload(payload) => payload,
+        StoredJobPayload::ProcessInvoice { path, content_type } => JobPayload::ProcessInvoice {
+            media: vec![Media {
+                path,
+                content_type,
+                thumbnail_path: None,
+                hash: None,
+            }],
+        },
+    })
+}
+
+/// What a finished job produced.
+pub enum JobResult {
+    Messages(Vec<Message>),
+    Diff(InvoiceDiff),
+    Empty,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, FromRedisValue, ToRedisArgs, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    #[schema(example = "0d1f5b6e-5f7c-4f0e-9a4e-2d6f0c3b9a11")]
+    pub id: String,
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    /// Workspace the result is saved in, the ledger of the user when missing
+    #[schema(example = "66a4e3f2ce6a5cbb87195e10")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    #[schema(example = "queued")]
+    pub status: JobStatus,
+    #[serde(deserialize_with = "deserialize_payload")]
+    pub payload: JobPayload,
+    #[schema(example = 0)]
+    pub attempts: u32,
+    #[schema(example = "no transactions")]
+    pub error: Option<String>,
+    pub messages: Option<Vec<Message>>,
+    /// Changes found by a reprocess job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<InvoiceDiff>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_payload() {
+        let job = |payload: &str| {
+            serde_json::from_str::<Job>(&format!(
+                r#"{{"id":"1","userId":"66990b1947d76ec3781adc9d","status":"queued",
+                "payload":{payload},"attempts":0,"error":null,"messages":null,
+                "createdAt":"2024-07-22T13:30:42Z","updatedAt":"2024-07-22T13:30:42Z"}}"#
+            ))
+            .unwrap()
+            .payload
+        };
+
+        // queued before an upload held several files
+        let payload = job(
+            r#"{"type":"process_invoice","path":"invoices/1/0.jpg","contentType":"image/jpeg"}"#,
+        );
+        match payload {
+            JobPayload::ProcessInvoice { media } => {
+                assert_eq!(media.len(), 1);
+                assert_eq!(media[0].path, "invoices/1/0.jpg");
+                assert_eq!(media[0].content_type, "image/jpeg");
+            }
+            payload => panic!("unexpected payload: {payload:?}"),
+        }
+
+        let payload = job(
+            r#"{"type":"process_invoice","media":[{"path":"invoices/1/0.jpg","contentType":"image/jpeg"},{"path":"invoices/1/1.jpg","contentType":"image/jpeg"}]}"#,
+        );
+        assert!(matches!(payload, JobPayload::ProcessInvoice { media } if media.len() == 2));
+        assert!(matches!(
+            job(r#"{"type":"delete_user_objects"}"#),
+            JobPayload::DeleteUserObjects
+        ));
+    }
+}
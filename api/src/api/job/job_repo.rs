@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, Direction};
+
+use crate::api::job::*;
+
+#[async_trait]
+pub trait JobRepoExt: Send + Sync {
+    async fn save(&self, job: &Job) -> Result<(), JobError>;
+    async fn find_by_id(&self, id: &str) -> Result<Option<Job>, JobError>;
+    async fn push(&self, id: &str) -> Result<(), JobError>;
+    async fn pop(&self, timeout_secs: f64) -> Result<Option<String>, JobError>;
+    async fn ack(&self, id: &str) -> Result<(), JobError>;
+    async fn lease(&self, id: &str, until: i64) -> Result<(), JobError>;
+    async fn lease_if_missing(&self, id: &str, until: i64) -> Result<(), JobError>;
+    async fn retry(&self, id: &str, at: i64) -> Result<(), JobError>;
+    async fn bury(&self, id: &str) -> Result<(), JobError>;
+    /// The jobs in processing, with the end of their lease.
+    async fn processing(&self) -> Result<Vec<(String, Option<i64>)>, JobError>;
+    /// Puts a job in processing back in the queue, false when someone else already did.
+    async fn requeue(&self, id: &str) -> Result<bool, JobError>;
+}
+
+pub type JobRepoDyn = Arc<dyn JobRepoExt + Send + Sync>;
+
+/// Jobs wait in `jobs:queue`, are moved to `jobs:processing` while a worker runs them
+/// and end up in `jobs:dead` once they run out of attempts. Running jobs hold a lease in
+/// `jobs:leases`, failed ones wait for their next attempt in `jobs:delayed`, both sorted
+/// by timestamp.
+#[derive(Clone)]
+pub struct JobRepo {
+    pub redis_client: redis::Client,
+}
+
+impl JobRepo {
+    const QUEUE_KEY: &'static str = "jobs:queue";
+    const PROCESSING_KEY: &'static str = "jobs:processing";
+    const DEAD_KEY: &'static str = "jobs:dead";
+    const LEASES_KEY: &'static str = "jobs:leases";
+    const DELAYED_KEY: &'static str = "jobs:delayed";
+    // 7 days
+    const MAX_AGE: u64 = 7 * 24 * 60 * 60;
+
+    fn key(id: &str) -> String {
+        format!("job:{}", id)
+    }
+
+    async fn connection(&self) -> Result<MultiplexedConnection, JobError> {
+        self.redis_client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    /// Moves the delayed jobs whose time has come to the queue.
+    async fn promote_delayed(&self, con: &mut MultiplexedConnection) -> Result<(), JobError> {
+        let now = chrono::Utc::now().timestamp();
+        let ids: Vec<String> = con
+            .zrangebyscore(Self::DELAYED_KEY, "-inf", now)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))?;
+
+        for id in ids {
+            // only the instance that removes it queues it
+            let removed: usize = con
+                .zrem(Self::DELAYED_KEY, &id)
+                .await
+                .map_err(|e| JobError::Unknown(anyhow!(e)))?;
+            if removed == 1 {
+                con.lpush::<_, _, ()>(Self::QUEUE_KEY, &id)
+                    .await
+                    .map_err(|e| JobError::Unknown(anyhow!(e)))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl JobRepoExt for JobRepo {
+    async fn save(&self, job: &Job) -> Result<(), JobError> {
+        let mut con = self.connection().await?;
+
+        con.set_ex(Self::key(&job.id), job, Self::MAX_AGE)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Job>, JobError> {
+        let mut con = self.connection().await?;
+
+        con.get(Self::key(id))
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn push(&self, id: &str) -> Result<(), JobError> {
+        let mut con = self.connection().await?;
+
+        con.lpush(Self::QUEUE_KEY, id)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn pop(&self, timeout_secs: f64) -> Result<Option<String>, JobError> {
+        let mut con = self.connection().await?;
+        self.promote_delayed(&mut con).await?;
+
+        con.blmove(
+            Self::QUEUE_KEY,
+            Self::PROCESSING_KEY,
+            Direction::Right,
+            Direction::Left,
+            timeout_secs,
+        )
+        .await
+        .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn ack(&self, id: &str) -> Result<(), JobError> {
+        let mut con = self.connection().await?;
+
+        redis::pipe()
+            .atomic()
+            .lrem(Self::PROCESSING_KEY, 1, id)
+            .zrem(Self::LEASES_KEY, id)
+            .query_async(&mut con)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn lease(&self, id: &str, until: i64) -> Result<(), JobError> {
+        let mut con = self.connection().await?;
+
+        con.zadd(Self::LEASES_KEY, id, until)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn lease_if_missing(&self, id: &str, until: i64) -> Result<(), JobError> {
+        let mut con = self.connection().await?;
+
+        redis::cmd("ZADD")
+            .arg(Self::LEASES_KEY)
+            .arg("NX")
+            .arg(until)
+            .arg(id)
+            .query_async(&mut con)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn retry(&self, id: &str, at: i64) -> Result<(), JobError> {
+        let mut con = self.connection().await?;
+
+        redis::pipe()
+            .atomic()
+            .lrem(Self::PROCESSING_KEY, 1, id)
+            .zrem(Self::LEASES_KEY, id)
+            .zadd(Self::DELAYED_KEY, id, at)
+            .query_async(&mut con)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn bury(&self, id: &str) -> Result<(), JobError> {
+        let mut con = self.connection().await?;
+
+        redis::pipe()
+            .atomic()
+            .lrem(Self::PROCESSING_KEY, 1, id)
+            .zrem(Self::LEASES_KEY, id)
+            .lpush(Self::DEAD_KEY, id)
+            .query_async(&mut con)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))
+    }
+
+    async fn processing(&self) -> Result<Vec<(String, Option<i64>)>, JobError> {
+        let mut con = self.connection().await?;
+
+        let ids: Vec<String> = con
+            .lrange(Self::PROCESSING_KEY, 0, -1)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))?;
+        let mut jobs = vec![];
+        for id in ids {
+            let until: Option<f64> = con
+                .zscore(Self::LEASES_KEY, &id)
+                .await
+                .map_err(|e| JobError::Unknown(anyhow!(e)))?;
+            jobs.push((id, until.map(|until| until as i64)));
+        }
+
+        Ok(jobs)
+    }
+
+    async fn requeue(&self, id: &str) -> Result<bool, JobError> {
+        let mut con = self.connection().await?;
+
+        let removed: usize = con
+            .lrem(Self::PROCESSING_KEY, 1, id)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))?;
+        if removed == 0 {
+            return Ok(false);
+        }
+
+        redis::pipe()
+            .atomic()
+            .zrem(Self::LEASES_KEY, id)
+            .rpush(Self::QUEUE_KEY, id)
+            .query_async::<()>(&mut con)
+            .await
+            .map_err(|e| JobError::Unknown(anyhow!(e)))?;
+
+        Ok(true)
+    }
+}
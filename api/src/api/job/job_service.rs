@@ -0,0 +1,378 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::api::job::*;
+use crate::common::errors::AppError;
+
+#[async_trait]
+pub trait JobServiceExt: Send + Sync {
+    async fn enqueue(&self, input: CreateJobInput) -> Result<Job, AppError>;
+    async fn find_by_id(&self, id: &str) -> Result<Option<Job>, AppError>;
+    async fn next(&self) -> Result<Option<Job>, AppError>;
+    async fn complete(&self, job: Job, result: JobResult) -> Result<Job, AppError>;
+    async fn fail(&self, job: Job, error: String) -> Result<Job, AppError>;
+    /// Keeps the job with its worker for another lease.
+    async fn heartbeat(&self, id: &str) -> Result<(), AppError>;
+    /// Queues again the jobs whose worker stopped, returns how many.
+    async fn recover(&self) -> Result<usize, AppError>;
+}
+
+pub type JobServiceDyn = Arc<dyn JobServiceExt + Send + Sync>;
+
+#[derive(Clone)]
+pub struct JobService {
+    pub repo: JobRepoDyn,
+    pub max_attempts: u32,
+}
+
+impl JobService {
+    const POP_TIMEOUT_SECS: f64 = 5.0;
+}
+
+#[async_trait]
+impl JobServiceExt for JobService {
+    async fn enqueue(&self, input: CreateJobInput) -> Result<Job, AppError> {
+        let now = chrono::Utc::now();
+        let job = Job {
+            id: Uuid::new_v4().to_string(),
+            user_id: input.user_id.to_hex(),
+            workspace_id: input.workspace_id.map(|id| id.to_hex()),
+            status: JobStatus::Queued,
+            payload: input.payload,
+            attempts: 0,
+            error: None,
+            messages: None,
+            diff: None,
+            created_at: now,
+            updated_at: now,
+        };
+
+        self.repo.save(&job).await?;
+        self.repo.push(&job.id).await?;
+
+        Ok(job)
+    }
+
+    async fn find_by_id(&self, id: &str) -> Result<Option<Job>, AppError> {
+        self.repo.find_by_id(id).await.map_err(Into::into)
+    }
+
+    async fn next(&self) -> Result<Option<Job>, AppError> {
+        let Some(id) = self.repo.pop(Self::POP_TIMEOUT_SECS).await? else {
+            return Ok(None);
+        };
+
+        let Some(job) = self.repo.find_by_id(&id).await? else {
+            // The job expired before anyone picked it up.
+            self.repo.ack(&id).await?;
+            return Ok(None);
+        };
+
+        // a job recovered from a stopped worker never reached `fail`
+        if job.attempts >= self.max_attempts {
+            let job = Job {
+                status: JobStatus::Failed,
+                error: job
+                    .error
+                    .or(Some("the job ran out of attempts".to_string())),
+                updated_at: chrono::Utc::now(),
+                ..job
+            };
+            self.repo.save(&job).await?;
+            self.repo.bury(&job.id).await?;
+            return Ok(None);
+        }
+
+        let job = Job {
+            status: JobStatus::Processing,
+            attempts: job.attempts + 1,
+            updated_at: chrono::Utc::now(),
+            ..job
+        };
+        self.repo.save(&job).await?;
+        self.heartbeat(&job.id).await?;
+
+        Ok(Some(job))
+    }
+
+    async fn complete(&self, job: Job, result: JobResult) -> Result<Job, AppError> {
+        let (messages, diff) = match result {
+            JobResult::Messages(messages) => (Some(messages), None),
+            JobResult::Diff(diff) => (None, Some(diff)),
+            JobResult::Empty => (None, None),
+        };
+        let job = Job {
+            status: JobStatus::Done,
+            error: None,
+            messages,
+            diff,
+            updated_at: chrono::Utc::now(),
+            ..job
+        };
+        self.repo.save(&job).await?;
+        self.repo.ack(&job.id).await?;
+
+        Ok(job)
+    }
+
+    async fn fail(&self, job: Job, error: String) -> Result<Job, AppError> {
+        let status = if job.attempts < self.max_attempts {
+            JobStatus::Queued
+        } else {
+            JobStatus::Failed
+        };
+        let job = Job {
+            status,
+            error: Some(error),
+            updated_at: chrono::Utc::now(),
+            ..job
+        };
+        self.repo.save(&job).await?;
+
+        match job.status {
+            JobStatus::Queued => {
+                let at =
+                    chrono::Utc::now().timestamp() + retry_delay(job.attempts).as_secs() as i64;
+                self.repo.retry(&job.id, at).await?
+            }
+            _ => self.repo.bury(&job.id).await?,
+        }
+
+        Ok(job)
+    }
+
+    async fn heartbeat(&self, id: &str) -> Result<(), AppError> {
+        let until = chrono::Utc::now().timestamp() + LEASE.as_secs() as i64;
+
+        self.repo.lease(id, until).await.map_err(Into::into)
+    }
+
+    async fn recover(&self) -> Result<usize, AppError> {
+        let now = chrono::Utc::now().timestamp();
+
+        let mut count = 0;
+        for (id, until) in self.repo.processing().await? {
+            match recovery(until, now) {
+                Recovery::Keep => {}
+                Recovery::Grant => {
+                    self.repo
+                        .lease_if_missing(&id, now + LEASE.as_secs() as i64)
+                        .await?
+                }
+                Recovery::Requeue => {
+                    if self.repo.requeue(&id).await? {
+                        count += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// The redis lists and sorted sets of `JobRepo`, in memory.
+    #[derive(Default)]
+    struct Lists {
+        jobs: HashMap<String, Job>,
+        queue: VecDeque<String>,
+        processing: Vec<String>,
+        leases: HashMap<String, i64>,
+        delayed: HashMap<String, i64>,
+        dead: Vec<String>,
+    }
+
+    #[derive(Default)]
+    struct MemoryJobRepo(Mutex<Lists>);
+
+    impl Lists {
+        fn release(&mut self, id: &str) {
+            self.processing.retain(|v| v != id);
+            self.leases.remove(id);
+        }
+    }
+
+    #[async_trait]
+    impl JobRepoExt for MemoryJobRepo {
+        async fn save(&self, job: &Job) -> Result<(), JobError> {
+            let mut lists = self.0.lock().unwrap();
+            lists.jobs.insert(job.id.clone(), job.clone());
+            Ok(())
+        }
+
+        async fn find_by_id(&self, id: &str) -> Result<Option<Job>, JobError> {
+            Ok(self.0.lock().unwrap().jobs.get(id).cloned())
+        }
+
+        async fn push(&self, id: &str) -> Result<(), JobError> {
+            self.0.lock().unwrap().queue.push_front(id.to_string());
+            Ok(())
+        }
+
+        async fn pop(&self, _timeout_secs: f64) -> Result<Option<String>, JobError> {
+            let mut lists = self.0.lock().unwrap();
+            let id = lists.queue.pop_back();
+            if let Some(id) = &id {
+                lists.processing.insert(0, id.clone());
+            }
+            Ok(id)
+        }
+
+        async fn ack(&self, id: &str) -> Result<(), JobError> {
+            self.0.lock().unwrap().release(id);
+            Ok(())
+        }
+
+        async fn lease(&self, id: &str, until: i64) -> Result<(), JobError> {
+            self.0.lock().unwrap().leases.insert(id.to_string(), until);
+            Ok(())
+        }
+
+        async fn lease_if_missing(&self, id: &str, until: i64) -> Result<(), JobError> {
+            let mut lists = self.0.lock().unwrap();
+            lists.leases.entry(id.to_string()).or_insert(until);
+            Ok(())
+        }
+
+        async fn retry(&self, id: &str, at: i64) -> Result<(), JobError> {
+            let mut lists = self.0.lock().unwrap();
+            lists.release(id);
+            lists.delayed.insert(id.to_string(), at);
+            Ok(())
+        }
+
+        async fn bury(&self, id: &str) -> Result<(), JobError> {
+            let mut lists = self.0.lock().unwrap();
+            lists.release(id);
+            lists.dead.push(id.to_string());
+            Ok(())
+        }
+
+        async fn processing(&self) -> Result<Vec<(String, Option<i64>)>, JobError> {
+            let lists = self.0.lock().unwrap();
+            Ok(lists
+                .processing
+                .iter()
+                .map(|id| (id.clone(), lists.leases.get(id).copied()))
+                .collect())
+        }
+
+        async fn requeue(&self, id: &str) -> Result<bool, JobError> {
+            let mut lists = self.0.lock().unwrap();
+            if !lists.processing.iter().any(|v| v == id) {
+                return Ok(false);
+            }
+            lists.release(id);
+            lists.queue.push_back(id.to_string());
+            Ok(true)
+        }
+    }
+
+    fn service() -> (JobService, Arc<MemoryJobRepo>) {
+        let repo = Arc::new(MemoryJobRepo::default());
+        let service = JobService {
+            repo: repo.clone(),
+            max_attempts: 2,
+        };
+
+        (service, repo)
+    }
+
+    async fn enqueue(service: &JobService) -> Job {
+        service
+            .enqueue(CreateJobInput {
+                user_id: bson::oid::ObjectId::new(),
+                workspace_id: None,
+                payload: JobPayload::DeleteUserObjects,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_recover_leaves_running_jobs() {
+        let (service, repo) = service();
+        enqueue(&service).await;
+        let job = service.next().await.unwrap().unwrap();
+
+        // another instance starting up must not take the job of a live worker
+        assert_eq!(service.recover().await.unwrap(), 0);
+        assert!(repo.0.lock().unwrap().queue.is_empty());
+
+        // the worker stopped, its lease runs out
+        repo.0.lock().unwrap().leases.insert(job.id.clone(), 0);
+        assert_eq!(service.recover().await.unwrap(), 1);
+        assert_eq!(repo.0.lock().unwrap().queue, [job.id]);
+    }
+
+    #[tokio::test]
+    async fn test_recover_grants_lease_first() {
+        let (service, repo) = service();
+        let job = enqueue(&service).await;
+        service.repo.pop(0.0).await.unwrap();
+
+        // picked up, the lease is not written yet
+        assert_eq!(service.recover().await.unwrap(), 0);
+        assert!(repo.0.lock().unwrap().leases.contains_key(&job.id));
+    }
+
+    #[tokio::test]
+    async fn test_fail_backs_off_then_buries() {
+        let (service, repo) = service();
+        let job = enqueue(&service).await;
+
+        let running = service.next().await.unwrap().unwrap();
+        let before = chrono::Utc::now().timestamp();
+        let failed = service
+            .fail(running, "no transactions".to_string())
+            .await
+            .unwrap();
+        assert_eq!(failed.status, JobStatus::Queued);
+        {
+            let lists = repo.0.lock().unwrap();
+            assert!(lists.queue.is_empty());
+            assert!(lists.delayed[&job.id] >= before + retry_delay(1).as_secs() as i64);
+        }
+
+        // the delay is over
+        {
+            let mut lists = repo.0.lock().unwrap();
+            lists.delayed.remove(&job.id);
+            lists.queue.push_back(job.id.clone());
+        }
+        let running = service.next().await.unwrap().unwrap();
+        let failed = service
+            .fail(running, "no transactions".to_string())
+            .await
+            .unwrap();
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(repo.0.lock().unwrap().dead, [job.id]);
+    }
+
+    #[tokio::test]
+    async fn test_next_buries_lost_job_out_of_attempts() {
+        let (service, repo) = service();
+        let job = enqueue(&service).await;
+
+        // the job was lost twice with its worker, it is not run a third time
+        for _ in 0..2 {
+            service.next().await.unwrap().unwrap();
+            repo.0.lock().unwrap().leases.insert(job.id.clone(), 0);
+            service.recover().await.unwrap();
+        }
+        assert!(service.next().await.unwrap().is_none());
+
+        let lists = repo.0.lock().unwrap();
+        assert_eq!(lists.dead.len(), 1);
+        assert_eq!(lists.jobs[&job.id].status, JobStatus::Failed);
+    }
+}
@@ -0,0 +1,212 @@
+use std::time::Duration;
+
+use bson::oid::ObjectId;
+use tracing::{error, info, warn};
+
+use crate::api::email::create_email_message;
+use crate::api::invoice::{create_invoice_message, Media};
+use crate::api::job::{Job, JobPayload, JobResult, JobStatus, HEARTBEAT, LEASE};
+use crate::api::message::{log_voice_note, Message};
+use crate::api::reprocess::{reprocess_invoice, InvoiceDiff};
+use crate::api::state::AppState;
+use crate::api::user::UserError;
+use crate::common::errors::AppError;
+use crate::object_id;
+
+pub struct JobWorker {
+    pub state: AppState,
+}
+
+impl JobWorker {
+    const ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+    /// Whose ledger the job writes to.
+    fn owner_id(job: &Job) -> ObjectId {
+        object_id!(job.workspace_id.as_deref().unwrap_or(&job.user_id))
+    }
+
+    /// Starts the worker pool. Jobs whose lease ran out, e.g. those of a stopped instance,
+    /// are queued again every lease, so a restart does not lose them while the jobs other
+    /// instances run are left alone.
+    pub async fn spawn(state: AppState) {
+        let job_service = state.job_service.clone();
+        tokio::spawn(async move {
+            loop {
+                match job_service.recover().await {
+                    Ok(0) => {}
+                    Ok(count) => info!(count, "requeued unfinished jobs"),
+                    Err(e) => error!("failed to requeue unfinished jobs: {e}"),
+                }
+                tokio::time::sleep(LEASE).await;
+            }
+        });
+
+        for id in 0..state.settings.invoice.workers {
+            let worker = Self {
+                state: state.clone(),
+            };
+            tokio::spawn(async move { worker.run(id).await });
+        }
+    }
+
+    async fn run(&self, id: usize) {
+        info!(worker = id, "job worker started");
+        loop {
+            match self.state.job_service.next().await {
+                Ok(Some(job)) => self.handle_leased(job).await,
+                Ok(None) => {}
+                Err(e) => {
+                    error!(worker = id, "failed to fetch job: {e}");
+                    tokio::time::sleep(Self::ERROR_BACKOFF).await;
+                }
+            }
+        }
+    }
+
+    /// Runs the job, renewing its lease until it is done.
+    async fn handle_leased(&self, job: Job) {
+        let id = job.id.clone();
+        let handle = self.handle(job);
+        tokio::pin!(handle);
+
+        let mut heartbeat = tokio::time::interval(HEARTBEAT);
+        // the first tick is immediate, the lease was just taken
+        heartbeat.tick().await;
+        loop {
+            tokio::select! {
+                _ = &mut handle => break,
+                _ = heartbeat.tick() => {
+                    if let Err(e) = self.state.job_service.heartbeat(&id).await {
+                        warn!(job = id, "failed to renew lease: {e}");
+                    }
+                }
+            }
+        }
+    }
+
+    async fn handle(&self, job: Job) {
+        let result = match job.payload.clone() {
+            JobPayload::ProcessInvoice { media } => self
+                .process_invoice(&job, media)
+                .await
+                .map(JobResult::Messages),
+            JobPayload::ProcessEmail { media } => self
+                .process_email(&job, media)
+                .await
+                .map(JobResult::Messages),
+            JobPayload::ProcessVoice { media } => self
+                .process_voice(&job, media)
+                .await
+                .map(JobResult::Messages),
+            JobPayload::ReprocessInvoice {
+                invoice_id,
+                dry_run,
+            } => self
+                .reprocess_invoice(&job, &invoice_id, dry_run)
+                .await
+                .map(JobResult::Diff),
+            JobPayload::DeleteObjects { paths } => self
+                .state
+                .storage_service
+                .delete_objects(&paths)
+                .await
+                .map(|_| JobResult::Empty),
+            JobPayload::DeleteUserObjects => self
+                .state
+                .storage_service
+                .delete_user_objects(object_id!(&job.user_id))
+                .await
+                .map(|_| JobResult::Empty),
+        };
+
+        let result = match result {
+            Ok(result) => self.state.job_service.complete(job, result).await,
+            Err(e) => {
+                warn!(job = job.id, attempts = job.attempts, "job failed: {e}");
+                self.state.job_service.fail(job, e.to_string()).await
+            }
+        };
+
+        match result {
+            Ok(job) if job.status == JobStatus::Failed => self.discard(&job).await,
+            Ok(_) => {}
+            Err(e) => error!("failed to update job: {e}"),
+        }
+    }
+
+    /// Deletes the voice note of a job that gave up, no message refers to it.
+    async fn discard(&self, job: &Job) {
+        let JobPayload::ProcessVoice { media } = &job.payload else {
+            return;
+        };
+        if let Err(e) = self
+            .state
+            .storage_service
+            .delete_media(object_id!(&job.user_id), std::slice::from_ref(media))
+            .await
+        {
+            warn!(job = job.id, "voice note left in storage: {e}");
+        }
+    }
+
+    async fn process_invoice(
+        &self,
+        job: &Job,
+        media: Vec<Media>,
+    ) -> Result<Vec<Message>, AppError> {
+        let user = self
+            .state
+            .user_service
+            .find_by_id(object_id!(&job.user_id))
+            .await?
+            .ok_or(UserError::NotFound)?;
+        let image = self.state.invoice_service.read_images(media).await?;
+
+        create_invoice_message(&self.state, user, Self::owner_id(job), image, None).await
+    }
+
+    async fn process_email(&self, job: &Job, media: Media) -> Result<Vec<Message>, AppError> {
+        let user = self
+            .state
+            .user_service
+            .find_by_id(object_id!(&job.user_id))
+            .await?
+            .ok_or(UserError::NotFound)?;
+
+        create_email_message(&self.state, user, media).await
+    }
+
+    async fn process_voice(&self, job: &Job, media: Media) -> Result<Vec<Message>, AppError> {
+        let user = self
+            .state
+            .user_service
+            .find_by_id(object_id!(&job.user_id))
+            .await?
+            .ok_or(UserError::NotFound)?;
+
+        log_voice_note(&self.state, user, Self::owner_id(job), media).await
+    }
+
+    async fn reprocess_invoice(
+        &self,
+        job: &Job,
+        invoice_id: &str,
+        dry_run: bool,
+    ) -> Result<InvoiceDiff, AppError> {
+        let user = self
+            .state
+            .user_service
+            .find_by_id(object_id!(&job.user_id))
+            .await?
+            .ok_or(UserError::NotFound)?;
+
+        reprocess_invoice(
+            &self.state,
+            user,
+            Self::owner_id(job),
+            object_id!(invoice_id),
+            dry_run,
+        )
+        .await
+    }
+}
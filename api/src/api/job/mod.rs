@@ -0,0 +1,15 @@
+pub use constants::*;
+pub use dto::*;
+pub(crate) use job_lease::*;
+pub use job_model::*;
+pub use job_repo::*;
+pub use job_service::*;
+pub use job_worker::*;
+
+mod constants;
+mod dto;
+mod job_lease;
+mod job_model;
+mod job_repo;
+mod job_service;
+mod job_worker;
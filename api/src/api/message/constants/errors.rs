@@ -11,6 +11,10 @@ pub enum MessageError {
     Unknown(#[from] anyhow::Error),
     #[error("message not found")]
     NotFound,
+    #[error("no audio file")]
+    NoAudio,
+    #[error("unsupported audio type")]
+    UnsupportedContentType,
 }
 
 impl IntoResponse for MessageError {
@@ -18,6 +22,8 @@ impl IntoResponse for MessageError {
         let (status, message) = match self {
             Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             Self::NotFound => (StatusCode::NOT_FOUND, "message not found".to_string()),
+            Self::NoAudio => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::UnsupportedContentType => (StatusCode::UNSUPPORTED_MEDIA_TYPE, self.to_string()),
         };
 
         let error_response = ErrorResponse { message };
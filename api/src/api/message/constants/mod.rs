@@ -1,3 +1,5 @@
 mod errors;
+mod replies;
 
 pub use errors::*;
+pub use replies::*;
@@ -0,0 +1,46 @@
+/// Builds the bot confirmation for a logged message, e.g.
+/// `Logged 2 items, 145,000 ₫ total: Groceries`, from the total and the category names
+/// already written in the language. Unsupported languages fall back to English.
+pub fn make_logged_reply(language: &str, count: usize, total: &str, categories: &[String]) -> String {
+    let categories = categories.join(", ");
+
+    match (language, count) {
+        ("vi", 0) => "Không tìm thấy khoản chi nào để ghi lại.".to_string(),
+        ("vi", _) => format!("Đã ghi {count} mục, tổng cộng {total}: {categories}"),
+        ("ja", 0) => "記録できる項目が見つかりませんでした。".to_string(),
+        ("ja", _) => format!("{count}件を記録しました（合計 {total}）：{categories}"),
+        (_, 0) => "Nothing to log in this message.".to_string(),
+        (_, 1) => format!("Logged 1 item, {total} total: {categories}"),
+        (_, _) => format!("Logged {count} items, {total} total: {categories}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_make_logged_reply() {
+        let categories = vec!["Groceries".to_string(), "Dining out".to_string()];
+        assert_eq!(
+            make_logged_reply("en", 2, "145,000 ₫", &categories),
+            "Logged 2 items, 145,000 ₫ total: Groceries, Dining out"
+        );
+        assert_eq!(
+            make_logged_reply("en", 1, "5.00 $", &categories[..1]),
+            "Logged 1 item, 5.00 $ total: Groceries"
+        );
+        assert_eq!(
+            make_logged_reply("vi", 2, "145.000 ₫", &["Thực phẩm".to_string()]),
+            "Đã ghi 2 mục, tổng cộng 145.000 ₫: Thực phẩm"
+        );
+        assert_eq!(
+            make_logged_reply("ja", 3, "980 ¥", &["食料品".to_string()]),
+            "3件を記録しました（合計 980 ¥）：食料品"
+        );
+        assert_eq!(
+            make_logged_reply("fr", 0, "0.00 €", &[]),
+            "Nothing to log in this message."
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::api::infer::models::InvoiceTool;
+use crate::api::invoice::Media;
 use bson::oid::ObjectId;
 use serde::Deserialize;
 use utoipa::ToSchema;
@@ -10,12 +11,27 @@ pub struct CreateMessageBody {
     pub content: String,
 }
 
+#[allow(unused)]
+#[derive(Deserialize, ToSchema)]
+pub struct CreateVoiceMessageBody {
+    /// Voice note (`audio/*`), e.g. "lunch 80 thousand with Nam"
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
 pub struct CreateMessageInput {
     pub prompt: String,
     pub currencies: Vec<String>,
     pub user_id: ObjectId,
+    pub language: String,
     pub invoice_tool: InvoiceTool,
     pub completion: String,
-    pub media_path: Option<String>,
-    pub media_type: Option<String>,
+    pub media: Vec<Media>,
+}
+
+pub struct CreateTextMessageInput {
+    pub prompt: String,
+    pub reply: String,
+    pub user_id: ObjectId,
+    pub completion: Option<String>,
 }
@@ -8,6 +8,7 @@ pub struct InsertMessageData {
     pub thread_id: ObjectId,
     pub reply_to_id: Option<ObjectId>,
     pub completion: Option<String>,
+    pub split_id: Option<ObjectId>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -19,5 +20,6 @@ pub struct InsertMessageInput {
     pub thread_id: ObjectId,
     pub reply_to_id: Option<ObjectId>,
     pub completion: Option<String>,
+    pub split_id: Option<ObjectId>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
@@ -1,20 +1,31 @@
 use std::str::FromStr;
 
-use axum::extract::{Path, Query, State};
+use anyhow::anyhow;
+use axum::extract::{Multipart, Path, Query, State};
 use axum::http::StatusCode;
+use axum::response::sse::{Event, Sse};
 use axum::{Extension, Json};
 use bson::oid::ObjectId;
+use futures::Stream;
+use mime2ext::mime2ext;
+use tokio::sync::mpsc;
+use tracing::warn;
 use utoipa::OpenApi;
+use uuid::Uuid;
 
-use crate::api::infer::models::InferMode;
-use crate::api::infer::InferOptions;
+use crate::api::assistant::{AnswerOptions, CommandOptions, Intent};
+use crate::api::duplicate::{Duplicate, DuplicateReason};
+use crate::api::invoice::{owner_prefix, Media};
+use crate::api::job::{CreateJobInput, Job, JobPayload};
 use crate::api::message::{
-    CreateMessageBody, CreateMessageInput, ListMessagesInput, ListMessagesQuery, Message,
-    MessageError,
+    log_expense, message_event_stream, CreateMessageBody, CreateTextMessageInput,
+    CreateVoiceMessageBody, ListMessagesInput, ListMessagesQuery, Message, MessageError,
+    MessageEvent, MessageEventSender,
 };
 use crate::api::state::AppState;
 use crate::api::transaction::Transaction;
 use crate::api::user::User;
+use crate::api::workspace::Scope;
 use crate::common::errors::AppError;
 use crate::common::hooks::ValidJson;
 use crate::common::mongo::{Cursor, FindOptions};
@@ -32,14 +43,14 @@ use crate::object_id;
 )]
 pub async fn list_messages(
     State(state): State<AppState>,
-    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
     Query(query): Query<ListMessagesQuery>,
 ) -> Result<Json<Vec<Message>>, AppError> {
     let messages = state
         .message_service
         .list(
             ListMessagesInput {
-                user_id: object_id!(&user.id),
+                user_id: scope.owner_id,
                 cursor: query.after.map(Cursor::try_from).transpose()?,
             },
             FindOptions {
@@ -63,35 +74,186 @@ pub async fn list_messages(
 pub async fn create_message(
     State(state): State<AppState>,
     Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
     ValidJson(body): ValidJson<CreateMessageBody>,
 ) -> Result<Json<Vec<Message>>, AppError> {
-    let categories = state.category_service.find().await?;
-    let infer_service = state.infer_service_factory.create_service(InferMode::Text);
-
-    let (invoice_tool, completion) = infer_service
-        .infer(
-            body.content.clone(),
-            InferOptions {
-                currencies: vec![user.currency.clone()],
-                categories,
+    let messages = process_message(&state, user, scope.owner_id, body.content, None).await?;
+
+    Ok(Json(messages))
+}
+
+#[utoipa::path(
+    post,
+    path = "/stream",
+    request_body = CreateMessageBody,
+    responses(
+        (status = 200, description = "Stream message processing events (ocr_done, extracted, categorized, saved, failed)", content_type = "text/event-stream"),
+    )
+)]
+pub async fn create_message_stream(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
+    ValidJson(body): ValidJson<CreateMessageBody>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let (events, receiver) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let messages = process_message(
+            &state,
+            user,
+            scope.owner_id,
+            body.content,
+            Some(events.clone()),
+        )
+        .await;
+        let event = match messages {
+            Ok(messages) => MessageEvent::Saved(messages),
+            Err(e) => MessageEvent::Failed {
+                message: e.to_string(),
             },
+        };
+        event.send(Some(&events));
+    });
+
+    message_event_stream(receiver)
+}
+
+#[utoipa::path(
+    post,
+    path = "/voice",
+    request_body(content = CreateVoiceMessageBody, content_type = "multipart/form-data"),
+    responses(
+        (status = 202, description = "Upload successfully, the voice note is transcribed and logged in the background", body = Job),
+    )
+)]
+pub async fn create_voice_message(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<Job>), AppError> {
+    let (content, content_type) = read_audio(multipart).await?;
+    let extension = mime2ext(&content_type).ok_or(MessageError::UnsupportedContentType)?;
+    let path = format!(
+        "{}voice/{}.{}",
+        owner_prefix(scope.owner_id),
+        Uuid::new_v4(),
+        extension
+    );
+    let user_id = object_id!(&user.id);
+
+    // transcribing and reading the expense outlast the request, they run in a job
+    let path = state
+        .r2_service
+        .upload_object(
+            state.settings.invoice.bucket.clone(),
+            path,
+            &content,
+            &content_type,
         )
         .await?;
+    let media = Media {
+        path,
+        content_type,
+        thumbnail_path: None,
+        hash: None,
+    };
 
-    let messages = state
-        .message_service
-        .create(CreateMessageInput {
-            prompt: body.content,
-            currencies: vec![user.currency],
-            user_id: object_id!(&user.id),
-            invoice_tool,
-            completion,
-            media_path: None,
-            media_type: None,
+    let job = state
+        .job_service
+        .enqueue(CreateJobInput {
+            user_id,
+            workspace_id: scope.workspace_id,
+            payload: JobPayload::ProcessVoice {
+                media: media.clone(),
+            },
         })
-        .await?;
+        .await;
+    if job.is_err() {
+        if let Err(e) = state.storage_service.delete_media(user_id, &[media]).await {
+            warn!("voice note left in storage: {e}");
+        }
+    }
 
-    Ok(Json(messages))
+    Ok((StatusCode::ACCEPTED, Json(job?)))
+}
+
+async fn read_audio(mut multipart: Multipart) -> Result<(Vec<u8>, String), AppError> {
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::Unknown(anyhow!(e)))?
+        .ok_or(MessageError::NoAudio)?;
+
+    let content_type = field
+        .content_type()
+        .filter(|content_type| content_type.starts_with("audio/"))
+        .ok_or(MessageError::UnsupportedContentType)?
+        .to_string();
+    let content = field
+        .bytes()
+        .await
+        .map_err(|e| AppError::Unknown(anyhow!(e)))?;
+
+    Ok((content.to_vec(), content_type))
+}
+
+/// Answers the message of the user in the ledger of the owner, the user or a workspace.
+async fn process_message(
+    state: &AppState,
+    user: User,
+    user_id: ObjectId,
+    content: String,
+    events: Option<MessageEventSender>,
+) -> Result<Vec<Message>, AppError> {
+    let intent = state.assistant_service.classify(&content).await?;
+
+    let messages = match intent {
+        Intent::LogExpense => log_expense(state, user, user_id, content, vec![], events).await?,
+        Intent::Question => {
+            let categories = state.category_service.find().await?;
+            let (reply, completion) = state
+                .assistant_service
+                .answer(
+                    content.clone(),
+                    AnswerOptions {
+                        user_id,
+                        currency: user.currency,
+                        categories,
+                    },
+                )
+                .await?;
+
+            state
+                .message_service
+                .create_text(CreateTextMessageInput {
+                    prompt: content,
+                    reply,
+                    user_id,
+                    completion: Some(completion),
+                })
+                .await?
+        }
+        Intent::Command(command) => {
+            let reply = state
+                .assistant_service
+                .run_command(command, CommandOptions { user_id })
+                .await?;
+
+            state
+                .message_service
+                .create_text(CreateTextMessageInput {
+                    prompt: content,
+                    reply,
+                    user_id,
+                    completion: None,
+                })
+                .await?
+        }
+    };
+
+    Ok(messages)
 }
 
 #[utoipa::path(
@@ -106,14 +268,14 @@ pub async fn create_message(
 )]
 pub async fn delete_message(
     State(state): State<AppState>,
-    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
     Path((id,)): Path<(String,)>,
 ) -> Result<StatusCode, AppError> {
     let id = ObjectId::from_str(&id).map_err(|e| AppError::Unknown(e.into()))?;
 
     state
         .message_service
-        .delete_many_by_id(id, object_id!(&user.id))
+        .delete_many_by_id(id, scope.owner_id)
         .await?;
 
     Ok(StatusCode::NO_CONTENT)
@@ -131,12 +293,12 @@ pub async fn delete_message(
 )]
 pub async fn list_transactions(
     State(state): State<AppState>,
-    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
     Path((id,)): Path<(String,)>,
 ) -> Result<Json<Vec<Transaction>>, AppError> {
     let message = state.message_service.find_by_id(object_id!(&id)).await?;
     if let Some(message) = message {
-        if message.to_id != user.id {
+        if message.to_id != scope.owner_id.to_hex() {
             return Err(AppError::Forbidden);
         }
     } else {
@@ -153,11 +315,21 @@ pub async fn list_transactions(
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(list_messages, create_message, delete_message, list_transactions),
+    paths(
+        list_messages,
+        create_message,
+        create_message_stream,
+        create_voice_message,
+        delete_message,
+        list_transactions
+    ),
     components(
         schemas(
             CreateMessageBody,
+            CreateVoiceMessageBody,
             Message,
+            Duplicate,
+            DuplicateReason,
             Transaction,
         )
     ),
@@ -1,6 +1,7 @@
 use bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
 
+use crate::api::group::SplitEntity;
 use crate::api::invoice::InvoiceEntity;
 use crate::api::transaction::TransactionEntity;
 
@@ -15,10 +16,15 @@ pub struct MessageEntity {
     pub thread_id: ObjectId,
     pub reply_to_id: Option<ObjectId>,
     pub completion: Option<String>,
+    // share of a bill split in a group, posted in the thread of each member
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_id: Option<ObjectId>,
     #[serde(skip_serializing)]
     pub invoice: Option<InvoiceEntity>,
     #[serde(skip_serializing)]
     pub transactions: Option<Vec<TransactionEntity>>,
+    #[serde(skip_serializing)]
+    pub split: Option<SplitEntity>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::Stream;
+use serde::Serialize;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+
+use crate::api::infer::models::InvoiceTool;
+use crate::api::message::Message;
+
+/// Progress of a message being processed, streamed to the client as server-sent events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", content = "data", rename_all = "snake_case")]
+pub enum MessageEvent {
+    OcrDone { content: String },
+    Extracted(InvoiceTool),
+    Categorized(InvoiceTool),
+    Saved(Vec<Message>),
+    Failed { message: String },
+}
+
+pub type MessageEventSender = UnboundedSender<MessageEvent>;
+
+impl MessageEvent {
+    pub fn stage(&self) -> &'static str {
+        match self {
+            Self::OcrDone { .. } => "ocr_done",
+            Self::Extracted(_) => "extracted",
+            Self::Categorized(_) => "categorized",
+            Self::Saved(_) => "saved",
+            Self::Failed { .. } => "failed",
+        }
+    }
+
+    /// Sends the event if anyone is listening. A closed stream is not an error, the
+    /// processing still finishes and gets saved.
+    pub fn send(self, events: Option<&MessageEventSender>) {
+        if let Some(events) = events {
+            let _ = events.send(self);
+        }
+    }
+}
+
+pub fn message_event_stream(
+    events: UnboundedReceiver<MessageEvent>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let stream = futures::stream::unfold(events, |mut events| async move {
+        let event = events.recv().await?;
+        let sse_event = Event::default().event(event.stage()).json_data(&event);
+
+        Some((sse_event, events))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(5)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_event_stage() {
+        let events = [
+            MessageEvent::OcrDone {
+                content: "Banh mi 25000".to_string(),
+            },
+            MessageEvent::Saved(vec![]),
+            MessageEvent::Failed {
+                message: "no transactions".to_string(),
+            },
+        ];
+
+        // the sse event name is the same tag the payload carries
+        for event in events {
+            let value = serde_json::to_value(&event).unwrap();
+            assert_eq!(value["stage"], event.stage());
+        }
+
+        let value = serde_json::to_value(MessageEvent::OcrDone {
+            content: "Banh mi 25000".to_string(),
+        })
+        .unwrap();
+        assert_eq!(value["data"]["content"], "Banh mi 25000");
+    }
+
+    #[test]
+    fn test_message_event_send() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        MessageEvent::Saved(vec![]).send(None);
+        MessageEvent::Saved(vec![]).send(Some(&sender));
+        assert_eq!(receiver.try_recv().unwrap().stage(), "saved");
+        assert!(receiver.try_recv().is_err());
+
+        // the client went away, processing carries on
+        drop(receiver);
+        MessageEvent::Failed {
+            message: "closed".to_string(),
+        }
+        .send(Some(&sender));
+    }
+}
@@ -5,6 +5,8 @@ use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use utoipa::ToSchema;
 
+use crate::api::duplicate::Duplicate;
+use crate::api::group::Split;
 use crate::api::invoice::Invoice;
 use crate::api::message::MessageEntity;
 use crate::api::transaction::Transaction;
@@ -28,6 +30,11 @@ pub struct Message {
     pub reply_to_id: Option<String>,
     pub invoice: Option<Invoice>,
     pub transactions: Option<Vec<Transaction>>,
+    /// Bill split in a group, the transactions are the ones of the member who paid
+    pub split: Option<Split>,
+    /// Earlier messages this one likely repeats, only set when it is created
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<Duplicate>,
     #[schema(example = "2024-07-22T13:30:42.246014Z")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[schema(example = "2024-07-22T13:30:42.246017Z")]
@@ -47,6 +54,8 @@ impl From<MessageEntity> for Message {
             transactions: value
                 .transactions
                 .map(|txs| txs.into_iter().map(Into::into).collect()),
+            split: value.split.map(Into::into),
+            duplicates: vec![],
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
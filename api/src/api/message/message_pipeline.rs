@@ -0,0 +1,65 @@
+use bson::oid::ObjectId;
+
+use crate::api::infer::models::InferMode;
+use crate::api::infer::InferOptions;
+use crate::api::invoice::Media;
+use crate::api::message::{CreateMessageInput, Message, MessageEventSender};
+use crate::api::state::AppState;
+use crate::api::user::User;
+use crate::common::errors::AppError;
+
+/// Reads the expense from the text of the user and saves it as a message in the ledger of
+/// the owner, the user or a workspace.
+pub async fn log_expense(
+    state: &AppState,
+    user: User,
+    owner_id: ObjectId,
+    content: String,
+    media: Vec<Media>,
+    events: Option<MessageEventSender>,
+) -> Result<Vec<Message>, AppError> {
+    let categories = state.category_service.find().await?;
+    let infer_service = state.infer_service_factory.create_service(InferMode::Text);
+
+    let (invoice_tool, completion) = infer_service
+        .infer(
+            content.clone(),
+            InferOptions {
+                currencies: vec![user.currency.clone()],
+                categories,
+                events,
+                images: vec![],
+            },
+        )
+        .await?;
+
+    state
+        .message_service
+        .create(CreateMessageInput {
+            prompt: content,
+            currencies: vec![user.currency],
+            user_id: owner_id,
+            language: user.language,
+            invoice_tool,
+            completion,
+            media,
+        })
+        .await
+}
+
+/// Transcribes a stored voice note and logs the expense it tells, the audio is kept with
+/// the message.
+pub async fn log_voice_note(
+    state: &AppState,
+    user: User,
+    owner_id: ObjectId,
+    media: Media,
+) -> Result<Vec<Message>, AppError> {
+    let content = state.invoice_service.read_media(&media).await?;
+    let transcript = state
+        .speech_to_text_service
+        .transcribe(content, &media.content_type, Some(&user.language))
+        .await?;
+
+    log_expense(state, user, owner_id, transcript, vec![media], None).await
+}
@@ -20,6 +20,11 @@ pub trait MessageRepoExt: Send + Sync {
         filter: Document,
         options: FindOptions,
     ) -> Result<Vec<MessageEntity>, MessageError>;
+    async fn find_last_with_transactions(
+        &self,
+        thread_id: ObjectId,
+        from_id: ObjectId,
+    ) -> Result<Option<MessageEntity>, MessageError>;
     async fn find_by_id(&self, id: ObjectId) -> Result<Option<MessageEntity>, MessageError>;
     async fn insert_one(&self, data: InsertMessageData) -> Result<MessageEntity, MessageError>;
     async fn insert_many_with_session(
@@ -32,6 +37,18 @@ pub trait MessageRepoExt: Send + Sync {
         ids: &[ObjectId],
         session: &mut ClientSession,
     ) -> Result<bool, MessageError>;
+    async fn delete_by_split_id_with_session(
+        &self,
+        split_id: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, MessageError>;
+    async fn change_owner_with_session(
+        &self,
+        ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, MessageError>;
 }
 
 pub type MessageRepoDyn = Arc<dyn MessageRepoExt + Send + Sync>;
@@ -45,6 +62,84 @@ impl MessageRepo {
     const DEFAULT_LIMIT: i64 = 20;
 }
 
+/// The transactions of the split a message shares. Plain messages have no `splitId`, and a
+/// `localField` join would match them with every transaction that has none either.
+fn shared_transactions_lookup() -> Document {
+    doc! {
+        "$lookup": {
+            "from": "transactions",
+            "let": { "splitId": { "$ifNull": ["$splitId", null] } },
+            "pipeline": [
+                {
+                    "$match": {
+                        "$expr": {
+                            "$and": [
+                                { "$ne": ["$$splitId", null] },
+                                { "$eq": ["$splitId", "$$splitId"] },
+                            ]
+                        }
+                    }
+                },
+            ],
+            "as": "sharedTransactions"
+        }
+    }
+}
+
+/// Joins a message with its invoice, its transactions and the split it shares.
+fn message_lookups() -> Vec<Document> {
+    vec![
+        doc! {
+            "$lookup": {
+                "from": "invoices",
+                "localField": "_id",
+                "foreignField": "messageId",
+                "as": "invoices"
+            }
+        },
+        doc! {
+            "$lookup": {
+                "from": "transactions",
+                "localField": "_id",
+                "foreignField": "messageId",
+                "as": "transactions"
+            }
+        },
+        // a shared bill links to the transactions of the member who paid it
+        doc! {
+            "$lookup": {
+                "from": "splits",
+                "localField": "splitId",
+                "foreignField": "_id",
+                "as": "splits"
+            }
+        },
+        shared_transactions_lookup(),
+        doc! {
+            "$project": {
+                "_id": 1,
+                "content": 1,
+                "fromId": 1,
+                "toId": 1,
+                "threadId": 1,
+                "replyToId": 1,
+                "splitId": 1,
+                "createdAt": 1,
+                "updatedAt": 1,
+                "invoice": {
+                    "$first": "$invoices"
+                },
+                "transactions": {
+                    "$concatArrays": ["$transactions", "$sharedTransactions"]
+                },
+                "split": {
+                    "$first": "$splits"
+                }
+            }
+        },
+    ]
+}
+
 #[async_trait]
 impl MessageRepoExt for MessageRepo {
     async fn list(
@@ -64,47 +159,12 @@ impl MessageRepoExt for MessageRepo {
 
         let mut cursor = self
             .collection
-            .aggregate(vec![
-                doc! { "$match": filter },
-                doc! {
-                    "$lookup": {
-                        "from": "invoices",
-                        "localField": "_id",
-                        "foreignField": "messageId",
-                        "as": "invoices"
-                    }
-                },
-                doc! {
-                    "$lookup": {
-                        "from": "transactions",
-                        "localField": "_id",
-                        "foreignField": "messageId",
-                        "as": "transactions"
-                    }
-                },
-                doc! {
-                    "$project": {
-                        "_id": 1,
-                        "content": 1,
-                        "fromId": 1,
-                        "toId": 1,
-                        "threadId": 1,
-                        "replyToId": 1,
-                        "createdAt": 1,
-                        "updatedAt": 1,
-                        "invoice": {
-                            "$first": "$invoices"
-                        },
-                        "transactions": 1
-                    }
-                },
-                doc! {
-                    "$sort": { "_id": -1 }
-                },
-                doc! {
-                    "$limit": limit
-                },
-            ])
+            .aggregate(
+                [doc! { "$match": filter }]
+                    .into_iter()
+                    .chain(message_lookups())
+                    .chain([doc! { "$sort": { "_id": -1 } }, doc! { "$limit": limit }]),
+            )
             .await
             .map_err(|e| MessageError::Unknown(e.into()))?;
 
@@ -138,6 +198,39 @@ impl MessageRepoExt for MessageRepo {
         Ok(messages)
     }
 
+    async fn find_last_with_transactions(
+        &self,
+        thread_id: ObjectId,
+        from_id: ObjectId,
+    ) -> Result<Option<MessageEntity>, MessageError> {
+        let mut cursor = self
+            .collection
+            .aggregate(
+                [
+                    doc! { "$match": { "threadId": thread_id, "fromId": from_id } },
+                    doc! { "$sort": { "_id": -1 } },
+                ]
+                .into_iter()
+                .chain(message_lookups())
+                .chain([
+                    doc! { "$match": { "transactions.0": { "$exists": true } } },
+                    doc! { "$limit": 1 },
+                ]),
+            )
+            .await
+            .map_err(|e| MessageError::Unknown(e.into()))?;
+
+        match cursor.next().await {
+            Some(document) => {
+                let document = document.map_err(|e| MessageError::Unknown(e.into()))?;
+                from_document(document)
+                    .map(Some)
+                    .map_err(|e| MessageError::Unknown(e.into()))
+            }
+            None => Ok(None),
+        }
+    }
+
     async fn find_by_id(&self, id: ObjectId) -> Result<Option<MessageEntity>, MessageError> {
         self.collection
             .find_one(doc! { "_id": id })
@@ -154,8 +247,10 @@ impl MessageRepoExt for MessageRepo {
             thread_id: data.thread_id,
             reply_to_id: data.reply_to_id,
             completion: data.completion,
+            split_id: data.split_id,
             invoice: None,
             transactions: None,
+            split: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -183,8 +278,10 @@ impl MessageRepoExt for MessageRepo {
                 thread_id: item.thread_id,
                 reply_to_id: item.reply_to_id,
                 completion: item.completion,
+                split_id: item.split_id,
                 invoice: None,
                 transactions: None,
+                split: None,
                 created_at: item.created_at,
                 updated_at: item.created_at,
             };
@@ -214,4 +311,79 @@ impl MessageRepoExt for MessageRepo {
             .map(|v| v.deleted_count == ids.len() as u64)
             .map_err(|e| MessageError::Unknown(e.into()))
     }
+
+    async fn delete_by_split_id_with_session(
+        &self,
+        split_id: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, MessageError> {
+        self.collection
+            .delete_many(doc! { "splitId": split_id })
+            .session(session)
+            .await
+            .map(|v| v.deleted_count)
+            .map_err(|e| MessageError::Unknown(e.into()))
+    }
+
+    async fn change_owner_with_session(
+        &self,
+        ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, MessageError> {
+        // the owner is on one side of the conversation with the bot
+        let mut modified = 0;
+        for field in ["fromId", "toId"] {
+            modified += self
+                .collection
+                .update_many(
+                    doc! { "_id": { "$in": ids }, "threadId": from, field: from },
+                    doc! {
+                        "$set": { field: to, "threadId": to, "updatedAt": chrono::Utc::now() },
+                    },
+                )
+                .session(&mut *session)
+                .await
+                .map(|v| v.modified_count)
+                .map_err(|e| MessageError::Unknown(e.into()))?;
+        }
+
+        Ok(modified)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_transactions_lookup_skips_plain_messages() {
+        let lookup = shared_transactions_lookup();
+        let lookup = lookup.get_document("$lookup").unwrap();
+
+        // a plain join on a missing field matches every transaction without a split
+        assert!(!lookup.contains_key("localField"));
+        assert!(!lookup.contains_key("foreignField"));
+        assert_eq!(
+            lookup.get_document("let").unwrap(),
+            &doc! { "splitId": { "$ifNull": ["$splitId", null] } }
+        );
+        let stage = lookup.get_array("pipeline").unwrap()[0]
+            .as_document()
+            .unwrap();
+        assert_eq!(
+            stage,
+            &doc! {
+                "$match": {
+                    "$expr": {
+                        "$and": [
+                            { "$ne": ["$$splitId", null] },
+                            { "$eq": ["$splitId", "$$splitId"] },
+                        ]
+                    }
+                }
+            }
+        );
+    }
 }
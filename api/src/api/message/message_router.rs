@@ -1,3 +1,4 @@
+use axum::extract::DefaultBodyLimit;
 use axum::middleware::from_fn_with_state;
 use axum::routing::{delete, get, post};
 use axum::Router;
@@ -6,6 +7,9 @@ use crate::api::message::message_controller::*;
 use crate::api::state::AppState;
 use crate::mw::authorization_mw;
 
+// 5MB, about a minute of voice
+const MAX_VOICE_SIZE: usize = 5 * 1024 * 1024;
+
 pub struct MessageRouter(Router<AppState>);
 
 impl MessageRouter {
@@ -13,6 +17,11 @@ impl MessageRouter {
         let routes = Router::new()
             .route("/", get(list_messages))
             .route("/", post(create_message))
+            .route("/stream", post(create_message_stream))
+            .route(
+                "/voice",
+                post(create_voice_message).layer(DefaultBodyLimit::max(MAX_VOICE_SIZE)),
+            )
             .route("/:id", delete(delete_message))
             .route("/:id/transactions", get(list_transactions))
             .route_layer(from_fn_with_state(state.clone(), authorization_mw));
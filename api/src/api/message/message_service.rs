@@ -1,6 +1,12 @@
+use crate::api::account::{transfer_accounts, AccountServiceDyn};
+use crate::api::asset::format_money_in;
+use crate::api::category::CategoryServiceDyn;
+use crate::api::debt::{CreateDebtInput, DebtKind, DebtServiceDyn};
+use crate::api::duplicate::{DuplicateServiceDyn, FindDuplicatesInput};
 use crate::api::invoice::{CreateInvoiceInput, InvoiceServiceDyn};
 use crate::api::message::*;
-use crate::api::transaction::{InsertTransactionInput, TransactionServiceDyn};
+use crate::api::storage::StorageServiceDyn;
+use crate::api::transaction::{InsertTransactionInput, TransactionError, TransactionServiceDyn};
 use crate::common::errors::AppError;
 use crate::common::mongo::FindOptions;
 use crate::object_id;
@@ -8,10 +14,11 @@ use async_trait::async_trait;
 use bson::doc;
 use bson::oid::ObjectId;
 use futures::FutureExt;
+use itertools::Itertools;
 use mongodb::{Client, ClientSession};
 use std::str::FromStr;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
 #[async_trait]
 pub trait MessageServiceExt: Send + Sync {
@@ -21,6 +28,8 @@ pub trait MessageServiceExt: Send + Sync {
         options: FindOptions,
     ) -> Result<Vec<Message>, AppError>;
     async fn find_by_id(&self, id: ObjectId) -> Result<Option<Message>, AppError>;
+    /// The latest reply of the bot in the thread that logged transactions.
+    async fn find_last_logged(&self, user_id: ObjectId) -> Result<Option<Message>, AppError>;
     async fn insert_one(&self, input: InsertMessageInput) -> Result<Message, AppError>;
     async fn insert_many_with_session(
         &self,
@@ -28,7 +37,23 @@ pub trait MessageServiceExt: Send + Sync {
         session: &mut ClientSession,
     ) -> Result<Vec<Message>, AppError>;
     async fn create(&self, input: CreateMessageInput) -> Result<Vec<Message>, AppError>;
+    async fn create_text(&self, input: CreateTextMessageInput) -> Result<Vec<Message>, AppError>;
     async fn delete_many_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, AppError>;
+    /// Removes the shares of a split from the threads of the members.
+    async fn delete_by_split_id_with_session(
+        &self,
+        split_id: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError>;
+    /// Moves the messages of invoices, with the messages they reply to, to the thread of
+    /// the new owner.
+    async fn change_owner_with_session(
+        &self,
+        invoice_message_ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError>;
 }
 
 pub type MessageServiceDyn = Arc<dyn MessageServiceExt + Send + Sync>;
@@ -38,7 +63,12 @@ pub struct MessageService {
     pub repo: MessageRepoDyn,
     pub mongo_client: Client,
     pub transaction_service: TransactionServiceDyn,
+    pub account_service: AccountServiceDyn,
+    pub debt_service: DebtServiceDyn,
     pub invoice_service: InvoiceServiceDyn,
+    pub category_service: CategoryServiceDyn,
+    pub duplicate_service: DuplicateServiceDyn,
+    pub storage_service: StorageServiceDyn,
 }
 
 impl MessageService {
@@ -47,6 +77,84 @@ impl MessageService {
     pub fn default_bot_id() -> ObjectId {
         ObjectId::from_str(Self::DEFAULT_BOT_ID).unwrap()
     }
+
+    async fn make_reply(&self, input: &CreateMessageInput) -> Result<String, AppError> {
+        let categories = self.category_service.find().await?;
+        let invoice_tool = &input.invoice_tool;
+
+        let category_names = invoice_tool
+            .transactions
+            .iter()
+            .map(|tx| {
+                categories
+                    .iter()
+                    .find(|category| category.id == tx.category_id)
+                    .map_or(tx.category_id.clone(), |category| {
+                        category.label(&input.language).to_string()
+                    })
+            })
+            .unique()
+            .collect::<Vec<String>>();
+
+        Ok(make_logged_reply(
+            &input.language,
+            invoice_tool.transactions.len(),
+            &format_money_in(invoice_tool.total, &invoice_tool.currency, &input.language),
+            &category_names,
+        ))
+    }
+
+    /// Counterparty and kind of each transaction, set on debts only. People named for the
+    /// first time are added as counterparties.
+    async fn debts(
+        &self,
+        input: &CreateMessageInput,
+        session: &mut ClientSession,
+    ) -> Result<Vec<Option<(ObjectId, DebtKind)>>, AppError> {
+        let mut debts = vec![];
+        for tx in &input.invoice_tool.transactions {
+            let debt = match (&tx.counterparty, tx.debt_kind) {
+                (Some(name), Some(kind)) if tx.r#type == "debt" => {
+                    let counterparty = self
+                        .debt_service
+                        .find_or_create_counterparty_with_session(input.user_id, name, session)
+                        .await?;
+                    Some((object_id!(&counterparty.id), kind))
+                }
+                _ => None,
+            };
+            debts.push(debt);
+        }
+
+        Ok(debts)
+    }
+
+    /// Source and destination accounts of each transaction, set on transfers only.
+    async fn transfer_accounts(
+        &self,
+        input: &CreateMessageInput,
+        card_account_id: Option<ObjectId>,
+    ) -> Result<Vec<(Option<ObjectId>, Option<ObjectId>)>, AppError> {
+        let transactions = &input.invoice_tool.transactions;
+        if transactions.iter().all(|tx| tx.r#type != "transfer") {
+            return Ok(vec![(None, None); transactions.len()]);
+        }
+
+        let accounts = self.account_service.find_by_user_id(input.user_id).await?;
+
+        transactions
+            .iter()
+            .map(|tx| {
+                if tx.r#type != "transfer" {
+                    return Ok((None, None));
+                }
+                // a card on the receipt is the one the money left
+                let (from, to) =
+                    transfer_accounts(&accounts, card_account_id, tx.from_account, tx.to_account)?;
+                Ok((Some(from), Some(to)))
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -77,6 +185,14 @@ impl MessageServiceExt for MessageService {
             .map_err(Into::into)
     }
 
+    async fn find_last_logged(&self, user_id: ObjectId) -> Result<Option<Message>, AppError> {
+        self.repo
+            .find_last_with_transactions(user_id, Self::default_bot_id())
+            .await
+            .map(|v| v.map(Into::into))
+            .map_err(Into::into)
+    }
+
     async fn insert_one(&self, input: InsertMessageInput) -> Result<Message, AppError> {
         self.repo
             .insert_one(InsertMessageData {
@@ -87,6 +203,7 @@ impl MessageServiceExt for MessageService {
                 thread_id: input.thread_id,
                 reply_to_id: input.reply_to_id,
                 completion: input.completion,
+                split_id: input.split_id,
                 created_at: input.created_at,
             })
             .await
@@ -111,6 +228,7 @@ impl MessageServiceExt for MessageService {
                         thread_id: item.thread_id,
                         reply_to_id: item.reply_to_id,
                         completion: item.completion,
+                        split_id: item.split_id,
                         created_at: item.created_at,
                     })
                     .collect(),
@@ -125,6 +243,31 @@ impl MessageServiceExt for MessageService {
         let user_message_id = ObjectId::new();
         let bot_message_id = ObjectId::new();
         let invoice_id = ObjectId::new();
+        let reply = self.make_reply(&input).await?;
+        // looked up before saving, so the new invoice does not match itself. Only a hint,
+        // the receipt is saved without it when the lookup fails.
+        let duplicates = self
+            .duplicate_service
+            .find(&FindDuplicatesInput {
+                user_id: input.user_id,
+                invoice_tool: input.invoice_tool.clone(),
+                media: input.media.clone(),
+            })
+            .await
+            .unwrap_or_else(|e| {
+                warn!("duplicates not looked up: {e}");
+                vec![]
+            });
+        // the card printed on the receipt tells which account paid
+        let account_id = match input.invoice_tool.card_number {
+            Some(card_number) => self
+                .account_service
+                .find_by_card_number(input.user_id, card_number)
+                .await?
+                .map(|account| object_id!(&account.id)),
+            None => None,
+        };
+        let transfer_accounts = &self.transfer_accounts(&input, account_id).await?;
 
         let mut session = self
             .mongo_client
@@ -133,7 +276,7 @@ impl MessageServiceExt for MessageService {
             .map_err(|e| AppError::Unknown(e.into()))?;
         let (invoice, messages, txs) = session
             .start_transaction()
-            .and_run(&input, |session, input| {
+            .and_run((&input, &reply), |session, (input, reply)| {
                 async move {
                     let invoice_tool = input.invoice_tool.clone();
                     let completion = input.completion.clone();
@@ -155,8 +298,8 @@ impl MessageServiceExt for MessageService {
                                 total: invoice_tool.total,
                                 currency: invoice_tool.currency.clone(),
                                 card_number: invoice_tool.card_number.clone(),
-                                media_path: input.media_path.clone(),
-                                media_type: input.media_type.clone(),
+                                seller: invoice_tool.seller.clone(),
+                                media: input.media.clone(),
                                 issued_at: invoice_tool.issued_at,
                             },
                             session,
@@ -169,12 +312,13 @@ impl MessageServiceExt for MessageService {
                             vec![
                                 InsertMessageInput {
                                     id: bot_message_id,
-                                    content: "".to_string(),
+                                    content: reply.clone(),
                                     from_id: Self::default_bot_id(),
                                     to_id: input.user_id,
                                     thread_id: input.user_id,
                                     reply_to_id: Some(user_message_id),
                                     completion: Some(completion.clone()),
+                                    split_id: None,
                                     created_at: chrono::Utc::now() + chrono::Duration::seconds(1),
                                 },
                                 InsertMessageInput {
@@ -185,6 +329,7 @@ impl MessageServiceExt for MessageService {
                                     thread_id: input.user_id,
                                     reply_to_id: None,
                                     completion: None,
+                                    split_id: None,
                                     created_at: chrono::Utc::now(),
                                 },
                             ],
@@ -197,10 +342,13 @@ impl MessageServiceExt for MessageService {
                         .transactions
                         .clone()
                         .into_iter()
-                        .map(|tx| InsertTransactionInput {
+                        .zip(transfer_accounts)
+                        .map(|(tx, (from, to))| InsertTransactionInput {
                             message_id: bot_message_id,
                             user_id: input.user_id.clone(),
                             invoice_id,
+                            account_id: from.or(account_id),
+                            to_account_id: *to,
 
                             title: tx.title,
                             amount: tx.amount,
@@ -218,6 +366,32 @@ impl MessageServiceExt for MessageService {
                         .await
                         .map_err(|e| mongodb::error::Error::custom(e))?;
 
+                    // new counterparties are only kept with the debts they are read from
+                    let debts = self
+                        .debts(input, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+                    let debt_items = transactions
+                        .iter()
+                        .zip(&debts)
+                        .filter_map(|(tx, debt)| {
+                            debt.map(|(counterparty_id, kind)| CreateDebtInput {
+                                user_id: input.user_id,
+                                counterparty_id,
+                                transaction_id: Some(object_id!(&tx.id)),
+                                kind,
+                                amount: tx.amount,
+                                currency: tx.currency.clone(),
+                                note: Some(tx.title.clone()),
+                                issued_at: tx.issued_at,
+                            })
+                        })
+                        .collect::<Vec<CreateDebtInput>>();
+                    self.debt_service
+                        .insert_many_with_session(&debt_items, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+
                     Ok((invoice, messages, transactions))
                 }
                 .boxed()
@@ -228,10 +402,59 @@ impl MessageServiceExt for MessageService {
         let mut messages = messages;
         messages[0].invoice = Some(invoice);
         messages[0].transactions = Some(txs);
+        messages[0].duplicates = duplicates;
 
         Ok(messages)
     }
 
+    async fn create_text(&self, input: CreateTextMessageInput) -> Result<Vec<Message>, AppError> {
+        let user_message_id = ObjectId::new();
+
+        let mut session = self
+            .mongo_client
+            .start_session()
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))?;
+        session
+            .start_transaction()
+            .and_run(&input, |session, input| {
+                async move {
+                    self.insert_many_with_session(
+                        vec![
+                            InsertMessageInput {
+                                id: ObjectId::new(),
+                                content: input.reply.clone(),
+                                from_id: Self::default_bot_id(),
+                                to_id: input.user_id,
+                                thread_id: input.user_id,
+                                reply_to_id: Some(user_message_id),
+                                completion: input.completion.clone(),
+                                split_id: None,
+                                created_at: chrono::Utc::now() + chrono::Duration::seconds(1),
+                            },
+                            InsertMessageInput {
+                                id: user_message_id,
+                                content: input.prompt.clone(),
+                                from_id: input.user_id,
+                                to_id: Self::default_bot_id(),
+                                thread_id: input.user_id,
+                                reply_to_id: None,
+                                completion: None,
+                                split_id: None,
+                                created_at: chrono::Utc::now(),
+                            },
+                        ],
+                        session,
+                    )
+                    .await
+                    .map_err(mongodb::error::Error::custom)
+                }
+                .boxed()
+            })
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))
+    }
+
     async fn delete_many_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<bool, AppError> {
         let mut session = self
             .mongo_client
@@ -280,8 +503,12 @@ impl MessageServiceExt for MessageService {
         } else {
             (None, vec![])
         };
+        // the split goes first, the group keeps the shares of the receipt until then
+        if transactions.iter().any(|tx| tx.split_id.is_some()) {
+            return Err(TransactionError::Split.into());
+        }
 
-        session
+        let deleted = session
             .start_transaction()
             .and_run(
                 (&messages, &invoice, transactions),
@@ -309,6 +536,13 @@ impl MessageServiceExt for MessageService {
                             .delete_many_by_ids_with_session(transaction_ids.as_slice(), session)
                             .await
                             .map_err(|e| mongodb::error::Error::custom(e))?;
+                        self.debt_service
+                            .delete_by_transaction_ids_with_session(
+                                transaction_ids.as_slice(),
+                                session,
+                            )
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
 
                         Ok(true)
                     }
@@ -316,6 +550,57 @@ impl MessageServiceExt for MessageService {
                 },
             )
             .await
-            .map_err(|e| AppError::Unknown(e.into()))
+            .map_err(|e| AppError::Unknown(e.into()))?;
+
+        // the files are only deleted once nothing refers to them anymore. The message is
+        // already gone, the files left are found by the storage sweep.
+        if let Some(invoice) = &invoice {
+            if let Err(e) = self
+                .storage_service
+                .delete_media(user_id, &invoice.media)
+                .await
+            {
+                warn!("media of the deleted message left in storage: {e}");
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    async fn delete_by_split_id_with_session(
+        &self,
+        split_id: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError> {
+        self.repo
+            .delete_by_split_id_with_session(split_id, session)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn change_owner_with_session(
+        &self,
+        invoice_message_ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError> {
+        let replies = self
+            .repo
+            .find(
+                doc! { "_id": { "$in": invoice_message_ids }, "threadId": from },
+                FindOptions::with_limit(invoice_message_ids.len() as i64),
+            )
+            .await?;
+        let ids = replies
+            .iter()
+            .flat_map(|message| [Some(message.id), message.reply_to_id])
+            .flatten()
+            .collect::<Vec<_>>();
+
+        self.repo
+            .change_owner_with_session(&ids, from, to, session)
+            .await
+            .map_err(Into::into)
     }
 }
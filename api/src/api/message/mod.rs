@@ -3,7 +3,9 @@ pub use dto::*;
 #[allow(unused_imports)]
 pub use message_controller::MessageApiDoc;
 pub(crate) use message_entity::*;
+pub use message_event::*;
 pub use message_model::*;
+pub(crate) use message_pipeline::*;
 pub(crate) use message_repo::*;
 pub use message_router::*;
 pub use message_service::*;
@@ -12,7 +14,9 @@ mod constants;
 mod dto;
 mod message_controller;
 mod message_entity;
+mod message_event;
 mod message_model;
+mod message_pipeline;
 mod message_repo;
 mod message_router;
 mod message_service;
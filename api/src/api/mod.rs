@@ -1,15 +1,25 @@
+pub mod account;
 pub mod asset;
+pub mod assistant;
 pub mod auth;
 pub mod budget;
 pub mod category;
+pub mod debt;
+pub mod duplicate;
+pub mod email;
 pub mod exchange_rate;
+pub mod group;
 pub mod identity;
 mod infer;
 pub mod invoice;
+pub mod job;
 pub mod message;
 pub mod report;
+pub mod reprocess;
 pub mod router;
 pub mod state;
+pub mod storage;
 pub mod transaction;
 mod usage;
 pub mod user;
+pub mod workspace;
@@ -8,9 +8,8 @@ use utoipa::OpenApi;
 
 use crate::api::report::{ExpenseByRange, ReportError, ReportExpensesByRangeQuery};
 use crate::api::state::AppState;
-use crate::api::user::User;
+use crate::api::workspace::Scope;
 use crate::common::errors::AppError;
-use crate::object_id;
 
 #[utoipa::path(
     get,
@@ -24,7 +23,7 @@ use crate::object_id;
 )]
 pub async fn report_expenses_by_range(
     State(state): State<AppState>,
-    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
     Query(query): Query<ReportExpensesByRangeQuery>,
 ) -> Result<Json<Vec<ExpenseByRange>>, AppError> {
     let from = NaiveDate::from_str(&query.from).map_err(|e| AppError::Unknown(e.into()))?;
@@ -63,7 +62,7 @@ pub async fn report_expenses_by_range(
 
     let expenses = state
         .report_service
-        .get_expenses_by_range(object_id!(&user.id), from_datetime, to_datetime)
+        .get_expenses_by_range(scope.owner_id, from_datetime, to_datetime)
         .await?;
 
     Ok(Json(expenses))
@@ -51,9 +51,69 @@ impl ReportRepoExt for ReportRepo {
                             doc! {
                                 "userId": user_id,
                             },
+                            // money moved between accounts is neither earned nor spent
+                            doc! {
+                                "type": doc! {
+                                    "$ne": "transfer",
+                                },
+                            },
+                            // a bill shared in a group counts for the share of each member
+                            doc! {
+                                "splitId": doc! {
+                                    "$exists": false,
+                                },
+                            },
                         ]
                     }
                 },
+                doc! {
+                    "$unionWith": doc! {
+                        "coll": "splits",
+                        "pipeline": [
+                            doc! {
+                                "$match": doc! {
+                                    "allocations.userId": user_id,
+                                },
+                            },
+                            doc! {
+                                "$unwind": "$allocations",
+                            },
+                            doc! {
+                                "$match": doc! {
+                                    "allocations.userId": user_id,
+                                },
+                            },
+                            doc! {
+                                "$lookup": doc! {
+                                    "from": "transactions",
+                                    "localField": "allocations.transactionId",
+                                    "foreignField": "_id",
+                                    "as": "transaction",
+                                },
+                            },
+                            doc! {
+                                "$unwind": "$transaction",
+                            },
+                            doc! {
+                                "$match": doc! {
+                                    "transaction.issuedAt": doc! {
+                                        "$gte": from,
+                                        "$lt": to,
+                                    },
+                                },
+                            },
+                            doc! {
+                                "$project": doc! {
+                                    "issuedAt": "$transaction.issuedAt",
+                                    "userId": "$allocations.userId",
+                                    "amount": "$allocations.amount",
+                                    "type": "$transaction.type",
+                                    "categoryId": "$transaction.categoryId",
+                                },
+                            },
+                        ],
+                    },
+                },
                 doc! {
                     "$project": doc! {
                         "issuedAt": doc! {
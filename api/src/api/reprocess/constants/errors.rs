@@ -0,0 +1,30 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+use crate::common::errors::ErrorResponse;
+
+#[derive(Error, Debug)]
+pub enum ReprocessError {
+    #[error("invalid admin secret")]
+    Unauthorized,
+    #[error("invoice has no receipt to read again")]
+    NoReceipt,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for ReprocessError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()),
+            Self::NoReceipt => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
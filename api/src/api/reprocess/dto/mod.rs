@@ -0,0 +1,3 @@
+mod reprocess_invoice_dto;
+
+pub use reprocess_invoice_dto::*;
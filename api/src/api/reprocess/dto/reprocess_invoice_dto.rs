@@ -0,0 +1,46 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::invoice::ReplaceInvoiceInput;
+use crate::api::reprocess::InvoiceDiff;
+use crate::api::transaction::{InsertTransactionInput, ReplaceTransactionInput};
+
+/// The writes that bring an invoice and its transactions in line with a new extraction.
+pub struct ReprocessPlan {
+    pub diff: InvoiceDiff,
+    pub invoice: ReplaceInvoiceInput,
+    pub updated: Vec<ReplaceTransactionInput>,
+    pub added: Vec<InsertTransactionInput>,
+    pub removed: Vec<ObjectId>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprocessInvoiceBody {
+    /// Only compute the diff, nothing is saved
+    #[serde(default)]
+    #[schema(example = false)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReprocessBatchBody {
+    /// Only the invoices of this user
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: Option<String>,
+    /// Invoices created at or after
+    #[schema(example = "2024-07-01T00:00:00Z")]
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Invoices created before
+    #[schema(example = "2024-08-01T00:00:00Z")]
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    #[schema(example = 100)]
+    #[validate(range(min = 1, max = 1000))]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    #[schema(example = true)]
+    pub dry_run: bool,
+}
@@ -0,0 +1,18 @@
+pub(crate) use constants::*;
+pub(crate) use dto::*;
+#[allow(unused_imports)]
+pub use reprocess_controller::ReprocessApiDoc;
+pub(crate) use reprocess_diff::*;
+pub use reprocess_model::*;
+pub(crate) use reprocess_pipeline::*;
+pub use reprocess_router::*;
+pub use reprocess_service::*;
+
+mod constants;
+mod dto;
+mod reprocess_controller;
+mod reprocess_diff;
+mod reprocess_model;
+mod reprocess_pipeline;
+mod reprocess_router;
+mod reprocess_service;
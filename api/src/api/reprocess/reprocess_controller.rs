@@ -0,0 +1,150 @@
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::{Extension, Json};
+use bson::oid::ObjectId;
+use futures::future::try_join_all;
+use utoipa::OpenApi;
+
+use crate::api::invoice::InvoiceError;
+use crate::api::job::{CreateJobInput, Job, JobPayload};
+use crate::api::reprocess::{
+    FieldChange, InvoiceDiff, ReprocessBatchBody, ReprocessError, ReprocessInvoiceBody,
+    TransactionChange, TransactionChangeKind,
+};
+use crate::api::state::AppState;
+use crate::api::user::{User, UserError};
+use crate::api::workspace::Scope;
+use crate::common::errors::AppError;
+use crate::common::hooks::ValidJson;
+use crate::common::mongo::FindOptions;
+use crate::common::secret::secret_matches;
+use crate::object_id;
+
+pub const ADMIN_SECRET_HEADER: &str = "x-admin-secret";
+
+// invoices enqueued by one batch request when no limit is given
+const DEFAULT_BATCH_LIMIT: i64 = 100;
+
+#[utoipa::path(
+    post,
+    path = "/invoices/{invoice_id}",
+    request_body = ReprocessInvoiceBody,
+    responses(
+        (status = 202, description = "The receipt is read again in the background, the job holds the diff", body = Job),
+    ),
+    params(
+        ("invoice_id" = String, Path, description = "Invoice database id"),
+    )
+)]
+pub async fn reprocess_invoice(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
+    Path(invoice_id): Path<String>,
+    Json(body): Json<ReprocessInvoiceBody>,
+) -> Result<(StatusCode, Json<Job>), AppError> {
+    let invoice = state
+        .invoice_service
+        .find_by_id(object_id!(&invoice_id))
+        .await?
+        .filter(|invoice| invoice.user_id == scope.owner_id.to_hex())
+        .ok_or(InvoiceError::NotFound)?;
+
+    let job = state
+        .job_service
+        .enqueue(CreateJobInput {
+            user_id: object_id!(&user.id),
+            workspace_id: scope.workspace_id,
+            payload: JobPayload::ReprocessInvoice {
+                invoice_id: invoice.id,
+                dry_run: body.dry_run,
+            },
+        })
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/batch",
+    request_body = ReprocessBatchBody,
+    responses(
+        (status = 202, description = "One job is enqueued per invoice with a stored receipt", body = [Job]),
+    ),
+    params(
+        ("x-admin-secret" = String, Header, description = "Admin secret"),
+    )
+)]
+pub async fn reprocess_batch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    ValidJson(body): ValidJson<ReprocessBatchBody>,
+) -> Result<(StatusCode, Json<Vec<Job>>), AppError> {
+    let config = &state.settings.admin;
+    let secret = headers
+        .get(ADMIN_SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !secret_matches(secret, &config.secret) {
+        return Err(ReprocessError::Unauthorized.into());
+    }
+
+    let user_id = body
+        .user_id
+        .map(|id| ObjectId::parse_str(id).map_err(|_| UserError::NotFound))
+        .transpose()?;
+    let invoices = state
+        .invoice_service
+        .find_with_media(
+            user_id,
+            body.from,
+            body.to,
+            FindOptions::with_limit(body.limit.unwrap_or(DEFAULT_BATCH_LIMIT)),
+        )
+        .await?;
+
+    // the invoices of a workspace are stored with the workspace id, an owner reads them
+    let (state, dry_run) = (&state, body.dry_run);
+    let jobs = try_join_all(invoices.into_iter().map(|invoice| async move {
+        let (user_id, workspace_id) = state
+            .workspace_service
+            .ledger_member(object_id!(&invoice.user_id))
+            .await?;
+
+        state
+            .job_service
+            .enqueue(CreateJobInput {
+                user_id,
+                workspace_id,
+                payload: JobPayload::ReprocessInvoice {
+                    invoice_id: invoice.id,
+                    dry_run,
+                },
+            })
+            .await
+    }))
+    .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(jobs)))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(reprocess_invoice, reprocess_batch),
+    components(
+        schemas(
+            ReprocessInvoiceBody,
+            ReprocessBatchBody,
+            InvoiceDiff,
+            FieldChange,
+            TransactionChange,
+            TransactionChangeKind,
+            Job,
+        )
+    ),
+    tags(
+        (name = "crate::api::reprocess", description = "Reprocess API")
+    )
+)]
+pub struct ReprocessApiDoc;
@@ -0,0 +1,425 @@
+use serde::Serialize;
+use serde_json::json;
+
+use crate::api::duplicate::title_similarity;
+use crate::api::infer::models::{InvoiceTool, TransactionTool};
+use crate::api::invoice::{Invoice, ReplaceInvoiceInput, Tax};
+use crate::api::reprocess::*;
+use crate::api::transaction::{InsertTransactionInput, ReplaceTransactionInput, Transaction};
+use crate::object_id;
+
+// titles read again may be spelled differently, e.g. "BANH MI THIT" and "Banh mi"
+const MIN_TITLE_SIMILARITY: f64 = 0.5;
+
+/// Collects the fields whose extracted value differs from the stored one.
+struct Merge<'a> {
+    edited_fields: &'a [String],
+    changes: Vec<FieldChange>,
+}
+
+impl<'a> Merge<'a> {
+    fn new(edited_fields: &'a [String]) -> Self {
+        Self {
+            edited_fields,
+            changes: vec![],
+        }
+    }
+
+    /// Takes the extracted value, unless the user edited the field.
+    fn field<T: Serialize>(&mut self, field: &str, current: T, extracted: T) -> T {
+        let before = serde_json::to_value(&current).unwrap_or_default();
+        let after = serde_json::to_value(&extracted).unwrap_or_default();
+        if before == after {
+            return current;
+        }
+
+        let kept = self.edited_fields.iter().any(|edited| edited == field);
+        self.changes.push(FieldChange {
+            field: field.to_string(),
+            before,
+            after,
+            kept,
+        });
+
+        if kept {
+            current
+        } else {
+            extracted
+        }
+    }
+}
+
+/// Pairs the stored transactions with the extracted ones, by similar titles first. The
+/// rest are paired in order, a misread title is still the same line of the receipt.
+/// Returns `(stored, extracted)` indexes, a missing side is a removed or added transaction.
+pub fn match_transactions(
+    current: &[&str],
+    extracted: &[&str],
+) -> Vec<(Option<usize>, Option<usize>)> {
+    let mut pairs = vec![None; current.len()];
+    let mut used = vec![false; extracted.len()];
+
+    for (index, title) in current.iter().enumerate() {
+        let best = extracted
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| !used[*other])
+            .map(|(other, other_title)| (other, title_similarity(title, other_title)))
+            .filter(|(_, similarity)| *similarity >= MIN_TITLE_SIMILARITY)
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)));
+        if let Some((other, _)) = best {
+            pairs[index] = Some(other);
+            used[other] = true;
+        }
+    }
+
+    let mut rest = (0..extracted.len()).filter(|other| !used[*other]);
+    for pair in pairs.iter_mut().filter(|pair| pair.is_none()) {
+        *pair = rest.next();
+    }
+
+    pairs
+        .into_iter()
+        .enumerate()
+        .map(|(index, other)| (Some(index), other))
+        .chain(rest.map(|other| (None, Some(other))))
+        .collect()
+}
+
+/// Compares a new extraction of the receipt with the stored invoice and transactions.
+/// Fields the user edited keep their value, and so do removed transactions they edited.
+pub fn plan_reprocess(
+    invoice: &Invoice,
+    transactions: &[Transaction],
+    extracted: InvoiceTool,
+) -> ReprocessPlan {
+    let invoice_id = object_id!(&invoice.id);
+    let user_id = object_id!(&invoice.user_id);
+    let message_id = object_id!(&invoice.message_id);
+    // new line items are paid from the same account as the others
+    let account_id = transactions
+        .iter()
+        .find_map(|tx| tx.account_id.as_ref())
+        .map(|id| object_id!(id));
+
+    let mut merge = Merge::new(&invoice.edited_fields);
+    let taxes = merge.field(
+        "taxes",
+        invoice.taxes.clone(),
+        extracted.taxes.into_iter().map(Tax::from).collect(),
+    );
+    let discounts = merge.field(
+        "discounts",
+        invoice.discounts.clone(),
+        extracted.discounts.into_iter().map(Into::into).collect(),
+    );
+    let subtotal = merge.field("subtotal", invoice.subtotal, extracted.subtotal);
+    let total = merge.field("total", invoice.total, extracted.total);
+    let currency = merge.field("currency", invoice.currency.clone(), extracted.currency);
+    let card_number = merge.field("cardNumber", invoice.card_number, extracted.card_number);
+    let seller = merge.field("seller", invoice.seller.clone(), extracted.seller);
+    // receipts without a readable date are dated when they are read
+    let extracted_issued_at = if extracted.issued_at > invoice.created_at {
+        invoice.issued_at
+    } else {
+        extracted.issued_at
+    };
+    let issued_at = merge.field("issuedAt", invoice.issued_at, extracted_issued_at);
+    let invoice_changes = merge.changes;
+
+    let titles = transactions
+        .iter()
+        .map(|tx| tx.title.as_str())
+        .collect::<Vec<_>>();
+    let extracted_titles = extracted
+        .transactions
+        .iter()
+        .map(|tx| tx.title.as_str())
+        .collect::<Vec<_>>();
+
+    let mut changes = vec![];
+    let mut updated = vec![];
+    let mut added = vec![];
+    let mut removed = vec![];
+    for pair in match_transactions(&titles, &extracted_titles) {
+        match pair {
+            (Some(index), Some(other)) => {
+                let tx = &transactions[index];
+                let new_tx = &extracted.transactions[other];
+
+                let mut merge = Merge::new(&tx.edited_fields);
+                let title = merge.field("title", tx.title.clone(), new_tx.title.clone());
+                let amount = merge.field("amount", tx.amount, new_tx.amount);
+                let quantity = merge.field("quantity", tx.quantity, new_tx.quantity);
+                let unit = merge.field("unit", tx.unit.clone(), new_tx.unit.clone());
+                let category_id = merge.field(
+                    "categoryId",
+                    tx.category_id.clone(),
+                    new_tx.category_id.clone(),
+                );
+                let r#type = merge.field("type", tx.r#type.clone(), new_tx.r#type.clone());
+                // purchased items have no currency or date of their own, they follow the invoice
+                let tx_currency = merge.field(
+                    "currency",
+                    tx.currency.clone(),
+                    if currency != invoice.currency {
+                        currency.clone()
+                    } else {
+                        tx.currency.clone()
+                    },
+                );
+                let tx_issued_at = merge.field(
+                    "issuedAt",
+                    tx.issued_at,
+                    if issued_at != invoice.issued_at {
+                        issued_at
+                    } else {
+                        tx.issued_at
+                    },
+                );
+
+                if merge.changes.is_empty() {
+                    continue;
+                }
+                changes.push(TransactionChange {
+                    kind: TransactionChangeKind::Changed,
+                    transaction_id: Some(tx.id.clone()),
+                    title: title.clone(),
+                    fields: merge.changes,
+                    kept: false,
+                });
+                updated.push(ReplaceTransactionInput {
+                    id: object_id!(&tx.id),
+                    title,
+                    amount,
+                    currency: tx_currency,
+                    category_id,
+                    r#type,
+                    unit,
+                    quantity,
+                    issued_at: tx_issued_at,
+                });
+            }
+            (Some(index), None) => {
+                let tx = &transactions[index];
+                let kept = !tx.edited_fields.is_empty();
+                if !kept {
+                    removed.push(object_id!(&tx.id));
+                }
+                changes.push(TransactionChange {
+                    kind: TransactionChangeKind::Removed,
+                    transaction_id: Some(tx.id.clone()),
+                    title: tx.title.clone(),
+                    fields: vec![],
+                    kept,
+                });
+            }
+            (None, Some(other)) => {
+                let new_tx = &extracted.transactions[other];
+                changes.push(TransactionChange {
+                    kind: TransactionChangeKind::Added,
+                    transaction_id: None,
+                    title: new_tx.title.clone(),
+                    fields: added_fields(new_tx),
+                    kept: false,
+                });
+                added.push(InsertTransactionInput {
+                    message_id,
+                    user_id,
+                    invoice_id,
+                    account_id,
+                    to_account_id: None,
+                    title: new_tx.title.clone(),
+                    amount: new_tx.amount,
+                    currency: currency.clone(),
+                    category_id: new_tx.category_id.clone(),
+                    r#type: new_tx.r#type.clone(),
+                    unit: new_tx.unit.clone(),
+                    quantity: new_tx.quantity,
+                    issued_at,
+                });
+            }
+            (None, None) => {}
+        }
+    }
+
+    ReprocessPlan {
+        diff: InvoiceDiff {
+            invoice_id: invoice.id.clone(),
+            invoice: invoice_changes,
+            transactions: changes,
+            applied: false,
+        },
+        invoice: ReplaceInvoiceInput {
+            id: invoice_id,
+            taxes,
+            discounts,
+            subtotal,
+            total,
+            currency,
+            card_number,
+            seller,
+            issued_at,
+        },
+        updated,
+        added,
+        removed,
+    }
+}
+
+fn added_fields(tx: &TransactionTool) -> Vec<FieldChange> {
+    [
+        ("amount", json!(tx.amount)),
+        ("quantity", json!(tx.quantity)),
+        ("unit", json!(tx.unit)),
+        ("categoryId", json!(tx.category_id)),
+        ("type", json!(tx.r#type)),
+    ]
+    .into_iter()
+    .map(|(field, after)| FieldChange {
+        field: field.to_string(),
+        before: serde_json::Value::Null,
+        after,
+        kept: false,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn date(day: u32) -> chrono::DateTime<chrono::Utc> {
+        chrono::Utc
+            .with_ymd_and_hms(2024, 7, day, 12, 0, 0)
+            .unwrap()
+    }
+
+    fn transaction(id: &str, title: &str, amount: f64, edited_fields: &[&str]) -> Transaction {
+        Transaction {
+            id: id.to_string(),
+            message_id: "669e5f02b781150b9a578203".to_string(),
+            user_id: "66990b1947d76ec3781adc9d".to_string(),
+            account_id: None,
+            transfer: None,
+            split_id: None,
+            title: title.to_string(),
+            amount,
+            currency: "VND".to_string(),
+            category_id: "food".to_string(),
+            r#type: "outcome".to_string(),
+            unit: None,
+            quantity: 1.0,
+            edited_fields: edited_fields.iter().map(|f| f.to_string()).collect(),
+            issued_at: date(22),
+            created_at: date(22),
+            updated_at: date(22),
+        }
+    }
+
+    fn transaction_tool(title: &str, amount: f64) -> TransactionTool {
+        TransactionTool {
+            title: title.to_string(),
+            currency: "USD".to_string(),
+            category_id: "food".to_string(),
+            r#type: "outcome".to_string(),
+            amount,
+            quantity: 1.0,
+            unit: None,
+            issued_at: date(25),
+            from_account: None,
+            to_account: None,
+            counterparty: None,
+            debt_kind: None,
+        }
+    }
+
+    #[test]
+    fn test_match_transactions() {
+        let pairs = match_transactions(
+            &["Banh mi thit", "Tra chanh", "Ca phe sua"],
+            &["Ca phe sua da", "BANH MI THIT", "Nuoc suoi", "Khan lanh"],
+        );
+
+        assert_eq!(
+            pairs,
+            vec![
+                (Some(0), Some(1)),
+                (Some(1), Some(2)),
+                (Some(2), Some(0)),
+                (None, Some(3)),
+            ]
+        );
+        assert_eq!(
+            match_transactions(&["Tra da", "Banh"], &[]),
+            vec![(Some(0), None), (Some(1), None)]
+        );
+    }
+
+    #[test]
+    fn test_plan_reprocess() {
+        let invoice = Invoice {
+            id: "669e5f02b781150b9a578205".to_string(),
+            user_id: "66990b1947d76ec3781adc9d".to_string(),
+            message_id: "669e5f02b781150b9a578203".to_string(),
+            taxes: vec![],
+            discounts: vec![],
+            subtotal: None,
+            total: 80000.0,
+            currency: "VND".to_string(),
+            card_number: None,
+            seller: None,
+            media: vec![],
+            edited_fields: vec!["total".to_string()],
+            issued_at: date(22),
+            created_at: date(23),
+            updated_at: date(23),
+        };
+        let transactions = vec![
+            transaction("669fb456ce6a5cbb87195a60", "Banh mi thit", 25000.0, &[]),
+            transaction("669fb456ce6a5cbb87195a61", "Tra da", 5000.0, &["amount"]),
+            transaction("669fb456ce6a5cbb87195a62", "Khan lanh", 2000.0, &["title"]),
+        ];
+        let extracted = InvoiceTool {
+            issued_at: date(25),
+            transactions: vec![
+                transaction_tool("Banh mi thit", 30000.0),
+                transaction_tool("Tra da", 6000.0),
+                transaction_tool("Ca phe", 20000.0),
+            ],
+            discounts: vec![],
+            taxes: vec![],
+            subtotal: Some(56000.0),
+            total: 56000.0,
+            currency: "VND".to_string(),
+            card_number: None,
+            seller: None,
+        };
+
+        let plan = plan_reprocess(&invoice, &transactions, extracted);
+
+        // the date read after the upload is ignored, the edited total is kept
+        assert_eq!(plan.invoice.issued_at, date(22));
+        assert_eq!(plan.invoice.total, 80000.0);
+        assert_eq!(plan.invoice.subtotal, Some(56000.0));
+        let fields = plan
+            .diff
+            .invoice
+            .iter()
+            .map(|change| (change.field.as_str(), change.kept))
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec![("subtotal", false), ("total", true)]);
+
+        // "Khan lanh" was edited, it is paired with the unmatched "Ca phe" but keeps its title
+        assert_eq!(plan.updated.len(), 3);
+        assert_eq!(plan.updated[0].amount, 30000.0);
+        assert_eq!(plan.updated[1].amount, 5000.0);
+        assert_eq!(plan.updated[2].title, "Khan lanh");
+        assert_eq!(plan.updated[2].amount, 20000.0);
+        assert!(plan.added.is_empty());
+        assert!(plan.removed.is_empty());
+        assert!(plan.diff.transactions[1].fields[0].kept);
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+
+/// A field whose extracted value differs from the stored one.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    #[schema(example = "total")]
+    pub field: String,
+    #[schema(value_type = Object, example = json!(80000.0))]
+    pub before: serde_json::Value,
+    #[schema(value_type = Object, example = json!(85000.0))]
+    pub after: serde_json::Value,
+    /// Edited by the user, the stored value is kept
+    #[schema(example = false)]
+    pub kept: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionChangeKind {
+    Added,
+    Changed,
+    Removed,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionChange {
+    #[schema(example = "changed")]
+    pub kind: TransactionChangeKind,
+    /// Not set for added transactions
+    #[schema(example = "669fb456ce6a5cbb87195a60")]
+    pub transaction_id: Option<String>,
+    #[schema(example = "Banh mi")]
+    pub title: String,
+    pub fields: Vec<FieldChange>,
+    /// A removed transaction edited by the user is kept
+    #[schema(example = false)]
+    pub kept: bool,
+}
+
+/// What reading a receipt again changes on its invoice and transactions.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InvoiceDiff {
+    #[schema(example = "669e5f02b781150b9a578205")]
+    pub invoice_id: String,
+    pub invoice: Vec<FieldChange>,
+    pub transactions: Vec<TransactionChange>,
+    /// False for a dry run, or when nothing changed
+    #[schema(example = true)]
+    pub applied: bool,
+}
+
+impl InvoiceDiff {
+    pub fn is_empty(&self) -> bool {
+        self.invoice.is_empty() && self.transactions.is_empty()
+    }
+}
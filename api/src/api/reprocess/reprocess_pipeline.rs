@@ -0,0 +1,124 @@
+use bson::oid::ObjectId;
+
+use crate::api::email::{email_body, parse_email, EML_CONTENT_TYPE};
+use crate::api::infer::models::InferMode;
+use crate::api::infer::InferOptions;
+use crate::api::invoice::{InvoiceError, Media, UploadedImage};
+use crate::api::reprocess::*;
+use crate::api::state::AppState;
+use crate::api::transaction::TransactionError;
+use crate::api::user::User;
+use crate::common::errors::AppError;
+use crate::services::pdf::PDF_CONTENT_TYPE;
+
+/// Reads the stored receipt of an invoice again, with the current OCR and model, and
+/// compares the result with the invoice and its transactions. The changes are saved in
+/// one Mongo transaction, unless it is a dry run.
+pub async fn reprocess_invoice(
+    state: &AppState,
+    user: User,
+    owner_id: ObjectId,
+    invoice_id: ObjectId,
+    dry_run: bool,
+) -> Result<InvoiceDiff, AppError> {
+    let invoice = state
+        .invoice_service
+        .find_by_id(invoice_id)
+        .await?
+        .filter(|invoice| invoice.user_id == owner_id.to_hex())
+        .ok_or(InvoiceError::NotFound)?;
+
+    let (image, mode) = read_receipt(state, &invoice.media).await?;
+    let infer_service = state.infer_service_factory.create_service(mode);
+
+    let categories = state.category_service.find().await?;
+    let (invoice_tool, _) = infer_service
+        .infer(
+            image.content,
+            InferOptions {
+                currencies: vec![user.currency],
+                categories,
+                events: None,
+                images: image.images,
+            },
+        )
+        .await?;
+
+    let transactions = state
+        .transaction_service
+        .find_by_invoice_id(invoice_id)
+        .await?;
+    let mut plan = plan_reprocess(&invoice, &transactions, invoice_tool);
+    if !dry_run && !plan.diff.is_empty() {
+        // the shares of a split follow the transactions it was made from
+        if transactions.iter().any(|tx| tx.split_id.is_some()) {
+            return Err(TransactionError::Split.into());
+        }
+        state.reprocess_service.apply(&plan).await?;
+        plan.diff.applied = true;
+    }
+
+    Ok(plan.diff)
+}
+
+/// What the invoice was extracted from: the xml of an e-invoice, or the photos and pdfs
+/// with the body of the email they were forwarded in.
+async fn read_receipt(
+    state: &AppState,
+    media: &[Media],
+) -> Result<(UploadedImage, InferMode), AppError> {
+    let is_xml = |content_type: &str| {
+        matches!(content_type, "application/xml" | "text/xml") || content_type.ends_with("+xml")
+    };
+    if let Some(xml) = media.iter().find(|media| is_xml(&media.content_type)) {
+        let content = String::from_utf8(state.invoice_service.read_media(xml).await?)
+            .map_err(|e| InvoiceError::InvalidEInvoice(e.to_string()))?;
+        let image = UploadedImage {
+            media: vec![],
+            content,
+            images: vec![],
+        };
+        return Ok((image, InferMode::EInvoice));
+    }
+
+    let body = match media
+        .iter()
+        .find(|media| media.content_type == EML_CONTENT_TYPE)
+    {
+        Some(eml) => email_body(&parse_email(&state.invoice_service.read_media(eml).await?)?),
+        None => String::new(),
+    };
+    let files = media
+        .iter()
+        .filter(|media| {
+            media.content_type.starts_with("image/") || media.content_type == PDF_CONTENT_TYPE
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    if files.is_empty() && body.is_empty() {
+        return Err(ReprocessError::NoReceipt.into());
+    }
+
+    let mut image = if files.is_empty() {
+        UploadedImage {
+            media: vec![],
+            content: String::new(),
+            images: vec![],
+        }
+    } else {
+        state.invoice_service.read_images(files).await?
+    };
+    image.content = [body, image.content]
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let mode = if image.images.is_empty() {
+        InferMode::Invoice
+    } else {
+        InferMode::InvoiceImage
+    };
+
+    Ok((image, mode))
+}
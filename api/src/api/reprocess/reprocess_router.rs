@@ -0,0 +1,27 @@
+use axum::middleware::from_fn_with_state;
+use axum::routing::post;
+use axum::Router;
+
+use crate::api::reprocess::reprocess_controller::*;
+use crate::api::state::AppState;
+use crate::mw::authorization_mw;
+
+pub struct ReprocessRouter(Router<AppState>);
+
+impl ReprocessRouter {
+    pub fn new(state: AppState) -> Self {
+        let routes = Router::new()
+            .route("/invoices/:invoice_id", post(reprocess_invoice))
+            .route_layer(from_fn_with_state(state.clone(), authorization_mw))
+            // run by admins over every user, checked with the admin secret instead
+            .route("/batch", post(reprocess_batch));
+
+        Self(routes)
+    }
+}
+
+impl From<ReprocessRouter> for Router<AppState> {
+    fn from(router: ReprocessRouter) -> Self {
+        router.0
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::FutureExt;
+use mongodb::Client;
+
+use crate::api::invoice::InvoiceServiceDyn;
+use crate::api::reprocess::*;
+use crate::api::transaction::TransactionServiceDyn;
+use crate::common::errors::AppError;
+
+#[async_trait]
+pub trait ReprocessServiceExt: Send + Sync {
+    /// Writes the invoice and transaction changes of the plan in one Mongo transaction.
+    async fn apply(&self, plan: &ReprocessPlan) -> Result<(), AppError>;
+}
+
+pub type ReprocessServiceDyn = Arc<dyn ReprocessServiceExt + Send + Sync>;
+
+#[derive(Clone)]
+pub struct ReprocessService {
+    pub mongo_client: Client,
+    pub invoice_service: InvoiceServiceDyn,
+    pub transaction_service: TransactionServiceDyn,
+}
+
+#[async_trait]
+impl ReprocessServiceExt for ReprocessService {
+    async fn apply(&self, plan: &ReprocessPlan) -> Result<(), AppError> {
+        let mut session = self
+            .mongo_client
+            .start_session()
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))?;
+
+        session
+            .start_transaction()
+            .and_run(plan, |session, plan| {
+                async move {
+                    self.invoice_service
+                        .replace_with_session(plan.invoice.clone(), session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+
+                    if !plan.updated.is_empty() {
+                        self.transaction_service
+                            .replace_many_with_session(&plan.updated, session)
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
+                    }
+                    if !plan.added.is_empty() {
+                        self.transaction_service
+                            .insert_many_with_session(&plan.added, session)
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
+                    }
+                    if !plan.removed.is_empty() {
+                        self.transaction_service
+                            .delete_many_by_ids_with_session(&plan.removed, session)
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
+                    }
+
+                    Ok(())
+                }
+                .boxed()
+            })
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))
+    }
+}
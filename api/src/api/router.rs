@@ -1,15 +1,22 @@
 use axum::Router;
 
+use crate::api::account::AccountRouter;
 use crate::api::asset::AssetRouter;
 use crate::api::auth::AuthRouter;
 use crate::api::category::CategoryRouter;
+use crate::api::debt::DebtRouter;
+use crate::api::email::EmailRouter;
 use crate::api::exchange_rate::ExchangeRateRouter;
+use crate::api::group::GroupRouter;
 use crate::api::invoice::InvoiceRouter;
 use crate::api::message::MessageRouter;
 use crate::api::report::ReportRouter;
+use crate::api::reprocess::ReprocessRouter;
 use crate::api::state::AppState;
+use crate::api::storage::StorageRouter;
 use crate::api::transaction::TransactionRouter;
 use crate::api::user::UserRouter;
+use crate::api::workspace::WorkspaceRouter;
 
 pub struct ApiRouter(Router<AppState>);
 
@@ -26,7 +33,14 @@ impl ApiRouter {
             )
             .nest("/messages", MessageRouter::new(state.clone()).into())
             .nest("/invoices", InvoiceRouter::new(state.clone()).into())
+            .nest("/emails", EmailRouter::new(state.clone()).into())
+            .nest("/accounts", AccountRouter::new(state.clone()).into())
+            .nest("/debts", DebtRouter::new(state.clone()).into())
+            .nest("/groups", GroupRouter::new(state.clone()).into())
+            .nest("/workspaces", WorkspaceRouter::new(state.clone()).into())
             .nest("/reports", ReportRouter::new(state.clone()).into())
+            .nest("/reprocess", ReprocessRouter::new(state.clone()).into())
+            .nest("/storage", StorageRouter::new(state.clone()).into())
             .nest("/transactions", TransactionRouter::new(state).into());
 
         Self(routes)
@@ -4,25 +4,38 @@ use async_openai::config::OpenAIConfig;
 use async_openai::Client as OpenAIClient;
 use mongodb::Client;
 
+use crate::api::account::{AccountRepo, AccountService, AccountServiceDyn};
+use crate::api::assistant::{AssistantService, AssistantServiceDyn};
 use crate::api::auth::{AuthService, AuthServiceDyn};
 use crate::api::category::{CategoryService, CategoryServiceDyn};
+use crate::api::debt::{DebtRepo, DebtService, DebtServiceDyn};
+use crate::api::duplicate::DuplicateService;
 use crate::api::exchange_rate::{ExchangeRateRepo, ExchangeRateService, ExchangeRateServiceDyn};
+use crate::api::group::{GroupRepo, GroupService, GroupServiceDyn};
 use crate::api::identity::{IdentityRepo, IdentityService, IdentityServiceDyn};
 use crate::api::infer::{
-    InferServiceFactory, InferServiceFactoryDyn, InvoiceInferService, TextInferService,
+    EInvoiceInferService, InferServiceFactory, InferServiceFactoryDyn, InvoiceImageInferService,
+    InvoiceInferService, TextInferService,
 };
 use crate::api::invoice::{InvoiceRepo, InvoiceService, InvoiceServiceDyn};
+use crate::api::job::{JobRepo, JobService, JobServiceDyn};
 use crate::api::message::{MessageRepo, MessageService, MessageServiceDyn};
 use crate::api::report::{ReportRepo, ReportService, ReportServiceDyn};
+use crate::api::reprocess::{ReprocessService, ReprocessServiceDyn};
+use crate::api::storage::{StorageService, StorageServiceDyn};
 use crate::api::transaction::{TransactionRepo, TransactionService, TransactionServiceDyn};
 use crate::api::user::{UserRepo, UserService, UserServiceDyn};
+use crate::api::workspace::{WorkspaceRepo, WorkspaceService, WorkspaceServiceDyn};
 use crate::services::currencyapi::CurrencyApiService;
 use crate::services::gcp::auth::GCPAuthService;
-use crate::services::gcp::vision::VisionService;
+use crate::services::gcp::vision::{VisionService, VisionServiceDyn};
 use crate::services::jwt::{JwtService, JwtServiceDyn};
 use crate::services::llm::{AnthropicService, OpenAIService};
-use crate::services::r2::{R2Service, R2ServiceDyn};
-use crate::settings::Settings;
+use crate::services::pdf::PdfService;
+use crate::services::r2::{LocalStorageService, MemoryStorageService, R2Service, R2ServiceDyn};
+use crate::services::speech::{SpeechToTextServiceDyn, StubSpeechToTextService, WhisperService};
+use crate::services::tesseract::TesseractService;
+use crate::settings::{OcrProvider, Settings, SpeechProvider, StorageProvider};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -35,12 +48,21 @@ pub struct AppState {
     pub jwt_service: JwtServiceDyn,
     pub exchange_rate_service: ExchangeRateServiceDyn,
     pub transaction_service: TransactionServiceDyn,
+    pub account_service: AccountServiceDyn,
+    pub debt_service: DebtServiceDyn,
     pub message_service: MessageServiceDyn,
+    pub group_service: GroupServiceDyn,
+    pub workspace_service: WorkspaceServiceDyn,
     pub invoice_service: InvoiceServiceDyn,
     pub category_service: CategoryServiceDyn,
     pub r2_service: R2ServiceDyn,
+    pub storage_service: StorageServiceDyn,
+    pub speech_to_text_service: SpeechToTextServiceDyn,
     pub infer_service_factory: InferServiceFactoryDyn,
     pub report_service: ReportServiceDyn,
+    pub reprocess_service: ReprocessServiceDyn,
+    pub assistant_service: AssistantServiceDyn,
+    pub job_service: JobServiceDyn,
 }
 
 impl AppState {
@@ -82,24 +104,69 @@ impl AppState {
         let invoice_infer_service = Arc::new(InvoiceInferService {
             llm_service: anthropic_service.clone(),
         });
+        let invoice_image_infer_service = Arc::new(InvoiceImageInferService {
+            llm_service: anthropic_service.clone(),
+            invoice_infer_service: invoice_infer_service.as_ref().clone(),
+        });
+        let einvoice_infer_service = Arc::new(EInvoiceInferService {
+            invoice_infer_service: invoice_infer_service.as_ref().clone(),
+        });
         let infer_service_factory = Arc::new(InferServiceFactory {
             text_infer_service: text_infer_service.clone(),
             invoice_infer_service: invoice_infer_service.clone(),
+            invoice_image_infer_service: invoice_image_infer_service.clone(),
+            einvoice_infer_service: einvoice_infer_service.clone(),
         });
 
-        // r2
-        let r2_service = Arc::new(R2Service::new(settings.r2.clone()));
+        // storage
+        let bucket = &settings.invoice.bucket;
+        let r2_service: R2ServiceDyn = match settings.storage.provider {
+            StorageProvider::R2 => Arc::new(R2Service::new(settings.r2.clone(), bucket)),
+            StorageProvider::S3 => Arc::new(R2Service::s3(settings.storage.s3.clone(), bucket)),
+            StorageProvider::Local => {
+                // the signed urls are the only check on the storage routes
+                assert!(
+                    !settings.storage.local.secret.is_empty(),
+                    "storage.local.secret is required by the local storage"
+                );
+                Arc::new(LocalStorageService {
+                    config: settings.storage.local.clone(),
+                    bucket: bucket.clone(),
+                })
+            }
+            StorageProvider::Memory => Arc::new(MemoryStorageService::default()),
+        };
 
-        // gcp
-        let gcp_auth_service = Arc::new(GCPAuthService::new(
-            settings.gcp.clone(),
-            redis_client.clone(),
-        ));
-        let gcp_vision_service = Arc::new(VisionService {
-            http_client: http_client.clone(),
-            gcp_auth_service: gcp_auth_service.clone(),
+        // ocr
+        let vision_service: VisionServiceDyn = match settings.ocr.provider {
+            OcrProvider::Gcp => {
+                let gcp_auth_service = Arc::new(GCPAuthService::new(
+                    settings.gcp.clone(),
+                    redis_client.clone(),
+                ));
+                Arc::new(VisionService {
+                    http_client: http_client.clone(),
+                    gcp_auth_service: gcp_auth_service.clone(),
+                })
+            }
+            OcrProvider::Tesseract => Arc::new(TesseractService {
+                config: settings.ocr.tesseract.clone(),
+            }),
+        };
+
+        // pdf
+        let pdf_service = Arc::new(PdfService {
+            config: settings.pdf.clone(),
         });
 
+        // speech
+        let speech_to_text_service: SpeechToTextServiceDyn = match settings.speech.provider {
+            SpeechProvider::Whisper => Arc::new(WhisperService {
+                config: settings.speech.whisper.clone(),
+            }),
+            SpeechProvider::Stub => Arc::new(StubSpeechToTextService),
+        };
+
         // identity
         let identity_repo = Arc::new(IdentityRepo {
             collection: database.collection("identities"),
@@ -148,12 +215,31 @@ impl AppState {
         // category
         let category_service = Arc::new(CategoryService {});
 
+        // debt
+        let debt_repo = Arc::new(DebtRepo {
+            collection: database.collection("debts"),
+            counterparty_col: database.collection("counterparties"),
+        });
+        let debt_service = Arc::new(DebtService { repo: debt_repo });
+
         // transaction
         let transaction_repo = Arc::new(TransactionRepo {
             collection: database.collection("transactions"),
         });
         let transaction_service = Arc::new(TransactionService {
             repo: transaction_repo,
+            mongo_client: mongo_client.clone(),
+            debt_service: debt_service.clone(),
+        });
+
+        // account
+        let account_repo = Arc::new(AccountRepo {
+            collection: database.collection("accounts"),
+            transaction_col: database.collection("transactions"),
+        });
+        let account_service = Arc::new(AccountService {
+            repo: account_repo,
+            transaction_service: transaction_service.clone(),
         });
 
         // invoice
@@ -162,11 +248,39 @@ impl AppState {
         });
         let invoice_service = Arc::new(InvoiceService {
             repo: invoice_repo,
+            mongo_client: mongo_client.clone(),
+            transaction_service: transaction_service.clone(),
             r2_service: r2_service.clone(),
-            gcp_vision_service: gcp_vision_service.clone(),
+            gcp_vision_service: vision_service.clone(),
+            pdf_service: pdf_service.clone(),
             config: settings.invoice.clone(),
         });
 
+        // duplicate
+        let duplicate_service = Arc::new(DuplicateService {
+            invoice_service: invoice_service.clone(),
+            transaction_service: transaction_service.clone(),
+            config: settings.duplicate.clone(),
+        });
+
+        // job
+        let job_repo = Arc::new(JobRepo {
+            redis_client: redis_client.clone(),
+        });
+        let job_service = Arc::new(JobService {
+            repo: job_repo,
+            max_attempts: settings.invoice.max_attempts,
+        });
+
+        // storage cleanup
+        let storage_service = Arc::new(StorageService {
+            r2_service: r2_service.clone(),
+            invoice_service: invoice_service.clone(),
+            job_service: job_service.clone(),
+            bucket: settings.invoice.bucket.clone(),
+            config: settings.storage.clone(),
+        });
+
         // message
         let message_repo = Arc::new(MessageRepo {
             collection: database.collection("messages"),
@@ -175,7 +289,47 @@ impl AppState {
             repo: message_repo,
             mongo_client: mongo_client.clone(),
             transaction_service: transaction_service.clone(),
+            account_service: account_service.clone(),
+            debt_service: debt_service.clone(),
             invoice_service: invoice_service.clone(),
+            category_service: category_service.clone(),
+            duplicate_service: duplicate_service.clone(),
+            storage_service: storage_service.clone(),
+        });
+
+        // group
+        let group_repo = Arc::new(GroupRepo {
+            collection: database.collection("groups"),
+            split_col: database.collection("splits"),
+            settlement_col: database.collection("settlements"),
+        });
+        let group_service = Arc::new(GroupService {
+            repo: group_repo,
+            mongo_client: mongo_client.clone(),
+            user_service: user_service.clone(),
+            invoice_service: invoice_service.clone(),
+            transaction_service: transaction_service.clone(),
+            message_service: message_service.clone(),
+        });
+
+        // workspace
+        let workspace_repo = Arc::new(WorkspaceRepo {
+            collection: database.collection("workspaces"),
+        });
+        let workspace_service = Arc::new(WorkspaceService {
+            repo: workspace_repo,
+            mongo_client: mongo_client.clone(),
+            user_service: user_service.clone(),
+            invoice_service: invoice_service.clone(),
+            transaction_service: transaction_service.clone(),
+            message_service: message_service.clone(),
+        });
+
+        // reprocess
+        let reprocess_service = Arc::new(ReprocessService {
+            mongo_client: mongo_client.clone(),
+            invoice_service: invoice_service.clone(),
+            transaction_service: transaction_service.clone(),
         });
 
         // report
@@ -184,6 +338,14 @@ impl AppState {
         });
         let report_service = Arc::new(ReportService { repo: report_repo });
 
+        // assistant
+        let assistant_service = Arc::new(AssistantService {
+            llm_service: openai_service.clone(),
+            report_service: report_service.clone(),
+            transaction_service: transaction_service.clone(),
+            message_service: message_service.clone(),
+        });
+
         Self {
             settings,
             http_client,
@@ -194,12 +356,21 @@ impl AppState {
             jwt_service,
             exchange_rate_service,
             transaction_service,
+            account_service,
+            debt_service,
             message_service,
+            group_service,
+            workspace_service,
             invoice_service,
             category_service,
             r2_service,
+            storage_service,
+            speech_to_text_service,
             infer_service_factory,
             report_service,
+            reprocess_service,
+            assistant_service,
+            job_service,
         }
     }
 }
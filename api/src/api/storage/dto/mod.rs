@@ -0,0 +1,3 @@
+mod signed_url_dto;
+
+pub use signed_url_dto::*;
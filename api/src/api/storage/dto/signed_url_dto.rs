@@ -0,0 +1,9 @@
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct SignedUrlQuery {
+    /// Unix timestamp after which the url is refused
+    pub expires: i64,
+    pub signature: String,
+}
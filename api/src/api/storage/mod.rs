@@ -0,0 +1,12 @@
+pub(crate) use dto::*;
+#[allow(unused_imports)]
+pub use storage_controller::StorageApiDoc;
+pub use storage_router::*;
+pub use storage_service::*;
+pub use storage_sweeper::*;
+
+mod dto;
+mod storage_controller;
+mod storage_router;
+mod storage_service;
+mod storage_sweeper;
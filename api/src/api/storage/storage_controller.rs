@@ -0,0 +1,94 @@
+use axum::body::Bytes;
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use utoipa::OpenApi;
+
+use crate::api::state::AppState;
+use crate::api::storage::SignedUrlQuery;
+use crate::common::errors::AppError;
+use crate::services::r2::{verify_signature, R2Error};
+use crate::settings::{LocalStorageConfig, StorageProvider};
+
+/// The url signing is only done by the local storage, other providers serve their own urls.
+fn local_config(state: &AppState) -> Result<&LocalStorageConfig, R2Error> {
+    match state.settings.storage.provider {
+        StorageProvider::Local => Ok(&state.settings.storage.local),
+        _ => Err(R2Error::NotFound),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/{key}",
+    responses(
+        (status = 200, description = "Get object successfully", content_type = "application/octet-stream"),
+    ),
+    params(
+        ("key" = String, Path, description = "Object key"),
+        SignedUrlQuery,
+    )
+)]
+pub async fn get_object(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(query): Query<SignedUrlQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let config = local_config(&state)?;
+    verify_signature(&config.secret, "GET", &key, query.expires, &query.signature)?;
+
+    let content = state.r2_service.get_object(key.clone()).await?;
+    let content_type = mime_guess::from_path(&key)
+        .first_or_octet_stream()
+        .to_string();
+
+    Ok(([(header::CONTENT_TYPE, content_type)], content))
+}
+
+#[utoipa::path(
+    put,
+    path = "/{key}",
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses(
+        (status = 204, description = "Put object successfully"),
+    ),
+    params(
+        ("key" = String, Path, description = "Object key"),
+        SignedUrlQuery,
+    )
+)]
+pub async fn put_object(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(query): Query<SignedUrlQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, AppError> {
+    let config = local_config(&state)?;
+    verify_signature(&config.secret, "PUT", &key, query.expires, &query.signature)?;
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/octet-stream");
+    state
+        .r2_service
+        .upload_object(
+            state.settings.invoice.bucket.clone(),
+            key,
+            &body,
+            content_type,
+        )
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_object, put_object),
+    tags(
+        (name = "crate::api::storage", description = "Storage API")
+    )
+)]
+pub struct StorageApiDoc;
@@ -0,0 +1,31 @@
+use axum::extract::DefaultBodyLimit;
+use axum::routing::get;
+use axum::Router;
+
+use crate::api::state::AppState;
+use crate::api::storage::storage_controller::*;
+
+// the largest photo a presigned url accepts
+const MAX_OBJECT_SIZE: usize = 10 * 1024 * 1024;
+
+pub struct StorageRouter(Router<AppState>);
+
+impl StorageRouter {
+    pub fn new(_state: AppState) -> Self {
+        // no authorization, the signature of the url is checked instead
+        let routes = Router::new().route(
+            "/*key",
+            get(get_object)
+                .put(put_object)
+                .layer(DefaultBodyLimit::max(MAX_OBJECT_SIZE)),
+        );
+
+        Self(routes)
+    }
+}
+
+impl From<StorageRouter> for Router<AppState> {
+    fn from(router: StorageRouter) -> Self {
+        router.0
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use futures::future::join_all;
+use tracing::{info, warn};
+
+use crate::api::invoice::{owner_prefix, InvoiceServiceDyn, Media};
+use crate::api::job::{CreateJobInput, JobPayload, JobServiceDyn};
+use crate::common::errors::AppError;
+use crate::services::r2::{R2ServiceDyn, StoredObject};
+use crate::settings::StorageConfig;
+
+#[async_trait]
+pub trait StorageServiceExt: Send + Sync {
+    /// Deletes the files of the media once their documents are gone. The files that could
+    /// not be deleted are left to a job, which retries them.
+    async fn delete_media(&self, user_id: ObjectId, media: &[Media]) -> Result<(), AppError>;
+    /// Deletes stored paths, fails if any of them is left.
+    async fn delete_objects(&self, paths: &[String]) -> Result<(), AppError>;
+    /// Deletes the files of the user, the ones uploaded in a workspace are kept with it.
+    async fn delete_user_objects(&self, user_id: ObjectId) -> Result<usize, AppError>;
+    /// Deletes the objects no invoice refers to anymore, returns how many were deleted.
+    async fn sweep(&self) -> Result<usize, AppError>;
+}
+
+pub type StorageServiceDyn = Arc<dyn StorageServiceExt + Send + Sync>;
+
+pub struct StorageService {
+    pub r2_service: R2ServiceDyn,
+    pub invoice_service: InvoiceServiceDyn,
+    pub job_service: JobServiceDyn,
+    pub bucket: String,
+    pub config: StorageConfig,
+}
+
+impl StorageService {
+    // objects checked against the invoices at once
+    const SWEEP_BATCH: usize = 500;
+
+    fn key<'a>(&self, path: &'a str) -> &'a str {
+        path.strip_prefix(&format!("{}/", self.bucket))
+            .unwrap_or(path)
+    }
+
+    /// Deletes every path, returns the ones that failed.
+    async fn try_delete(&self, paths: &[String]) -> Vec<String> {
+        let results = join_all(paths.iter().map(|path| async move {
+            self.r2_service
+                .delete_object(self.key(path).to_string())
+                .await
+                .map_err(|e| warn!(path, "failed to delete object: {e}"))
+        }))
+        .await;
+
+        paths
+            .iter()
+            .zip(results)
+            .filter(|(_, result)| result.is_err())
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+/// Stored paths of the media, the files and their thumbnails.
+pub fn media_paths(media: &[Media]) -> Vec<String> {
+    media
+        .iter()
+        .flat_map(|media| std::iter::once(&media.path).chain(&media.thumbnail_path))
+        .cloned()
+        .collect()
+}
+
+/// Paths of the objects older than `before` that are not referenced.
+pub fn find_orphans(
+    objects: &[StoredObject],
+    bucket: &str,
+    referenced: &HashSet<String>,
+    before: chrono::DateTime<chrono::Utc>,
+) -> Vec<String> {
+    objects
+        .iter()
+        .filter(|object| object.last_modified < before)
+        .map(|object| format!("{}/{}", bucket, object.key))
+        .filter(|path| !referenced.contains(path))
+        .collect()
+}
+
+#[async_trait]
+impl StorageServiceExt for StorageService {
+    async fn delete_media(&self, user_id: ObjectId, media: &[Media]) -> Result<(), AppError> {
+        let failed = self.try_delete(&media_paths(media)).await;
+        if failed.is_empty() {
+            return Ok(());
+        }
+
+        self.job_service
+            .enqueue(CreateJobInput {
+                user_id,
+                workspace_id: None,
+                payload: JobPayload::DeleteObjects { paths: failed },
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_objects(&self, paths: &[String]) -> Result<(), AppError> {
+        let failed = self.try_delete(paths).await;
+        if !failed.is_empty() {
+            return Err(AppError::Unknown(anyhow::anyhow!(
+                "failed to delete {} objects",
+                failed.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn delete_user_objects(&self, user_id: ObjectId) -> Result<usize, AppError> {
+        let paths = self
+            .r2_service
+            .list_objects(owner_prefix(user_id))
+            .await?
+            .into_iter()
+            .map(|object| format!("{}/{}", self.bucket, object.key))
+            .collect::<Vec<_>>();
+        self.delete_objects(&paths).await?;
+
+        Ok(paths.len())
+    }
+
+    async fn sweep(&self) -> Result<usize, AppError> {
+        let objects = self.r2_service.list_objects(String::new()).await?;
+        let before = chrono::Utc::now() - chrono::Duration::days(self.config.orphan_min_age_days);
+
+        let mut deleted = 0;
+        for objects in objects.chunks(Self::SWEEP_BATCH) {
+            let paths = objects
+                .iter()
+                .map(|object| format!("{}/{}", self.bucket, object.key))
+                .collect::<Vec<_>>();
+            let referenced = self
+                .invoice_service
+                .find_by_media_paths(&paths)
+                .await?
+                .iter()
+                .flat_map(|invoice| media_paths(&invoice.media))
+                .collect::<HashSet<_>>();
+
+            let orphans = find_orphans(objects, &self.bucket, &referenced, before);
+            // the failed ones are found again by the next sweep
+            let failed = self.try_delete(&orphans).await;
+            deleted += orphans.len() - failed.len();
+        }
+        info!(deleted, "swept orphaned objects");
+
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_orphans() {
+        let now = chrono::Utc::now();
+        let old = now - chrono::Duration::days(10);
+        let objects = vec![
+            StoredObject {
+                key: "u/a/0.jpg".to_string(),
+                last_modified: old,
+            },
+            StoredObject {
+                key: "u/a/0.thumb.jpg".to_string(),
+                last_modified: old,
+            },
+            StoredObject {
+                key: "u/b/0.jpg".to_string(),
+                last_modified: old,
+            },
+            StoredObject {
+                key: "u/uploads/c.jpg".to_string(),
+                last_modified: now,
+            },
+        ];
+        let referenced = media_paths(&[Media {
+            path: "invoices/u/a/0.jpg".to_string(),
+            content_type: "image/jpeg".to_string(),
+            thumbnail_path: Some("invoices/u/a/0.thumb.jpg".to_string()),
+            hash: None,
+        }])
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+        let orphans = find_orphans(
+            &objects,
+            "invoices",
+            &referenced,
+            now - chrono::Duration::days(7),
+        );
+
+        assert_eq!(orphans, vec!["invoices/u/b/0.jpg".to_string()]);
+    }
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use tokio::time::{interval_at, Instant};
+use tracing::{error, info};
+
+use crate::api::state::AppState;
+
+/// Periodically deletes the stored objects no invoice refers to, e.g. files of a failed
+/// upload or ones whose deletion was lost.
+pub struct StorageSweeper {
+    pub state: AppState,
+}
+
+impl StorageSweeper {
+    pub fn spawn(state: AppState) {
+        let hours = state.settings.storage.sweep_interval_hours;
+        if hours == 0 {
+            return;
+        }
+
+        let sweeper = Self { state };
+        tokio::spawn(async move { sweeper.run(Duration::from_secs(hours * 60 * 60)).await });
+    }
+
+    async fn run(&self, period: Duration) {
+        info!(?period, "storage sweeper started");
+        let mut interval = interval_at(Instant::now() + period, period);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.state.storage_service.sweep().await {
+                error!("failed to sweep storage: {e}");
+            }
+        }
+    }
+}
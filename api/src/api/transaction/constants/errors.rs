@@ -8,6 +8,10 @@ use thiserror::Error;
 pub enum TransactionError {
     #[error("transaction not found")]
     NotFound,
+    #[error("invalid transfer: {0}")]
+    InvalidTransfer(String),
+    #[error("the transaction is split in a group, delete the split first")]
+    Split,
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -16,6 +20,8 @@ impl IntoResponse for TransactionError {
     fn into_response(self) -> Response {
         let (status, message) = match self {
             Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::InvalidTransfer(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::Split => (StatusCode::CONFLICT, self.to_string()),
             Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
         };
 
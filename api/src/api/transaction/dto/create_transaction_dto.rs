@@ -4,6 +4,8 @@ pub struct InsertTransactionInput {
     pub message_id: ObjectId,
     pub user_id: ObjectId,
     pub invoice_id: ObjectId,
+    pub account_id: Option<ObjectId>,
+    pub to_account_id: Option<ObjectId>,
 
     pub title: String,
     pub amount: f64,
@@ -19,6 +21,8 @@ pub struct InsertTransactionData {
     pub message_id: ObjectId,
     pub user_id: ObjectId,
     pub invoice_id: ObjectId,
+    pub account_id: Option<ObjectId>,
+    pub to_account_id: Option<ObjectId>,
 
     pub title: String,
     pub amount: f64,
@@ -36,6 +40,8 @@ impl From<InsertTransactionInput> for InsertTransactionData {
             message_id: value.message_id,
             user_id: value.user_id,
             invoice_id: value.invoice_id,
+            account_id: value.account_id,
+            to_account_id: value.to_account_id,
             title: value.title,
             amount: value.amount,
             currency: value.currency,
@@ -59,6 +65,8 @@ impl From<&InsertTransactionInput> for InsertTransactionData {
             category_id: value.category_id.clone(),
             r#type: value.r#type.clone(),
             invoice_id: value.invoice_id,
+            account_id: value.account_id,
+            to_account_id: value.to_account_id,
             unit: value.unit.clone(),
             quantity: value.quantity,
             issued_at: value.issued_at,
@@ -1,7 +1,11 @@
 mod create_transaction_dto;
 mod delete_transaction_dto;
+mod replace_transaction_dto;
+mod search_transaction_dto;
 mod update_transaction_dto;
 
 pub use create_transaction_dto::*;
 pub use delete_transaction_dto::*;
+pub use replace_transaction_dto::*;
+pub use search_transaction_dto::*;
 pub use update_transaction_dto::*;
@@ -0,0 +1,42 @@
+use bson::oid::ObjectId;
+
+pub struct ReplaceTransactionData {
+    pub id: ObjectId,
+    pub title: String,
+    pub amount: f64,
+    pub currency: String,
+    pub category_id: String,
+    pub r#type: String,
+    pub unit: Option<String>,
+    pub quantity: f64,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Extracted fields of a transaction read again from its receipt.
+pub struct ReplaceTransactionInput {
+    pub id: ObjectId,
+    pub title: String,
+    pub amount: f64,
+    pub currency: String,
+    pub category_id: String,
+    pub r#type: String,
+    pub unit: Option<String>,
+    pub quantity: f64,
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&ReplaceTransactionInput> for ReplaceTransactionData {
+    fn from(value: &ReplaceTransactionInput) -> Self {
+        Self {
+            id: value.id,
+            title: value.title.clone(),
+            amount: value.amount,
+            currency: value.currency.clone(),
+            category_id: value.category_id.clone(),
+            r#type: value.r#type.clone(),
+            unit: value.unit.clone(),
+            quantity: value.quantity,
+            issued_at: value.issued_at,
+        }
+    }
+}
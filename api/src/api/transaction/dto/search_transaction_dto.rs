@@ -0,0 +1,32 @@
+use bson::oid::ObjectId;
+
+pub struct SearchTransactionsInput {
+    pub user_id: ObjectId,
+    pub keyword: Option<String>,
+    pub category_id: Option<String>,
+    pub r#type: Option<String>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+pub struct SearchTransactionsData {
+    pub user_id: ObjectId,
+    pub keyword: Option<String>,
+    pub category_id: Option<String>,
+    pub r#type: Option<String>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<SearchTransactionsInput> for SearchTransactionsData {
+    fn from(value: SearchTransactionsInput) -> Self {
+        Self {
+            user_id: value.user_id,
+            keyword: value.keyword,
+            category_id: value.category_id,
+            r#type: value.r#type,
+            from: value.from,
+            to: value.to,
+        }
+    }
+}
@@ -10,12 +10,25 @@ pub struct UpdateTransactionData {
     pub amount: Option<i64>,
     pub currency: Option<String>,
     pub category_id: Option<String>,
+    pub account_id: Option<ObjectId>,
+    pub to_account_id: Option<ObjectId>,
+    pub from_amount: Option<f64>,
+    pub to_amount: Option<f64>,
     pub type_: Option<String>,
     pub unit: Option<String>,
     pub quantity: Option<f32>,
     pub issued_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+/// Currency and date of an edited invoice, carried over to its line items.
+#[derive(Debug, PartialEq)]
+pub struct UpdateInvoiceTransactionsData {
+    pub invoice_id: ObjectId,
+    pub user_id: ObjectId,
+    pub currency: Option<String>,
+    pub issued_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 pub struct UpdateTransactionInput {
     pub id: ObjectId,
     pub user_id: ObjectId,
@@ -23,6 +36,10 @@ pub struct UpdateTransactionInput {
     pub amount: Option<i64>,
     pub currency: Option<String>,
     pub category_id: Option<String>,
+    pub account_id: Option<ObjectId>,
+    pub to_account_id: Option<ObjectId>,
+    pub from_amount: Option<f64>,
+    pub to_amount: Option<f64>,
     pub type_: Option<String>,
     pub unit: Option<String>,
     pub quantity: Option<f32>,
@@ -42,6 +59,19 @@ pub struct UpdateTransactionBody {
     pub currency: Option<String>,
     #[schema(example = "groceries")]
     pub category_id: Option<String>,
+    #[schema(example = "66a1f0c2ce6a5cbb87195b10")]
+    pub account_id: Option<String>,
+    /// Destination account of a transfer
+    #[schema(example = "66a1f0c2ce6a5cbb87195b11")]
+    pub to_account_id: Option<String>,
+    /// Amount that left the source account of a transfer, in its currency
+    #[schema(example = 2000000.0)]
+    #[validate(range(min = 0.0))]
+    pub from_amount: Option<f64>,
+    /// Amount that arrived in the destination account of a transfer, in its currency
+    #[schema(example = 80.0)]
+    #[validate(range(min = 0.0))]
+    pub to_amount: Option<f64>,
     #[schema(example = "income")]
     #[serde(rename = "type")]
     pub type_: Option<String>,
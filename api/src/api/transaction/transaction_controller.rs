@@ -1,8 +1,10 @@
+use crate::api::account::AccountError;
 use crate::api::state::AppState;
 use crate::api::transaction::{
-    DeleteTransactionBody, UpdateTransactionBody, UpdateTransactionInput,
+    DeleteTransactionBody, TransactionError, Transfer, UpdateTransactionBody,
+    UpdateTransactionInput,
 };
-use crate::api::user::User;
+use crate::api::workspace::Scope;
 use crate::common::errors::AppError;
 use crate::common::hooks::ValidJson;
 use crate::object_id;
@@ -18,18 +20,59 @@ use utoipa::OpenApi;
     request_body = [UpdateTransactionBody],
     responses(
         (status = 204, description = "Update transactions by id successfully"),
+        (status = 409, description = "A transaction is split in a group"),
+        (status = 422, description = "Invalid transfer"),
     ),
 )]
 pub async fn update_transactions(
     State(state): State<AppState>,
-    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
     ValidJson(body): ValidJson<Vec<UpdateTransactionBody>>,
 ) -> Result<StatusCode, AppError> {
+    for v in &body {
+        let has_transfer =
+            v.to_account_id.is_some() || v.from_amount.is_some() || v.to_amount.is_some();
+        if has_transfer && v.type_.as_deref().is_some_and(|type_| type_ != "transfer") {
+            return Err(TransactionError::InvalidTransfer(
+                "only transfers have a destination account".to_string(),
+            )
+            .into());
+        }
+        if v.account_id.is_some() && v.account_id == v.to_account_id {
+            return Err(TransactionError::InvalidTransfer(
+                "the source and destination accounts must differ".to_string(),
+            )
+            .into());
+        }
+    }
+
+    let mut account_ids = body
+        .iter()
+        .flat_map(|v| [v.account_id.clone(), v.to_account_id.clone()])
+        .flatten()
+        .collect::<Vec<_>>();
+    account_ids.sort();
+    account_ids.dedup();
+    for account_id in account_ids {
+        state
+            .account_service
+            .find_by_id(object_id!(&account_id))
+            .await?
+            .filter(|account| account.user_id == scope.owner_id.to_hex())
+            .ok_or(AccountError::NotFound)?;
+    }
+
+    let ids = body
+        .iter()
+        .map(|v| object_id!(&v.id))
+        .collect::<Vec<ObjectId>>();
+    state.transaction_service.check_unsplit(&ids).await?;
+
     let input = body
         .into_iter()
         .map(|v| UpdateTransactionInput {
             id: object_id!(&v.id),
-            user_id: object_id!(&user.id),
+            user_id: scope.owner_id,
             amount: v.amount,
             currency: v.currency,
             title: v.title,
@@ -38,6 +81,10 @@ pub async fn update_transactions(
             quantity: v.quantity,
             issued_at: v.issued_at,
             category_id: v.category_id,
+            account_id: v.account_id.map(|id| object_id!(&id)),
+            to_account_id: v.to_account_id.map(|id| object_id!(&id)),
+            from_amount: v.from_amount,
+            to_amount: v.to_amount,
         })
         .collect();
 
@@ -52,11 +99,12 @@ pub async fn update_transactions(
     request_body = [DeleteTransactionBody],
     responses(
         (status = 204, description = "Delete transactions by id successfully"),
+        (status = 409, description = "A transaction is split in a group"),
     ),
 )]
 pub async fn delete_transactions(
     State(state): State<AppState>,
-    Extension(user): Extension<User>,
+    Extension(scope): Extension<Scope>,
     ValidJson(body): ValidJson<DeleteTransactionBody>,
 ) -> Result<StatusCode, AppError> {
     let ids = body
@@ -65,9 +113,10 @@ pub async fn delete_transactions(
         .map(|v| object_id!(&v))
         .collect::<Vec<ObjectId>>();
 
+    state.transaction_service.check_unsplit(&ids).await?;
     state
         .transaction_service
-        .delete_many_by_ids(&ids, &object_id!(&user.id))
+        .delete_many_by_ids(&ids, &scope.owner_id)
         .await?;
 
     Ok(StatusCode::NO_CONTENT)
@@ -79,6 +128,7 @@ pub async fn delete_transactions(
     components(
         schemas(
             UpdateTransactionBody,
+            Transfer,
         )
     ),
     tags(
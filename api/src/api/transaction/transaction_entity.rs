@@ -9,6 +9,8 @@ pub struct TransactionEntity {
     pub message_id: ObjectId,
     pub user_id: ObjectId,
     pub invoice_id: ObjectId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<ObjectId>,
     pub title: String,
     pub amount: f64,
     pub currency: String,
@@ -16,6 +18,15 @@ pub struct TransactionEntity {
     pub r#type: String,
     pub unit: Option<String>,
     pub quantity: f64,
+    // set on transfers between two accounts of the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transfer: Option<TransferEntity>,
+    // set when the bill is shared with a group, the shares of the members are on the split
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub split_id: Option<ObjectId>,
+    // changed by the user, kept when the receipt is extracted again
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub edited_fields: Vec<String>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub issued_at: chrono::DateTime<chrono::Utc>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -23,3 +34,15 @@ pub struct TransactionEntity {
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
+
+/// The other side of a transfer. The transaction amount leaves `accountId` and arrives in
+/// `toAccountId`. The amounts are set when the currencies of the accounts differ.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferEntity {
+    pub to_account_id: Option<ObjectId>,
+    // in the currency of the source account
+    pub from_amount: Option<f64>,
+    // in the currency of the destination account
+    pub to_amount: Option<f64>,
+}
@@ -1,5 +1,7 @@
-use crate::api::transaction::TransactionEntity;
+use crate::api::transaction::{TransactionEntity, TransferEntity};
 use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
@@ -11,6 +13,9 @@ pub struct Transaction {
     pub message_id: String,
     #[schema(example = "66990b1947d76ec3781adc9d")]
     pub user_id: String,
+    /// Account the money came from or went to
+    #[schema(example = "66a1f0c2ce6a5cbb87195b10")]
+    pub account_id: Option<String>,
     #[schema(example = "Dinner")]
     pub title: String,
     #[schema(example = 100.00)]
@@ -25,6 +30,14 @@ pub struct Transaction {
     pub unit: Option<String>,
     #[schema(example = 1.0)]
     pub quantity: f64,
+    /// Set on transfers between two accounts
+    pub transfer: Option<Transfer>,
+    /// Group split the transaction is shared in
+    #[schema(example = "66a3d2e1ce6a5cbb87195d30")]
+    pub split_id: Option<String>,
+    /// Fields changed by the user
+    #[schema(example = json!(["amount"]))]
+    pub edited_fields: Vec<String>,
     #[schema(example = "2024-07-22T13:30:42.246017Z")]
     pub issued_at: chrono::DateTime<chrono::Utc>,
     #[schema(example = "2024-07-22T13:30:42.246017Z")]
@@ -39,6 +52,7 @@ impl From<TransactionEntity> for Transaction {
             id: value.id.to_hex(),
             message_id: value.message_id.to_hex(),
             user_id: value.user_id.to_hex(),
+            account_id: value.account_id.map(|id| id.to_hex()),
             title: value.title,
             amount: value.amount,
             currency: value.currency,
@@ -46,9 +60,36 @@ impl From<TransactionEntity> for Transaction {
             r#type: value.r#type,
             unit: value.unit,
             quantity: value.quantity,
+            transfer: value.transfer.map(Into::into),
+            split_id: value.split_id.map(|id| id.to_hex()),
+            edited_fields: value.edited_fields,
             issued_at: value.issued_at,
             created_at: value.created_at,
             updated_at: value.updated_at,
         }
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Transfer {
+    /// Account the money went to
+    #[schema(example = "66a1f0c2ce6a5cbb87195b11")]
+    pub to_account_id: Option<String>,
+    /// Amount that left the source account, in its currency
+    #[schema(example = 2000000.0)]
+    pub from_amount: Option<f64>,
+    /// Amount that arrived in the destination account, in its currency
+    #[schema(example = 80.0)]
+    pub to_amount: Option<f64>,
+}
+
+impl From<TransferEntity> for Transfer {
+    fn from(value: TransferEntity) -> Self {
+        Self {
+            to_account_id: value.to_account_id.map(|id| id.to_hex()),
+            from_amount: value.from_amount,
+            to_amount: value.to_amount,
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::api::transaction::*;
+use crate::common::mongo::FindOptions;
 use async_trait::async_trait;
 use bson::oid::ObjectId;
 use bson::{doc, Document};
@@ -19,6 +20,11 @@ pub trait TransactionRepoExt: Send + Sync {
         session: &mut ClientSession,
     ) -> Result<Vec<TransactionEntity>, TransactionError>;
     async fn find(&self, filter: Document) -> Result<Vec<TransactionEntity>, TransactionError>;
+    async fn search(
+        &self,
+        data: SearchTransactionsData,
+        options: FindOptions,
+    ) -> Result<Vec<TransactionEntity>, TransactionError>;
     async fn find_by_id(&self, id: ObjectId)
         -> Result<Option<TransactionEntity>, TransactionError>;
     async fn find_by_invoice_id(
@@ -32,11 +38,30 @@ pub trait TransactionRepoExt: Send + Sync {
         id: ObjectId,
         data: UpdateTransactionData,
     ) -> Result<Option<TransactionEntity>, TransactionError>;
-    async fn delete_many_by_ids(
+    async fn replace_many_with_session(
         &self,
-        ids: &[ObjectId],
-        user_id: &ObjectId,
+        items: &[ReplaceTransactionData],
+        session: &mut ClientSession,
     ) -> Result<bool, TransactionError>;
+    async fn update_by_invoice_id_with_session(
+        &self,
+        data: &UpdateInvoiceTransactionsData,
+        session: &mut ClientSession,
+    ) -> Result<u64, TransactionError>;
+    async fn unset_account(&self, account_id: ObjectId) -> Result<u64, TransactionError>;
+    async fn set_split_with_session(
+        &self,
+        ids: &[ObjectId],
+        split_id: Option<ObjectId>,
+        session: &mut ClientSession,
+    ) -> Result<u64, TransactionError>;
+    async fn change_owner_with_session(
+        &self,
+        invoice_ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, TransactionError>;
     async fn delete_many_by_ids_with_session(
         &self,
         ids: &[ObjectId],
@@ -51,17 +76,30 @@ pub struct TransactionRepo {
     pub collection: Collection<TransactionEntity>,
 }
 
+impl TransactionRepo {
+    const DEFAULT_LIMIT: i64 = 20;
+}
+
+fn new_transfer(r#type: &str, to_account_id: Option<ObjectId>) -> Option<TransferEntity> {
+    (r#type == "transfer").then(|| TransferEntity {
+        to_account_id,
+        ..Default::default()
+    })
+}
+
 #[async_trait]
 impl TransactionRepoExt for TransactionRepo {
     async fn insert_one(
         &self,
         data: InsertTransactionData,
     ) -> Result<TransactionEntity, TransactionError> {
+        let transfer = new_transfer(&data.r#type, data.to_account_id);
         let document = TransactionEntity {
             id: ObjectId::new(),
             message_id: data.message_id,
             user_id: data.user_id,
             invoice_id: data.invoice_id,
+            account_id: data.account_id,
             title: data.title,
             amount: data.amount,
             currency: data.currency,
@@ -69,6 +107,9 @@ impl TransactionRepoExt for TransactionRepo {
             r#type: data.r#type,
             unit: data.unit,
             quantity: data.quantity,
+            transfer,
+            split_id: None,
+            edited_fields: vec![],
             issued_at: data.issued_at,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
@@ -95,6 +136,7 @@ impl TransactionRepoExt for TransactionRepo {
                 message_id: item.message_id,
                 user_id: item.user_id,
                 invoice_id: item.invoice_id,
+                account_id: item.account_id,
                 title: item.title.clone(),
                 amount: item.amount,
                 currency: item.currency.clone(),
@@ -102,6 +144,9 @@ impl TransactionRepoExt for TransactionRepo {
                 r#type: item.r#type.clone(),
                 unit: item.unit.clone(),
                 quantity: item.quantity,
+                transfer: new_transfer(&item.r#type, item.to_account_id),
+                split_id: None,
+                edited_fields: vec![],
                 issued_at: item.issued_at,
                 created_at: now,
                 updated_at: now,
@@ -135,6 +180,52 @@ impl TransactionRepoExt for TransactionRepo {
         Ok(documents)
     }
 
+    async fn search(
+        &self,
+        data: SearchTransactionsData,
+        options: FindOptions,
+    ) -> Result<Vec<TransactionEntity>, TransactionError> {
+        let mut filter = doc! { "userId": data.user_id };
+        if let Some(keyword) = data.keyword {
+            filter.insert(
+                "title",
+                doc! { "$regex": regex::escape(&keyword), "$options": "i" },
+            );
+        }
+        if let Some(category_id) = data.category_id {
+            filter.insert("categoryId", category_id);
+        }
+        if let Some(r#type) = data.r#type {
+            filter.insert("type", r#type);
+        }
+        let mut issued_at = doc! {};
+        if let Some(from) = data.from {
+            issued_at.insert("$gte", from);
+        }
+        if let Some(to) = data.to {
+            issued_at.insert("$lt", to);
+        }
+        if !issued_at.is_empty() {
+            filter.insert("issuedAt", issued_at);
+        }
+
+        let mut cursor = self
+            .collection
+            .find(filter)
+            .sort(doc! { "issuedAt": -1 })
+            .limit(options.limit.unwrap_or(Self::DEFAULT_LIMIT))
+            .skip(options.skip.unwrap_or_default())
+            .await
+            .map_err(|e| TransactionError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+
     async fn find_by_id(
         &self,
         id: ObjectId,
@@ -187,9 +278,26 @@ impl TransactionRepoExt for TransactionRepo {
             set.insert("currency", currency);
         }
         if let Some(category_id) = data.category_id {
-            set.insert("category_id", category_id);
+            set.insert("categoryId", category_id);
+        }
+        if let Some(account_id) = data.account_id {
+            set.insert("accountId", account_id);
         }
+        if let Some(to_account_id) = data.to_account_id {
+            set.insert("transfer.toAccountId", to_account_id);
+        }
+        if let Some(from_amount) = data.from_amount {
+            set.insert("transfer.fromAmount", from_amount);
+        }
+        if let Some(to_amount) = data.to_amount {
+            set.insert("transfer.toAmount", to_amount);
+        }
+        let mut update = doc! {};
         if let Some(type_) = data.type_ {
+            // a transaction that is no longer a transfer has no other side
+            if type_ != "transfer" {
+                update.insert("$unset", doc! { "transfer": "" });
+            }
             set.insert("type", type_);
         }
         if let Some(unit) = data.unit {
@@ -199,18 +307,21 @@ impl TransactionRepoExt for TransactionRepo {
             set.insert("quantity", quantity);
         }
         if let Some(issued_at) = data.issued_at {
-            set.insert("issued_at", issued_at);
+            set.insert("issuedAt", issued_at);
         }
         if let Some(title) = data.title {
             set.insert("title", title);
         }
+        let edited_fields = set.keys().cloned().collect::<Vec<_>>();
+        update.insert("$set", set);
+        update.insert(
+            "$addToSet",
+            doc! { "editedFields": { "$each": edited_fields } },
+        );
 
         let document = self
             .collection
-            .find_one_and_update(
-                doc! { "_id": id, "userId": data.user_id },
-                doc! { "$set": set },
-            )
+            .find_one_and_update(doc! { "_id": id, "userId": data.user_id }, update)
             .return_document(ReturnDocument::After)
             .await
             .map_err(|e| TransactionError::Unknown(e.into()))?;
@@ -218,15 +329,110 @@ impl TransactionRepoExt for TransactionRepo {
         Ok(document)
     }
 
-    async fn delete_many_by_ids(
+    async fn replace_many_with_session(
         &self,
-        ids: &[ObjectId],
-        user_id: &ObjectId,
+        items: &[ReplaceTransactionData],
+        session: &mut ClientSession,
     ) -> Result<bool, TransactionError> {
+        for item in items {
+            self.collection
+                .update_one(
+                    doc! { "_id": item.id },
+                    doc! {
+                        "$set": {
+                            "title": &item.title,
+                            "amount": item.amount,
+                            "currency": &item.currency,
+                            "categoryId": &item.category_id,
+                            "type": &item.r#type,
+                            "unit": &item.unit,
+                            "quantity": item.quantity,
+                            "issuedAt": item.issued_at,
+                            "updatedAt": chrono::Utc::now(),
+                        }
+                    },
+                )
+                .session(&mut *session)
+                .await
+                .map_err(|e| TransactionError::Unknown(e.into()))?;
+        }
+
+        Ok(true)
+    }
+
+    async fn update_by_invoice_id_with_session(
+        &self,
+        data: &UpdateInvoiceTransactionsData,
+        session: &mut ClientSession,
+    ) -> Result<u64, TransactionError> {
+        let mut set = doc! { "updatedAt": chrono::Utc::now() };
+        if let Some(currency) = &data.currency {
+            set.insert("currency", currency);
+        }
+        if let Some(issued_at) = data.issued_at {
+            set.insert("issuedAt", issued_at);
+        }
+
         self.collection
-            .delete_many(doc! { "_id": { "$in": ids }, "userId": user_id })
+            .update_many(
+                doc! { "invoiceId": data.invoice_id, "userId": data.user_id },
+                doc! { "$set": set },
+            )
+            .session(session)
             .await
-            .map(|v| v.deleted_count == ids.len() as u64)
+            .map(|v| v.modified_count)
+            .map_err(|e| TransactionError::Unknown(e.into()))
+    }
+
+    async fn unset_account(&self, account_id: ObjectId) -> Result<u64, TransactionError> {
+        self.collection
+            .update_many(
+                doc! { "accountId": account_id },
+                doc! { "$unset": { "accountId": "" } },
+            )
+            .await
+            .map(|v| v.modified_count)
+            .map_err(|e| TransactionError::Unknown(e.into()))
+    }
+
+    async fn set_split_with_session(
+        &self,
+        ids: &[ObjectId],
+        split_id: Option<ObjectId>,
+        session: &mut ClientSession,
+    ) -> Result<u64, TransactionError> {
+        let update = match split_id {
+            Some(split_id) => doc! { "$set": { "splitId": split_id } },
+            None => doc! { "$unset": { "splitId": "" } },
+        };
+
+        self.collection
+            .update_many(doc! { "_id": { "$in": ids } }, update)
+            .session(session)
+            .await
+            .map(|v| v.modified_count)
+            .map_err(|e| TransactionError::Unknown(e.into()))
+    }
+
+    async fn change_owner_with_session(
+        &self,
+        invoice_ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, TransactionError> {
+        self.collection
+            .update_many(
+                doc! { "invoiceId": { "$in": invoice_ids }, "userId": from },
+                // accounts are personal, the new owner does not have them
+                doc! {
+                    "$set": { "userId": to, "updatedAt": chrono::Utc::now() },
+                    "$unset": { "accountId": "", "transfer.toAccountId": "" },
+                },
+            )
+            .session(session)
+            .await
+            .map(|v| v.modified_count)
             .map_err(|e| TransactionError::Unknown(e.into()))
     }
 
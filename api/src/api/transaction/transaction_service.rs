@@ -3,10 +3,13 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use bson::doc;
 use bson::oid::ObjectId;
-use mongodb::ClientSession;
+use futures::FutureExt;
+use mongodb::{Client, ClientSession};
 
+use crate::api::debt::DebtServiceDyn;
 use crate::api::transaction::*;
 use crate::common::errors::AppError;
+use crate::common::mongo::FindOptions;
 
 #[async_trait]
 pub trait TransactionServiceExt: Send + Sync {
@@ -18,6 +21,11 @@ pub trait TransactionServiceExt: Send + Sync {
     ) -> Result<Vec<Transaction>, AppError>;
     async fn find_by_message_id(&self, message_id: ObjectId) -> Result<Vec<Transaction>, AppError>;
     async fn find_by_id(&self, id: ObjectId) -> Result<Option<Transaction>, AppError>;
+    async fn search(
+        &self,
+        input: SearchTransactionsInput,
+        options: FindOptions,
+    ) -> Result<Vec<Transaction>, AppError>;
     async fn find_by_invoice_id(&self, invoice_id: ObjectId) -> Result<Vec<Transaction>, AppError>;
     async fn update_by_id(
         &self,
@@ -25,6 +33,36 @@ pub trait TransactionServiceExt: Send + Sync {
         data: UpdateTransactionData,
     ) -> Result<Option<Transaction>, AppError>;
     async fn update_many(&self, input: Vec<UpdateTransactionInput>) -> Result<bool, AppError>;
+    async fn replace_many_with_session(
+        &self,
+        items: &[ReplaceTransactionInput],
+        session: &mut ClientSession,
+    ) -> Result<bool, AppError>;
+    /// Detaches the transactions of a deleted account.
+    /// Carries the currency and the date of an edited invoice over to its line items.
+    async fn update_by_invoice_id_with_session(
+        &self,
+        data: &UpdateInvoiceTransactionsData,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError>;
+    async fn unset_account(&self, account_id: ObjectId) -> Result<u64, AppError>;
+    /// Split transactions are shared with the group, they only change with their split.
+    async fn check_unsplit(&self, ids: &[ObjectId]) -> Result<(), AppError>;
+    /// Links the transactions to a group split, or unlinks them.
+    async fn set_split_with_session(
+        &self,
+        ids: &[ObjectId],
+        split_id: Option<ObjectId>,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError>;
+    /// Hands the transactions of invoices to the new owner of the invoices.
+    async fn change_owner_with_session(
+        &self,
+        invoice_ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError>;
     async fn delete_many_by_ids(
         &self,
         ids: &[ObjectId],
@@ -42,6 +80,8 @@ pub type TransactionServiceDyn = Arc<dyn TransactionServiceExt + Send + Sync>;
 #[derive(Clone)]
 pub struct TransactionService {
     pub repo: TransactionRepoDyn,
+    pub mongo_client: Client,
+    pub debt_service: DebtServiceDyn,
 }
 
 #[async_trait]
@@ -87,6 +127,18 @@ impl TransactionServiceExt for TransactionService {
             .map_err(|e| e.into())
     }
 
+    async fn search(
+        &self,
+        input: SearchTransactionsInput,
+        options: FindOptions,
+    ) -> Result<Vec<Transaction>, AppError> {
+        self.repo
+            .search(input.into(), options)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(|e| e.into())
+    }
+
     async fn find_by_invoice_id(&self, invoice_id: ObjectId) -> Result<Vec<Transaction>, AppError> {
         self.repo
             .find_by_invoice_id(invoice_id)
@@ -118,6 +170,10 @@ impl TransactionServiceExt for TransactionService {
                         amount: v.amount,
                         currency: v.currency,
                         category_id: v.category_id,
+                        account_id: v.account_id,
+                        to_account_id: v.to_account_id,
+                        from_amount: v.from_amount,
+                        to_amount: v.to_amount,
                         type_: v.type_,
                         unit: v.unit,
                         quantity: v.quantity,
@@ -130,15 +186,118 @@ impl TransactionServiceExt for TransactionService {
             .map_err(|e| e.into())
     }
 
+    async fn replace_many_with_session(
+        &self,
+        items: &[ReplaceTransactionInput],
+        session: &mut ClientSession,
+    ) -> Result<bool, AppError> {
+        let items = items
+            .iter()
+            .map(Into::into)
+            .collect::<Vec<ReplaceTransactionData>>();
+
+        self.repo
+            .replace_many_with_session(&items, session)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn update_by_invoice_id_with_session(
+        &self,
+        data: &UpdateInvoiceTransactionsData,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError> {
+        self.repo
+            .update_by_invoice_id_with_session(data, session)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn unset_account(&self, account_id: ObjectId) -> Result<u64, AppError> {
+        self.repo
+            .unset_account(account_id)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn check_unsplit(&self, ids: &[ObjectId]) -> Result<(), AppError> {
+        let split = self
+            .repo
+            .find(doc! { "_id": { "$in": ids }, "splitId": { "$ne": null } })
+            .await?;
+        if !split.is_empty() {
+            return Err(TransactionError::Split.into());
+        }
+
+        Ok(())
+    }
+
+    async fn set_split_with_session(
+        &self,
+        ids: &[ObjectId],
+        split_id: Option<ObjectId>,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError> {
+        self.repo
+            .set_split_with_session(ids, split_id, session)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    async fn change_owner_with_session(
+        &self,
+        invoice_ids: &[ObjectId],
+        from: ObjectId,
+        to: ObjectId,
+        session: &mut ClientSession,
+    ) -> Result<u64, AppError> {
+        self.repo
+            .change_owner_with_session(invoice_ids, from, to, session)
+            .await
+            .map_err(|e| e.into())
+    }
+
     async fn delete_many_by_ids(
         &self,
         ids: &[ObjectId],
         user_id: &ObjectId,
     ) -> Result<bool, AppError> {
-        self.repo
-            .delete_many_by_ids(ids, user_id)
+        let owned = self
+            .repo
+            .find(doc! { "_id": { "$in": ids }, "userId": user_id })
+            .await?
+            .into_iter()
+            .map(|tx| tx.id)
+            .collect::<Vec<ObjectId>>();
+
+        // entries of the ledger read from the transactions go with them
+        let mut session = self
+            .mongo_client
+            .start_session()
             .await
-            .map_err(|e| e.into())
+            .map_err(|e| AppError::Unknown(e.into()))?;
+        let deleted = session
+            .start_transaction()
+            .and_run(&owned, |session, owned| {
+                async move {
+                    let deleted = self
+                        .repo
+                        .delete_many_by_ids_with_session(owned, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+                    self.debt_service
+                        .delete_by_transaction_ids_with_session(owned, session)
+                        .await
+                        .map_err(mongodb::error::Error::custom)?;
+
+                    Ok(deleted)
+                }
+                .boxed()
+            })
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))?;
+
+        Ok(deleted && owned.len() == ids.len())
     }
 
     async fn delete_many_by_ids_with_session(
@@ -1,3 +1,4 @@
+use crate::api::job::{CreateJobInput, JobPayload};
 use crate::api::state::AppState;
 use crate::api::user::{UpdateUserBody, UpdateUserInput, User, UserError};
 use crate::common::errors::AppError;
@@ -65,9 +66,17 @@ pub async fn delete_user(
     State(state): State<AppState>,
     Extension(user): Extension<User>,
 ) -> Result<StatusCode, AppError> {
+    let user_id = object_id!(&user.id);
+    state.user_service.soft_delete_by_id(user_id).await?;
+
+    // the stored files of the user are deleted in the background
     state
-        .user_service
-        .soft_delete_by_id(object_id!(&user.id))
+        .job_service
+        .enqueue(CreateJobInput {
+            user_id,
+            workspace_id: None,
+            payload: JobPayload::DeleteUserObjects,
+        })
         .await?;
 
     Ok(StatusCode::NO_CONTENT)
@@ -18,6 +18,9 @@ pub struct UserEntity {
     pub language: String,
     pub regions: Vec<String>,
     pub currency: String,
+    // local part of the address receipts are forwarded to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inbound_token: Option<String>,
     #[serde_as(as = "Option<bson::DateTime>")]
     pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
@@ -18,6 +18,12 @@ pub trait UserRepoExt: Send + Sync {
         data: UpdateUserData,
     ) -> Result<Option<UserEntity>, UserError>;
     async fn soft_delete_by_id(&self, id: ObjectId) -> Result<(), UserError>;
+    async fn find_by_inbound_token(&self, token: &str) -> Result<Option<UserEntity>, UserError>;
+    async fn set_inbound_token(
+        &self,
+        id: ObjectId,
+        token: String,
+    ) -> Result<Option<UserEntity>, UserError>;
 }
 
 pub type UserRepoDyn = Arc<dyn UserRepoExt + Send + Sync>;
@@ -56,6 +62,7 @@ impl UserRepoExt for UserRepo {
             given_name: data.given_name,
             picture: data.picture,
             encrypted_password: data.encrypted_password,
+            inbound_token: None,
             deleted_at: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
@@ -128,4 +135,26 @@ impl UserRepoExt for UserRepo {
 
         Ok(())
     }
+
+    async fn find_by_inbound_token(&self, token: &str) -> Result<Option<UserEntity>, UserError> {
+        self.collection
+            .find_one(doc! { "inboundToken": token, "deletedAt": null })
+            .await
+            .map_err(|e| UserError::Unknown(e.into()))
+    }
+
+    async fn set_inbound_token(
+        &self,
+        id: ObjectId,
+        token: String,
+    ) -> Result<Option<UserEntity>, UserError> {
+        self.collection
+            .find_one_and_update(
+                doc! { "_id": id, "deletedAt": null, "inboundToken": null },
+                doc! { "$set": { "inboundToken": token } },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| UserError::Unknown(e.into()))
+    }
 }
@@ -21,6 +21,8 @@ pub trait UserServiceExt: Send + Sync {
         data: UpdateUserInput,
     ) -> Result<Option<User>, AppError>;
     async fn soft_delete_by_id(&self, id: ObjectId) -> Result<(), AppError>;
+    async fn find_by_inbound_token(&self, token: &str) -> Result<Option<User>, AppError>;
+    async fn get_inbound_token(&self, id: ObjectId) -> Result<String, AppError>;
 }
 
 pub type UserServiceDyn = Arc<dyn UserServiceExt + Send + Sync>;
@@ -160,4 +162,35 @@ impl UserServiceExt for UserService {
 
         Ok(())
     }
+
+    async fn find_by_inbound_token(&self, token: &str) -> Result<Option<User>, AppError> {
+        self.repo
+            .find_by_inbound_token(&token.to_lowercase())
+            .await
+            .map_err(Into::into)
+            .map(|v| v.map(Into::into))
+    }
+
+    async fn get_inbound_token(&self, id: ObjectId) -> Result<String, AppError> {
+        let user = self.repo.find_by_id(id).await?.ok_or(UserError::NotFound)?;
+        if let Some(token) = user.inbound_token {
+            return Ok(token);
+        }
+
+        // lowercase only, mail servers may not keep the case of the local part
+        let alphabet = "abcdefghijklmnopqrstuvwxyz0123456789"
+            .chars()
+            .collect::<Vec<_>>();
+        let token = nanoid::nanoid!(16, &alphabet);
+        if let Some(user) = self.repo.set_inbound_token(id, token).await? {
+            return user.inbound_token.ok_or(UserError::NotFound.into());
+        }
+
+        // another request set the token first
+        self.repo
+            .find_by_id(id)
+            .await?
+            .and_then(|user| user.inbound_token)
+            .ok_or(UserError::NotFound.into())
+    }
 }
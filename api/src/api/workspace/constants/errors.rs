@@ -0,0 +1,50 @@
+use crate::common::errors::ErrorResponse;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    #[error("workspace not found")]
+    NotFound,
+    #[error("invalid workspace id {0}")]
+    InvalidWorkspaceId(String),
+    #[error("no user with email {0}")]
+    UserNotFound(String),
+    #[error("member not found")]
+    MemberNotFound,
+    #[error("{0} is already a member of the workspace")]
+    AlreadyMember(String),
+    #[error("only owners can manage the workspace")]
+    OwnerRequired,
+    #[error("viewers cannot change the workspace")]
+    ReadOnly,
+    #[error("the workspace needs an owner")]
+    LastOwner,
+    #[error("only available outside a workspace")]
+    PersonalOnly,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for WorkspaceError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::InvalidWorkspaceId(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::UserNotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::MemberNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::AlreadyMember(_) => (StatusCode::CONFLICT, self.to_string()),
+            Self::OwnerRequired => (StatusCode::FORBIDDEN, self.to_string()),
+            Self::ReadOnly => (StatusCode::FORBIDDEN, self.to_string()),
+            Self::LastOwner => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::PersonalOnly => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
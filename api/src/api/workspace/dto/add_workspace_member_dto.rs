@@ -0,0 +1,23 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::workspace::WorkspaceRole;
+
+pub struct AddWorkspaceMemberInput {
+    pub workspace_id: ObjectId,
+    pub user_id: ObjectId,
+    pub email: String,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct AddWorkspaceMemberBody {
+    #[schema(example = "lan@test.com")]
+    #[validate(length(min = 1))]
+    pub email: String,
+    #[schema(example = "editor")]
+    pub role: WorkspaceRole,
+}
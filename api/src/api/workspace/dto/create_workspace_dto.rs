@@ -0,0 +1,25 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::workspace::WorkspaceMemberEntity;
+
+pub struct CreateWorkspaceData {
+    pub name: String,
+    pub members: Vec<WorkspaceMemberEntity>,
+}
+
+pub struct CreateWorkspaceInput {
+    pub user_id: ObjectId,
+    pub user_name: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWorkspaceBody {
+    #[schema(example = "Family")]
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+}
@@ -0,0 +1,9 @@
+mod add_workspace_member_dto;
+mod create_workspace_dto;
+mod move_to_workspace_dto;
+mod update_workspace_member_dto;
+
+pub use add_workspace_member_dto::*;
+pub use create_workspace_dto::*;
+pub use move_to_workspace_dto::*;
+pub use update_workspace_member_dto::*;
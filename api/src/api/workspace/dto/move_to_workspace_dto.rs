@@ -0,0 +1,21 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+use validator::Validate;
+
+pub struct MoveToWorkspaceInput {
+    pub workspace_id: ObjectId,
+    pub user_id: ObjectId,
+    pub invoice_ids: Vec<ObjectId>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveToWorkspaceBody {
+    /// Personal invoices to share, with their transactions and messages
+    #[schema(example = json!(["669e5f02b781150b9a578205"]))]
+    #[validate(length(min = 1, max = 100))]
+    pub invoice_ids: Vec<String>,
+}
@@ -0,0 +1,20 @@
+use bson::oid::ObjectId;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::api::workspace::WorkspaceRole;
+
+pub struct UpdateWorkspaceMemberInput {
+    pub workspace_id: ObjectId,
+    pub user_id: ObjectId,
+    pub member_id: ObjectId,
+    pub role: WorkspaceRole,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWorkspaceMemberBody {
+    #[schema(example = "viewer")]
+    pub role: WorkspaceRole,
+}
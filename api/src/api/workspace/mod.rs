@@ -0,0 +1,20 @@
+pub(crate) use constants::*;
+pub(crate) use dto::*;
+pub(crate) use workspace_access::*;
+#[allow(unused_imports)]
+pub use workspace_controller::WorkspaceApiDoc;
+pub(crate) use workspace_entity::*;
+pub use workspace_model::*;
+pub(crate) use workspace_repo::*;
+pub use workspace_router::*;
+pub use workspace_service::*;
+
+mod constants;
+mod dto;
+mod workspace_access;
+mod workspace_controller;
+mod workspace_entity;
+mod workspace_model;
+mod workspace_repo;
+mod workspace_router;
+mod workspace_service;
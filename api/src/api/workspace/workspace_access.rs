@@ -0,0 +1,136 @@
+use bson::oid::ObjectId;
+
+use crate::api::workspace::{
+    Scope, WorkspaceEntity, WorkspaceError, WorkspaceMemberEntity, WorkspaceRole,
+};
+
+/// Scope of a request of the user in the workspace. Viewers only read, the workspace of
+/// someone else is not found.
+pub fn workspace_scope(
+    workspace: &WorkspaceEntity,
+    user_id: ObjectId,
+    writes: bool,
+) -> Result<Scope, WorkspaceError> {
+    let member = workspace.member(user_id).ok_or(WorkspaceError::NotFound)?;
+    if writes && !member.role.can_write() {
+        return Err(WorkspaceError::ReadOnly);
+    }
+
+    Ok(Scope {
+        owner_id: workspace.id,
+        workspace_id: Some(workspace.id),
+        role: member.role,
+    })
+}
+
+/// Who a job runs as on a ledger when no member asked for it, e.g. a batch reprocess: the
+/// user of a personal ledger, an owner of a workspace. Returns the user and the workspace.
+pub fn ledger_member(
+    owner_id: ObjectId,
+    workspace: Option<&WorkspaceEntity>,
+) -> Result<(ObjectId, Option<ObjectId>), WorkspaceError> {
+    let Some(workspace) = workspace else {
+        return Ok((owner_id, None));
+    };
+    let owner = workspace
+        .members
+        .iter()
+        .find(|member| member.role == WorkspaceRole::Owner)
+        .ok_or(WorkspaceError::NotFound)?;
+
+    Ok((owner.user_id, Some(workspace.id)))
+}
+
+/// Whether someone still owns the workspace once the member gets the role, or leaves when
+/// there is no role.
+pub fn keeps_owner(
+    members: &[WorkspaceMemberEntity],
+    user_id: ObjectId,
+    role: Option<WorkspaceRole>,
+) -> bool {
+    members.iter().any(|member| {
+        let role = if member.user_id == user_id {
+            role
+        } else {
+            Some(member.role)
+        };
+        role == Some(WorkspaceRole::Owner)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(roles: &[WorkspaceRole]) -> WorkspaceEntity {
+        WorkspaceEntity {
+            id: ObjectId::new(),
+            name: "Family".to_string(),
+            members: roles
+                .iter()
+                .map(|role| WorkspaceMemberEntity {
+                    user_id: ObjectId::new(),
+                    name: "Test User".to_string(),
+                    role: *role,
+                })
+                .collect(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_workspace_scope() {
+        let workspace = workspace(&[WorkspaceRole::Owner, WorkspaceRole::Viewer]);
+        let [owner, viewer] = [0, 1].map(|i| workspace.members[i].user_id);
+
+        let scope = workspace_scope(&workspace, owner, true).unwrap();
+        assert_eq!(scope.owner_id, workspace.id);
+        assert!(workspace_scope(&workspace, viewer, false).is_ok());
+        assert!(matches!(
+            workspace_scope(&workspace, viewer, true),
+            Err(WorkspaceError::ReadOnly)
+        ));
+        assert!(matches!(
+            workspace_scope(&workspace, ObjectId::new(), false),
+            Err(WorkspaceError::NotFound)
+        ));
+        assert!(matches!(
+            scope.personal_owner(),
+            Err(WorkspaceError::PersonalOnly)
+        ));
+        assert_eq!(Scope::personal(owner).personal_owner().unwrap(), owner);
+    }
+
+    #[test]
+    fn test_ledger_member() {
+        let user_id = ObjectId::new();
+        assert_eq!(ledger_member(user_id, None).unwrap(), (user_id, None));
+
+        // the invoice of a workspace is stored with the workspace id, it is read as an owner
+        let workspace = workspace(&[WorkspaceRole::Editor, WorkspaceRole::Owner]);
+        assert_eq!(
+            ledger_member(workspace.id, Some(&workspace)).unwrap(),
+            (workspace.members[1].user_id, Some(workspace.id))
+        );
+    }
+
+    #[test]
+    fn test_keeps_owner() {
+        let workspace = workspace(&[WorkspaceRole::Owner, WorkspaceRole::Editor]);
+        let [owner, editor] = [0, 1].map(|i| workspace.members[i].user_id);
+
+        assert!(!keeps_owner(&workspace.members, owner, None));
+        assert!(!keeps_owner(
+            &workspace.members,
+            owner,
+            Some(WorkspaceRole::Viewer)
+        ));
+        assert!(keeps_owner(&workspace.members, editor, None));
+        assert!(keeps_owner(
+            &workspace.members,
+            editor,
+            Some(WorkspaceRole::Owner)
+        ));
+    }
+}
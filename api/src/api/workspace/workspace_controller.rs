@@ -0,0 +1,233 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use utoipa::OpenApi;
+
+use crate::api::state::AppState;
+use crate::api::user::User;
+use crate::api::workspace::{
+    AddWorkspaceMemberBody, AddWorkspaceMemberInput, CreateWorkspaceBody, CreateWorkspaceInput,
+    MoveToWorkspaceBody, MoveToWorkspaceInput, MovedPayload, UpdateWorkspaceMemberBody,
+    UpdateWorkspaceMemberInput, Workspace, WorkspaceMember, WorkspaceRole,
+};
+use crate::common::errors::AppError;
+use crate::common::hooks::ValidJson;
+use crate::macros::object_id;
+
+#[utoipa::path(
+    get,
+    path = "",
+    responses(
+        (status = 200, description = "List workspaces of the user successfully", body = [Workspace]),
+    )
+)]
+pub async fn list_workspaces(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+) -> Result<Json<Vec<Workspace>>, AppError> {
+    let workspaces = state.workspace_service.list(object_id!(&user.id)).await?;
+
+    Ok(Json(workspaces))
+}
+
+#[utoipa::path(
+    post,
+    path = "",
+    request_body = CreateWorkspaceBody,
+    responses(
+        (status = 201, description = "Create workspace owned by the user successfully", body = Workspace),
+    )
+)]
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    ValidJson(body): ValidJson<CreateWorkspaceBody>,
+) -> Result<(StatusCode, Json<Workspace>), AppError> {
+    let workspace = state
+        .workspace_service
+        .create(CreateWorkspaceInput {
+            user_id: object_id!(&user.id),
+            user_name: user.full_name,
+            name: body.name,
+        })
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(workspace)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{workspace_id}",
+    responses(
+        (status = 200, description = "Get workspace successfully", body = Workspace),
+    ),
+    params(
+        ("workspace_id" = String, Path, description = "Workspace database id"),
+    )
+)]
+pub async fn get_workspace(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(workspace_id): Path<String>,
+) -> Result<Json<Workspace>, AppError> {
+    let workspace = state
+        .workspace_service
+        .find_by_id(object_id!(&workspace_id), object_id!(&user.id))
+        .await?;
+
+    Ok(Json(workspace))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{workspace_id}/members",
+    request_body = AddWorkspaceMemberBody,
+    responses(
+        (status = 200, description = "Add member successfully", body = Workspace),
+        (status = 403, description = "Only owners add members"),
+        (status = 409, description = "The user is already a member"),
+    ),
+    params(
+        ("workspace_id" = String, Path, description = "Workspace database id"),
+    )
+)]
+pub async fn add_member(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(workspace_id): Path<String>,
+    ValidJson(body): ValidJson<AddWorkspaceMemberBody>,
+) -> Result<Json<Workspace>, AppError> {
+    let workspace = state
+        .workspace_service
+        .add_member(AddWorkspaceMemberInput {
+            workspace_id: object_id!(&workspace_id),
+            user_id: object_id!(&user.id),
+            email: body.email,
+            role: body.role,
+        })
+        .await?;
+
+    Ok(Json(workspace))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/{workspace_id}/members/{user_id}",
+    request_body = UpdateWorkspaceMemberBody,
+    responses(
+        (status = 200, description = "Change role of member successfully", body = Workspace),
+        (status = 403, description = "Only owners change roles"),
+        (status = 422, description = "The workspace would have no owner"),
+    ),
+    params(
+        ("workspace_id" = String, Path, description = "Workspace database id"),
+        ("user_id" = String, Path, description = "User database id of the member"),
+    )
+)]
+pub async fn update_member(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path((workspace_id, member_id)): Path<(String, String)>,
+    ValidJson(body): ValidJson<UpdateWorkspaceMemberBody>,
+) -> Result<Json<Workspace>, AppError> {
+    let workspace = state
+        .workspace_service
+        .update_member(UpdateWorkspaceMemberInput {
+            workspace_id: object_id!(&workspace_id),
+            user_id: object_id!(&user.id),
+            member_id: object_id!(&member_id),
+            role: body.role,
+        })
+        .await?;
+
+    Ok(Json(workspace))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{workspace_id}/members/{user_id}",
+    responses(
+        (status = 200, description = "Remove member, or leave, successfully", body = Workspace),
+        (status = 403, description = "Only owners remove other members"),
+        (status = 422, description = "The workspace would have no owner"),
+    ),
+    params(
+        ("workspace_id" = String, Path, description = "Workspace database id"),
+        ("user_id" = String, Path, description = "User database id of the member"),
+    )
+)]
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path((workspace_id, member_id)): Path<(String, String)>,
+) -> Result<Json<Workspace>, AppError> {
+    let workspace = state
+        .workspace_service
+        .remove_member(
+            object_id!(&workspace_id),
+            object_id!(&user.id),
+            object_id!(&member_id),
+        )
+        .await?;
+
+    Ok(Json(workspace))
+}
+
+#[utoipa::path(
+    post,
+    path = "/{workspace_id}/move",
+    request_body = MoveToWorkspaceBody,
+    responses(
+        (status = 200, description = "Move personal invoices into the workspace successfully", body = MovedPayload),
+        (status = 403, description = "Viewers cannot move invoices"),
+    ),
+    params(
+        ("workspace_id" = String, Path, description = "Workspace database id"),
+    )
+)]
+pub async fn move_to_workspace(
+    State(state): State<AppState>,
+    Extension(user): Extension<User>,
+    Path(workspace_id): Path<String>,
+    ValidJson(body): ValidJson<MoveToWorkspaceBody>,
+) -> Result<Json<MovedPayload>, AppError> {
+    let moved = state
+        .workspace_service
+        .move_to_workspace(MoveToWorkspaceInput {
+            workspace_id: object_id!(&workspace_id),
+            user_id: object_id!(&user.id),
+            invoice_ids: body.invoice_ids.iter().map(|id| object_id!(id)).collect(),
+        })
+        .await?;
+
+    Ok(Json(moved))
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_workspaces,
+        create_workspace,
+        get_workspace,
+        add_member,
+        update_member,
+        remove_member,
+        move_to_workspace,
+    ),
+    components(
+        schemas(
+            Workspace,
+            WorkspaceMember,
+            WorkspaceRole,
+            MovedPayload,
+            CreateWorkspaceBody,
+            AddWorkspaceMemberBody,
+            UpdateWorkspaceMemberBody,
+            MoveToWorkspaceBody,
+        )
+    ),
+    tags(
+        (name = "crate::api::workspace", description = "Workspace API")
+    )
+)]
+pub struct WorkspaceApiDoc;
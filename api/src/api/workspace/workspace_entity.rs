@@ -0,0 +1,34 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+
+use crate::api::workspace::WorkspaceRole;
+
+/// A ledger shared by a household. Its transactions, invoices and messages are stored
+/// with the workspace id in place of the id of a user.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEntity {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub name: String,
+    pub members: Vec<WorkspaceMemberEntity>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "bson::serde_helpers::chrono_datetime_as_bson_datetime")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl WorkspaceEntity {
+    pub fn member(&self, user_id: ObjectId) -> Option<&WorkspaceMemberEntity> {
+        self.members.iter().find(|member| member.user_id == user_id)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMemberEntity {
+    pub user_id: ObjectId,
+    // full name when the member joined
+    pub name: String,
+    pub role: WorkspaceRole,
+}
@@ -0,0 +1,114 @@
+use bson::oid::ObjectId;
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use serde_json::json;
+use utoipa::ToSchema;
+
+use crate::api::workspace::{WorkspaceEntity, WorkspaceError, WorkspaceMemberEntity};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceRole {
+    /// Changes the ledger and manages the members
+    Owner,
+    /// Changes the ledger
+    Editor,
+    /// Reads the ledger
+    Viewer,
+}
+
+impl WorkspaceRole {
+    pub fn can_write(&self) -> bool {
+        matches!(self, Self::Owner | Self::Editor)
+    }
+}
+
+/// Header naming the workspace a request is made in.
+pub const WORKSPACE_HEADER: &str = "x-workspace-id";
+
+/// Whose ledger a request reads and writes, set by `authorization_mw`. Without the
+/// workspace header it is the ledger of the user.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    // the user, or the workspace, stored as `userId` and `threadId`
+    pub owner_id: ObjectId,
+    pub workspace_id: Option<ObjectId>,
+    pub role: WorkspaceRole,
+}
+
+impl Scope {
+    pub fn personal(user_id: ObjectId) -> Self {
+        Self {
+            owner_id: user_id,
+            workspace_id: None,
+            role: WorkspaceRole::Owner,
+        }
+    }
+
+    /// The user, for features kept out of workspaces.
+    pub fn personal_owner(&self) -> Result<ObjectId, WorkspaceError> {
+        match self.workspace_id {
+            Some(_) => Err(WorkspaceError::PersonalOnly),
+            None => Ok(self.owner_id),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    #[schema(example = "66a4e3f2ce6a5cbb87195e10")]
+    pub id: String,
+    #[schema(example = "Family")]
+    pub name: String,
+    pub members: Vec<WorkspaceMember>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(example = "2024-07-22T13:30:42.246017Z")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<WorkspaceEntity> for Workspace {
+    fn from(value: WorkspaceEntity) -> Self {
+        Self {
+            id: value.id.to_hex(),
+            name: value.name,
+            members: value.members.into_iter().map(Into::into).collect(),
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceMember {
+    #[schema(example = "66990b1947d76ec3781adc9d")]
+    pub user_id: String,
+    #[schema(example = "Test User")]
+    pub name: String,
+    #[schema(example = "editor")]
+    pub role: WorkspaceRole,
+}
+
+impl From<WorkspaceMemberEntity> for WorkspaceMember {
+    fn from(value: WorkspaceMemberEntity) -> Self {
+        Self {
+            user_id: value.user_id.to_hex(),
+            name: value.name,
+            role: value.role,
+        }
+    }
+}
+
+/// What was moved from the ledger of the user into the workspace.
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedPayload {
+    #[schema(example = 2)]
+    pub invoices: u64,
+    #[schema(example = 5)]
+    pub transactions: u64,
+    #[schema(example = 4)]
+    pub messages: u64,
+}
@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::doc;
+use bson::oid::ObjectId;
+use futures::StreamExt;
+use mongodb::options::ReturnDocument;
+use mongodb::Collection;
+
+use crate::api::workspace::*;
+
+#[async_trait]
+pub trait WorkspaceRepoExt: Send + Sync {
+    async fn insert_one(
+        &self,
+        data: CreateWorkspaceData,
+    ) -> Result<WorkspaceEntity, WorkspaceError>;
+    async fn find_by_id(&self, id: ObjectId) -> Result<Option<WorkspaceEntity>, WorkspaceError>;
+    async fn find_by_member(
+        &self,
+        user_id: ObjectId,
+    ) -> Result<Vec<WorkspaceEntity>, WorkspaceError>;
+    async fn add_member(
+        &self,
+        id: ObjectId,
+        member: WorkspaceMemberEntity,
+    ) -> Result<Option<WorkspaceEntity>, WorkspaceError>;
+    async fn update_member_role(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+        role: WorkspaceRole,
+    ) -> Result<Option<WorkspaceEntity>, WorkspaceError>;
+    async fn remove_member(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Option<WorkspaceEntity>, WorkspaceError>;
+}
+
+pub type WorkspaceRepoDyn = Arc<dyn WorkspaceRepoExt + Send + Sync>;
+
+pub struct WorkspaceRepo {
+    pub collection: Collection<WorkspaceEntity>,
+}
+
+#[async_trait]
+impl WorkspaceRepoExt for WorkspaceRepo {
+    async fn insert_one(
+        &self,
+        data: CreateWorkspaceData,
+    ) -> Result<WorkspaceEntity, WorkspaceError> {
+        let document = WorkspaceEntity {
+            id: ObjectId::new(),
+            name: data.name,
+            members: data.members,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        self.collection
+            .insert_one(&document)
+            .await
+            .map_err(|e| WorkspaceError::Unknown(e.into()))?;
+
+        Ok(document)
+    }
+
+    async fn find_by_id(&self, id: ObjectId) -> Result<Option<WorkspaceEntity>, WorkspaceError> {
+        self.collection
+            .find_one(doc! { "_id": id })
+            .await
+            .map_err(|e| WorkspaceError::Unknown(e.into()))
+    }
+
+    async fn find_by_member(
+        &self,
+        user_id: ObjectId,
+    ) -> Result<Vec<WorkspaceEntity>, WorkspaceError> {
+        let mut cursor = self
+            .collection
+            .find(doc! { "members.userId": user_id })
+            .sort(doc! { "name": 1 })
+            .await
+            .map_err(|e| WorkspaceError::Unknown(e.into()))?;
+
+        let mut documents = vec![];
+        while let Some(Ok(document)) = cursor.next().await {
+            documents.push(document);
+        }
+
+        Ok(documents)
+    }
+
+    async fn add_member(
+        &self,
+        id: ObjectId,
+        member: WorkspaceMemberEntity,
+    ) -> Result<Option<WorkspaceEntity>, WorkspaceError> {
+        let member = bson::to_bson(&member).map_err(|e| WorkspaceError::Unknown(e.into()))?;
+
+        self.collection
+            .find_one_and_update(
+                doc! { "_id": id },
+                doc! {
+                    "$push": { "members": member },
+                    "$set": { "updatedAt": chrono::Utc::now() },
+                },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| WorkspaceError::Unknown(e.into()))
+    }
+
+    async fn update_member_role(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+        role: WorkspaceRole,
+    ) -> Result<Option<WorkspaceEntity>, WorkspaceError> {
+        let role = bson::to_bson(&role).map_err(|e| WorkspaceError::Unknown(e.into()))?;
+
+        self.collection
+            .find_one_and_update(
+                doc! { "_id": id, "members.userId": user_id },
+                doc! {
+                    "$set": {
+                        "members.$.role": role,
+                        "updatedAt": chrono::Utc::now(),
+                    },
+                },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| WorkspaceError::Unknown(e.into()))
+    }
+
+    async fn remove_member(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<Option<WorkspaceEntity>, WorkspaceError> {
+        self.collection
+            .find_one_and_update(
+                doc! { "_id": id },
+                doc! {
+                    "$pull": { "members": { "userId": user_id } },
+                    "$set": { "updatedAt": chrono::Utc::now() },
+                },
+            )
+            .return_document(ReturnDocument::After)
+            .await
+            .map_err(|e| WorkspaceError::Unknown(e.into()))
+    }
+}
@@ -0,0 +1,32 @@
+use axum::middleware::from_fn_with_state;
+use axum::routing::{get, patch, post};
+use axum::Router;
+
+use crate::api::state::AppState;
+use crate::api::workspace::workspace_controller::*;
+use crate::mw::authorization_mw;
+
+pub struct WorkspaceRouter(Router<AppState>);
+
+impl WorkspaceRouter {
+    pub fn new(state: AppState) -> Self {
+        let routes = Router::new()
+            .route("/", get(list_workspaces).post(create_workspace))
+            .route("/:workspace_id", get(get_workspace))
+            .route("/:workspace_id/members", post(add_member))
+            .route(
+                "/:workspace_id/members/:user_id",
+                patch(update_member).delete(remove_member),
+            )
+            .route("/:workspace_id/move", post(move_to_workspace))
+            .route_layer(from_fn_with_state(state.clone(), authorization_mw));
+
+        Self(routes)
+    }
+}
+
+impl From<WorkspaceRouter> for Router<AppState> {
+    fn from(router: WorkspaceRouter) -> Self {
+        router.0
+    }
+}
@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bson::oid::ObjectId;
+use futures::FutureExt;
+use mongodb::Client;
+
+use crate::api::invoice::InvoiceServiceDyn;
+use crate::api::message::MessageServiceDyn;
+use crate::api::transaction::TransactionServiceDyn;
+use crate::api::user::UserServiceDyn;
+use crate::api::workspace::*;
+use crate::common::errors::AppError;
+use crate::object_id;
+
+#[async_trait]
+pub trait WorkspaceServiceExt: Send + Sync {
+    async fn create(&self, input: CreateWorkspaceInput) -> Result<Workspace, AppError>;
+    async fn list(&self, user_id: ObjectId) -> Result<Vec<Workspace>, AppError>;
+    async fn find_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<Workspace, AppError>;
+    async fn add_member(&self, input: AddWorkspaceMemberInput) -> Result<Workspace, AppError>;
+    async fn update_member(&self, input: UpdateWorkspaceMemberInput)
+        -> Result<Workspace, AppError>;
+    /// Owners remove anyone, the other members only leave.
+    async fn remove_member(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+        member_id: ObjectId,
+    ) -> Result<Workspace, AppError>;
+    /// Whose ledger the user reads, or writes, in the workspace.
+    async fn scope(&self, id: ObjectId, user_id: ObjectId, writes: bool)
+        -> Result<Scope, AppError>;
+    /// Who jobs on the ledger run as, and the workspace of the ledger, see `ledger_member`.
+    async fn ledger_member(
+        &self,
+        owner_id: ObjectId,
+    ) -> Result<(ObjectId, Option<ObjectId>), AppError>;
+    /// Moves personal invoices of the user, with their transactions and messages, into the
+    /// ledger of the workspace.
+    async fn move_to_workspace(
+        &self,
+        input: MoveToWorkspaceInput,
+    ) -> Result<MovedPayload, AppError>;
+}
+
+pub type WorkspaceServiceDyn = Arc<dyn WorkspaceServiceExt + Send + Sync>;
+
+pub struct WorkspaceService {
+    pub repo: WorkspaceRepoDyn,
+    pub mongo_client: Client,
+    pub user_service: UserServiceDyn,
+    pub invoice_service: InvoiceServiceDyn,
+    pub transaction_service: TransactionServiceDyn,
+    pub message_service: MessageServiceDyn,
+}
+
+impl WorkspaceService {
+    /// The workspace, only when the user is one of its members.
+    async fn member_workspace(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<WorkspaceEntity, AppError> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .filter(|workspace| workspace.member(user_id).is_some())
+            .ok_or(WorkspaceError::NotFound.into())
+    }
+
+    /// The workspace, only when the user owns it.
+    async fn owned_workspace(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+    ) -> Result<WorkspaceEntity, AppError> {
+        let workspace = self.member_workspace(id, user_id).await?;
+        if workspace.member(user_id).map(|member| member.role) != Some(WorkspaceRole::Owner) {
+            return Err(WorkspaceError::OwnerRequired.into());
+        }
+
+        Ok(workspace)
+    }
+}
+
+#[async_trait]
+impl WorkspaceServiceExt for WorkspaceService {
+    async fn create(&self, input: CreateWorkspaceInput) -> Result<Workspace, AppError> {
+        self.repo
+            .insert_one(CreateWorkspaceData {
+                name: input.name.trim().to_string(),
+                members: vec![WorkspaceMemberEntity {
+                    user_id: input.user_id,
+                    name: input.user_name,
+                    role: WorkspaceRole::Owner,
+                }],
+            })
+            .await
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+
+    async fn list(&self, user_id: ObjectId) -> Result<Vec<Workspace>, AppError> {
+        self.repo
+            .find_by_member(user_id)
+            .await
+            .map(|items| items.into_iter().map(Into::into).collect())
+            .map_err(Into::into)
+    }
+
+    async fn find_by_id(&self, id: ObjectId, user_id: ObjectId) -> Result<Workspace, AppError> {
+        self.member_workspace(id, user_id).await.map(Into::into)
+    }
+
+    async fn add_member(&self, input: AddWorkspaceMemberInput) -> Result<Workspace, AppError> {
+        let workspace = self
+            .owned_workspace(input.workspace_id, input.user_id)
+            .await?;
+        let user = self
+            .user_service
+            .find_by_email(input.email.trim().to_string())
+            .await?
+            .ok_or(WorkspaceError::UserNotFound(input.email.clone()))?;
+        let user_id = object_id!(&user.id);
+        if workspace.member(user_id).is_some() {
+            return Err(WorkspaceError::AlreadyMember(input.email).into());
+        }
+
+        self.repo
+            .add_member(
+                workspace.id,
+                WorkspaceMemberEntity {
+                    user_id,
+                    name: user.full_name,
+                    role: input.role,
+                },
+            )
+            .await?
+            .map(Into::into)
+            .ok_or(WorkspaceError::NotFound.into())
+    }
+
+    async fn update_member(
+        &self,
+        input: UpdateWorkspaceMemberInput,
+    ) -> Result<Workspace, AppError> {
+        let workspace = self
+            .owned_workspace(input.workspace_id, input.user_id)
+            .await?;
+        if workspace.member(input.member_id).is_none() {
+            return Err(WorkspaceError::MemberNotFound.into());
+        }
+        if !keeps_owner(&workspace.members, input.member_id, Some(input.role)) {
+            return Err(WorkspaceError::LastOwner.into());
+        }
+
+        self.repo
+            .update_member_role(workspace.id, input.member_id, input.role)
+            .await?
+            .map(Into::into)
+            .ok_or(WorkspaceError::MemberNotFound.into())
+    }
+
+    async fn remove_member(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+        member_id: ObjectId,
+    ) -> Result<Workspace, AppError> {
+        let workspace = if member_id == user_id {
+            self.member_workspace(id, user_id).await?
+        } else {
+            self.owned_workspace(id, user_id).await?
+        };
+        if workspace.member(member_id).is_none() {
+            return Err(WorkspaceError::MemberNotFound.into());
+        }
+        if !keeps_owner(&workspace.members, member_id, None) {
+            return Err(WorkspaceError::LastOwner.into());
+        }
+
+        self.repo
+            .remove_member(workspace.id, member_id)
+            .await?
+            .map(Into::into)
+            .ok_or(WorkspaceError::NotFound.into())
+    }
+
+    async fn scope(
+        &self,
+        id: ObjectId,
+        user_id: ObjectId,
+        writes: bool,
+    ) -> Result<Scope, AppError> {
+        let workspace = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or(WorkspaceError::NotFound)?;
+
+        workspace_scope(&workspace, user_id, writes).map_err(Into::into)
+    }
+
+    async fn ledger_member(
+        &self,
+        owner_id: ObjectId,
+    ) -> Result<(ObjectId, Option<ObjectId>), AppError> {
+        let workspace = self.repo.find_by_id(owner_id).await?;
+
+        ledger_member(owner_id, workspace.as_ref()).map_err(Into::into)
+    }
+
+    async fn move_to_workspace(
+        &self,
+        input: MoveToWorkspaceInput,
+    ) -> Result<MovedPayload, AppError> {
+        let scope = self.scope(input.workspace_id, input.user_id, true).await?;
+        let invoices = self
+            .invoice_service
+            .find_by_ids(&input.invoice_ids, input.user_id)
+            .await?;
+        let invoice_ids = invoices
+            .iter()
+            .map(|invoice| object_id!(&invoice.id))
+            .collect::<Vec<ObjectId>>();
+        let message_ids = invoices
+            .iter()
+            .map(|invoice| object_id!(&invoice.message_id))
+            .collect::<Vec<ObjectId>>();
+        if invoice_ids.is_empty() {
+            return Ok(MovedPayload {
+                invoices: 0,
+                transactions: 0,
+                messages: 0,
+            });
+        }
+        let (from, to) = (input.user_id, scope.owner_id);
+
+        let mut session = self
+            .mongo_client
+            .start_session()
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))?;
+        session
+            .start_transaction()
+            .and_run(
+                (&invoice_ids, &message_ids),
+                |session, (invoice_ids, message_ids)| {
+                    async move {
+                        let invoices = self
+                            .invoice_service
+                            .change_owner_with_session(invoice_ids, from, to, session)
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
+                        let transactions = self
+                            .transaction_service
+                            .change_owner_with_session(invoice_ids, from, to, session)
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
+                        let messages = self
+                            .message_service
+                            .change_owner_with_session(message_ids, from, to, session)
+                            .await
+                            .map_err(mongodb::error::Error::custom)?;
+
+                        Ok(MovedPayload {
+                            invoices,
+                            transactions,
+                            messages,
+                        })
+                    }
+                    .boxed()
+                },
+            )
+            .await
+            .map_err(|e| AppError::Unknown(e.into()))
+    }
+}
@@ -1,17 +1,27 @@
+use crate::api::account::AccountError;
+use crate::api::assistant::AssistantError;
 use crate::api::auth::AuthError;
 use crate::api::category::CategoryError;
+use crate::api::debt::DebtError;
+use crate::api::email::EmailError;
 use crate::api::exchange_rate::ExchangeRateError;
+use crate::api::group::GroupError;
 use crate::api::invoice::InvoiceError;
+use crate::api::job::JobError;
 use crate::api::message::MessageError;
 use crate::api::report::ReportError;
+use crate::api::reprocess::ReprocessError;
 use crate::api::transaction::TransactionError;
 use crate::api::user::UserError;
+use crate::api::workspace::WorkspaceError;
 use crate::common::mongo::CursorError;
 use crate::services::gcp::auth::GCPAuthError;
 use crate::services::gcp::vision::GCPVisionError;
 use crate::services::jwt::JwtError;
 use crate::services::llm::LLMError;
+use crate::services::pdf::PdfError;
 use crate::services::r2::R2Error;
+use crate::services::speech::SpeechError;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
@@ -58,7 +68,27 @@ pub enum AppError {
     #[error(transparent)]
     R2Error(#[from] R2Error),
     #[error(transparent)]
+    PdfError(#[from] PdfError),
+    #[error(transparent)]
+    SpeechError(#[from] SpeechError),
+    #[error(transparent)]
     ReportError(#[from] ReportError),
+    #[error(transparent)]
+    AssistantError(#[from] AssistantError),
+    #[error(transparent)]
+    JobError(#[from] JobError),
+    #[error(transparent)]
+    EmailError(#[from] EmailError),
+    #[error(transparent)]
+    ReprocessError(#[from] ReprocessError),
+    #[error(transparent)]
+    AccountError(#[from] AccountError),
+    #[error(transparent)]
+    DebtError(#[from] DebtError),
+    #[error(transparent)]
+    GroupError(#[from] GroupError),
+    #[error(transparent)]
+    WorkspaceError(#[from] WorkspaceError),
     #[error("forbidden")]
     Forbidden,
     #[error(transparent)]
@@ -81,7 +111,17 @@ impl IntoResponse for AppError {
             Self::GCPAuthError(e) => e.into_response(),
             Self::GCPVisionError(e) => e.into_response(),
             Self::R2Error(e) => e.into_response(),
+            Self::PdfError(e) => e.into_response(),
+            Self::SpeechError(e) => e.into_response(),
             Self::ReportError(e) => e.into_response(),
+            Self::AssistantError(e) => e.into_response(),
+            Self::JobError(e) => e.into_response(),
+            Self::EmailError(e) => e.into_response(),
+            Self::ReprocessError(e) => e.into_response(),
+            Self::AccountError(e) => e.into_response(),
+            Self::DebtError(e) => e.into_response(),
+            Self::GroupError(e) => e.into_response(),
+            Self::WorkspaceError(e) => e.into_response(),
             Self::Forbidden => (
                 StatusCode::FORBIDDEN,
                 Json(ErrorResponse {
@@ -1,3 +1,4 @@
 pub mod errors;
 pub mod hooks;
 pub mod mongo;
+pub mod secret;
@@ -0,0 +1,20 @@
+use subtle::ConstantTimeEq;
+
+/// Compares a secret sent with a request in constant time, so its bytes cannot be guessed
+/// from the response time. An empty expected secret matches nothing.
+pub fn secret_matches(given: &str, expected: &str) -> bool {
+    !expected.is_empty() && bool::from(given.as_bytes().ct_eq(expected.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_matches() {
+        assert!(secret_matches("s3cret", "s3cret"));
+        assert!(!secret_matches("s3cres", "s3cret"));
+        assert!(!secret_matches("s3cret!", "s3cret"));
+        assert!(!secret_matches("", ""));
+    }
+}
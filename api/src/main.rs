@@ -17,8 +17,10 @@ use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
 use utoipa::{Modify, OpenApi};
 use utoipa_scalar::{Scalar, Servable};
 
+use crate::api::job::JobWorker;
 use crate::api::router::ApiRouter;
 use crate::api::state::AppState;
+use crate::api::storage::StorageSweeper;
 use crate::common::errors::ErrorResponse;
 use crate::settings::Settings;
 
@@ -42,6 +44,8 @@ async fn main() {
 
     let settings = Settings::new().unwrap();
     let app_state = AppState::init(settings.clone()).await;
+    JobWorker::spawn(app_state.clone()).await;
+    StorageSweeper::spawn(app_state.clone());
 
     let app = Router::new()
         .nest("/api/v1", ApiRouter::new(app_state.clone()).into())
@@ -82,12 +86,19 @@ async fn main() {
     servers((url = "http://0.0.0.0:3000"), (url = "https://whatsexpense-api.onrender.com")),
     modifiers(&SecurityAddon),
     nest(
+        (path = "/api/v1/accounts", api = crate::api::account::AccountApiDoc),
         (path = "/api/v1/assets", api = crate::api::asset::AssetApiDoc),
         (path = "/api/v1/auth", api = crate::api::auth::AuthApiDoc),
+        (path = "/api/v1/debts", api = crate::api::debt::DebtApiDoc),
+        (path = "/api/v1/groups", api = crate::api::group::GroupApiDoc),
+        (path = "/api/v1/workspaces", api = crate::api::workspace::WorkspaceApiDoc),
         (path = "/api/v1/invoices", api = crate::api::invoice::InvoiceApiDoc),
+        (path = "/api/v1/emails", api = crate::api::email::EmailApiDoc),
         (path = "/api/v1/messages", api = crate::api::message::MessageApiDoc),
         (path = "/api/v1/users", api = crate::api::user::UserApiDoc),
         (path = "/api/v1/reports", api = crate::api::report::ReportApiDoc),
+        (path = "/api/v1/reprocess", api = crate::api::reprocess::ReprocessApiDoc),
+        (path = "/api/v1/storage", api = crate::api::storage::StorageApiDoc),
         (path = "/api/v1/transactions", api = crate::api::transaction::TransactionApiDoc),
         (path = "/api/v1/categories", api = crate::api::category::CategoryApiDoc),
     ),
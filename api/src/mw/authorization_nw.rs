@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use crate::api::state::AppState;
 use crate::api::user::UserError;
+use crate::api::workspace::{Scope, WorkspaceError, WORKSPACE_HEADER};
 use crate::common::errors::AppError;
 use crate::services::jwt::JwtError;
 use axum::body::Body;
@@ -50,7 +51,22 @@ pub async fn authorization_mw(
         _ => return Err(UserError::NotFound.into()),
     };
 
+    // requests in a workspace read and write its ledger in place of the one of the user
+    let scope = match req.headers().get(WORKSPACE_HEADER) {
+        Some(value) => {
+            let value = value.to_str().unwrap_or_default();
+            let workspace_id = ObjectId::from_str(value)
+                .map_err(|_| WorkspaceError::InvalidWorkspaceId(value.to_string()))?;
+            state
+                .workspace_service
+                .scope(workspace_id, user_id, !req.method().is_safe())
+                .await?
+        }
+        None => Scope::personal(user_id),
+    };
+
     req.extensions_mut().insert(user);
+    req.extensions_mut().insert(scope);
 
     Ok(next.run(req).await)
 }
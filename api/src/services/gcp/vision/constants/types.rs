@@ -25,6 +25,9 @@ pub struct BoundingPoly {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Vertex {
+    // Cloud Vision omits zero coordinates
+    #[serde(default)]
     pub x: i32,
+    #[serde(default)]
     pub y: i32,
 }
@@ -0,0 +1,273 @@
+use std::fmt::Display;
+
+use crate::services::gcp::vision::types::{TextAnnotation, Vertex};
+
+/// A reconstructed receipt row. `right` holds the right-hand column (usually the price)
+/// when the row has one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutLine {
+    pub left: String,
+    pub right: Option<String>,
+}
+
+impl Display for LayoutLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.right {
+            Some(right) if self.left.is_empty() => write!(f, "{}", right),
+            Some(right) => write!(f, "{} ... {}", self.left, right),
+            None => write!(f, "{}", self.left),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Word {
+    text: String,
+    center_y: f64,
+    left: f64,
+    right: f64,
+    height: f64,
+}
+
+// Words closer than this many line heights belong to the same line.
+const LINE_TOLERANCE: f64 = 0.5;
+// Gaps wider than this many line heights split a line into columns.
+const COLUMN_GAP: f64 = 2.0;
+// Skew beyond this is treated as noise, rotated photos are already normalized by the OCR.
+const MAX_SKEW: f64 = std::f64::consts::FRAC_PI_4;
+
+fn distance(a: &Vertex, b: &Vertex) -> f64 {
+    (((b.x - a.x) as f64).powi(2) + ((b.y - a.y) as f64).powi(2)).sqrt()
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    Some(values[values.len() / 2])
+}
+
+/// Estimates the page rotation from the top edge of each word box.
+fn estimate_skew(annotations: &[TextAnnotation]) -> f64 {
+    let angles = annotations
+        .iter()
+        .filter(|annotation| annotation.bounding_poly.vertices.len() == 4)
+        .filter(|annotation| annotation.description.chars().count() > 1)
+        .map(|annotation| {
+            let vertices = &annotation.bounding_poly.vertices;
+            ((vertices[1].y - vertices[0].y) as f64).atan2((vertices[1].x - vertices[0].x) as f64)
+        })
+        .filter(|angle| angle.abs() < MAX_SKEW)
+        .collect::<Vec<_>>();
+
+    median(angles).unwrap_or(0.0)
+}
+
+fn to_word(annotation: &TextAnnotation, skew: f64) -> Option<Word> {
+    let vertices = &annotation.bounding_poly.vertices;
+    if vertices.is_empty() || annotation.description.trim().is_empty() {
+        return None;
+    }
+
+    // Rotate the box back by the page skew so lines become horizontal.
+    let (sin, cos) = skew.sin_cos();
+    let rotated = vertices
+        .iter()
+        .map(|v| {
+            let (x, y) = (v.x as f64, v.y as f64);
+            (x * cos + y * sin, -x * sin + y * cos)
+        })
+        .collect::<Vec<_>>();
+
+    let left = rotated.iter().map(|p| p.0).fold(f64::INFINITY, f64::min);
+    let right = rotated.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max);
+    let top = rotated.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let bottom = rotated.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    let height = if vertices.len() == 4 {
+        distance(&vertices[0], &vertices[3])
+    } else {
+        bottom - top
+    };
+
+    Some(Word {
+        text: annotation.description.trim().to_string(),
+        center_y: (top + bottom) / 2.0,
+        left,
+        right,
+        height,
+    })
+}
+
+fn to_layout_line(mut words: Vec<Word>, line_height: f64) -> LayoutLine {
+    words.sort_by(|a, b| a.left.total_cmp(&b.left));
+
+    let mut segments: Vec<Vec<Word>> = vec![];
+    for word in words {
+        match segments.last_mut() {
+            Some(segment)
+                if word.left - segment.last().unwrap().right <= COLUMN_GAP * line_height =>
+            {
+                segment.push(word)
+            }
+            _ => segments.push(vec![word]),
+        }
+    }
+
+    let mut segments = segments
+        .into_iter()
+        .map(|segment| {
+            segment
+                .into_iter()
+                .map(|word| word.text)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>();
+
+    let right = match segments.last() {
+        Some(last) if segments.len() > 1 && last.chars().any(|c| c.is_ascii_digit()) => {
+            segments.pop()
+        }
+        _ => None,
+    };
+
+    LayoutLine {
+        left: segments.join(" "),
+        right,
+    }
+}
+
+/// Rebuilds receipt rows from word annotations. The page skew is estimated from the word
+/// boxes, words are clustered into lines relative to their height, and every line is split
+/// into columns on wide gaps so prices end up in `right`.
+pub fn build_lines(annotations: &[TextAnnotation]) -> Vec<LayoutLine> {
+    let skew = estimate_skew(annotations);
+    let mut words = annotations
+        .iter()
+        .filter_map(|annotation| to_word(annotation, skew))
+        .collect::<Vec<_>>();
+    let default_height = median(words.iter().map(|word| word.height).collect())
+        .unwrap_or(1.0)
+        .max(1.0);
+
+    words.sort_by(|a, b| a.center_y.total_cmp(&b.center_y));
+
+    let mut lines: Vec<Vec<Word>> = vec![];
+    for word in words {
+        let matched = lines.last_mut().filter(|line| {
+            let center_y = line.iter().map(|w| w.center_y).sum::<f64>() / line.len() as f64;
+            let height = line.iter().map(|w| w.height).fold(word.height, f64::max);
+
+            (word.center_y - center_y).abs() <= LINE_TOLERANCE * height.max(1.0)
+        });
+
+        match matched {
+            Some(line) => line.push(word),
+            None => lines.push(vec![word]),
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|line| {
+            let height = median(line.iter().map(|word| word.height).collect())
+                .unwrap_or(default_height)
+                .max(1.0);
+            to_layout_line(line, height)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::gcp::vision::types::BoundingPoly;
+
+    fn annotation(text: &str, x: i32, y: i32, width: i32, height: i32) -> TextAnnotation {
+        TextAnnotation {
+            description: text.to_string(),
+            bounding_poly: BoundingPoly {
+                vertices: vec![
+                    Vertex { x, y },
+                    Vertex { x: x + width, y },
+                    Vertex {
+                        x: x + width,
+                        y: y + height,
+                    },
+                    Vertex { x, y: y + height },
+                ],
+            },
+        }
+    }
+
+    fn transform(annotations: Vec<TextAnnotation>, angle: f64, scale: f64) -> Vec<TextAnnotation> {
+        let (sin, cos) = angle.sin_cos();
+        annotations
+            .into_iter()
+            .map(|mut annotation| {
+                for v in annotation.bounding_poly.vertices.iter_mut() {
+                    let (x, y) = (v.x as f64 * scale, v.y as f64 * scale);
+                    v.x = (x * cos - y * sin).round() as i32 + 200;
+                    v.y = (x * sin + y * cos).round() as i32 + 200;
+                }
+                annotation
+            })
+            .collect()
+    }
+
+    fn receipt() -> Vec<TextAnnotation> {
+        vec![
+            annotation("Iced", 20, 100, 40, 20),
+            annotation("latte", 66, 101, 50, 20),
+            annotation("45,000", 400, 99, 70, 20),
+            annotation("Croissant", 20, 140, 90, 20),
+            annotation("30,000", 400, 141, 70, 20),
+            annotation("Thank", 150, 200, 60, 20),
+            annotation("you", 215, 200, 40, 20),
+        ]
+    }
+
+    fn render(lines: Vec<LayoutLine>) -> Vec<String> {
+        lines.into_iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_build_lines() {
+        let lines = build_lines(&receipt());
+
+        assert_eq!(
+            lines[0],
+            LayoutLine {
+                left: "Iced latte".to_string(),
+                right: Some("45,000".to_string()),
+            }
+        );
+        assert_eq!(
+            render(lines),
+            vec!["Iced latte ... 45,000", "Croissant ... 30,000", "Thank you"]
+        );
+    }
+
+    #[test]
+    fn test_build_lines_tilted_and_scaled() {
+        let expected = render(build_lines(&receipt()));
+
+        for (angle, scale) in [(0.12, 1.0), (-0.15, 1.0), (0.1, 4.0), (0.0, 6.0)] {
+            let lines = build_lines(&transform(receipt(), angle, scale));
+            assert_eq!(render(lines), expected, "angle {angle}, scale {scale}");
+        }
+    }
+
+    #[test]
+    fn test_build_lines_two_text_columns() {
+        let lines = build_lines(&[
+            annotation("Cashier", 20, 50, 70, 20),
+            annotation("Date", 300, 50, 40, 20),
+            annotation("01/08/2024", 345, 50, 100, 20),
+        ]);
+
+        assert_eq!(render(lines), vec!["Cashier ... Date 01/08/2024"]);
+    }
+}
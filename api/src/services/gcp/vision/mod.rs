@@ -1,5 +1,7 @@
 mod constants;
+mod layout;
 mod vision_service;
 
 pub use constants::*;
+pub use layout::*;
 pub use vision_service::*;
@@ -1,6 +1,7 @@
 use crate::services::gcp::auth::GCPAuthServiceDyn;
+use crate::services::gcp::vision::build_lines;
 use crate::services::gcp::vision::constants::GCPVisionError;
-use crate::services::gcp::vision::types::DetectTextResponse;
+use crate::services::gcp::vision::types::{DetectTextResponse, TextAnnotation};
 use anyhow::anyhow;
 use async_trait::async_trait;
 use serde_json::json;
@@ -9,7 +10,13 @@ use tracing::debug;
 
 #[async_trait]
 pub trait VisionServiceExt {
-    async fn detect_text(&self, url: String) -> Result<String, GCPVisionError>;
+    async fn detect_text(&self, url: String) -> Result<DetectedText, GCPVisionError>;
+}
+
+/// The receipt rows read off an image, with the words and their boxes.
+pub struct DetectedText {
+    pub text: String,
+    pub words: Vec<TextAnnotation>,
 }
 
 pub type VisionServiceDyn = Arc<dyn VisionServiceExt + Send + Sync>;
@@ -21,7 +28,7 @@ pub struct VisionService {
 
 #[async_trait]
 impl VisionServiceExt for VisionService {
-    async fn detect_text(&self, content: String) -> Result<String, GCPVisionError> {
+    async fn detect_text(&self, content: String) -> Result<DetectedText, GCPVisionError> {
         let token = self
             .gcp_auth_service
             .get_access_token()
@@ -53,7 +60,7 @@ impl VisionServiceExt for VisionService {
             .await
             .map_err(|e| GCPVisionError::Unknown(anyhow!("failed to deserialize response: {e}")))?;
 
-        let mut annotations = response
+        let annotations = response
             .responses
             .first()
             .ok_or(GCPVisionError::Unknown(anyhow!("no response")))?
@@ -61,61 +68,25 @@ impl VisionServiceExt for VisionService {
             .clone()
             .unwrap_or_default();
 
-        let mut contents: Vec<Vec<(String, i32, i32)>> = vec![vec![]];
-        let mut current_line_y = 0;
-        let mut current_row = 0;
-
-        // Sort annotations by y
-        annotations.sort_by(|a, b| {
-            a.bounding_poly.vertices[0]
-                .y
-                .cmp(&b.bounding_poly.vertices[0].y)
-        });
-
-        for annotation in annotations {
-            if current_row == 0 {
-                current_row += 1;
-                continue;
-            }
-
-            let vertices = &annotation.bounding_poly.vertices;
-            let text = &annotation.description;
-            let line_y = vertices[0].y;
-            let line_x = vertices[0].x;
-            if current_line_y == 0 {
-                current_line_y = line_y;
-            }
-
-            if line_y <= current_line_y + 10 {
-                contents
-                    .last_mut()
-                    .unwrap()
-                    .push((text.clone(), line_x, line_y));
-            } else {
-                contents.push(vec![(text.clone(), line_x, line_y)]);
-                current_line_y = line_y;
-                current_row += 1;
-            }
-        }
+        Ok(to_detected_text(annotations))
+    }
+}
 
-        // Sort contents by x
-        for content in &mut contents {
-            content.sort_by(|a, b| a.1.cmp(&b.1));
-        }
+/// The first annotation is the full text, the others are single words.
+pub fn to_detected_text(annotations: Vec<TextAnnotation>) -> DetectedText {
+    let words = annotations.get(1..).unwrap_or_default().to_vec();
+    let text = join_annotations(annotations);
+    debug!("description: {text}");
 
-        let description = contents
-            .into_iter()
-            .map(|content| {
-                content
-                    .into_iter()
-                    .map(|line| line.0)
-                    .collect::<Vec<String>>()
-                    .join(" ")
-            })
-            .collect::<Vec<String>>()
-            .join(", ");
-        debug!("description: {description}");
+    DetectedText { text, words }
+}
 
-        Ok(description)
-    }
+/// Joins the word annotations of a response into receipt rows, one per line. The first
+/// annotation is the full text and is skipped.
+pub fn join_annotations(annotations: Vec<TextAnnotation>) -> String {
+    build_lines(annotations.get(1..).unwrap_or_default())
+        .into_iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
 }
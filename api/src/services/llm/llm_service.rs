@@ -1,4 +1,4 @@
-use crate::services::llm::LLMError;
+use crate::services::llm::{ChatImage, ChatMessage, ChatReply, LLMError};
 use async_openai::types::FunctionObject;
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -10,6 +10,17 @@ pub trait LLMServiceExt: Send + Sync {
         prompt: String,
         fn_obj: FunctionObject,
     ) -> Result<(Vec<String>, String), LLMError>;
+    async fn chat_with_fn_and_images(
+        &self,
+        prompt: String,
+        images: Vec<ChatImage>,
+        fn_obj: FunctionObject,
+    ) -> Result<(Vec<String>, String), LLMError>;
+    async fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: Vec<FunctionObject>,
+    ) -> Result<(ChatReply, String), LLMError>;
 }
 
 pub type LLMServiceDyn = Arc<dyn LLMServiceExt + Send + Sync>;
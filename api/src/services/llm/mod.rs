@@ -6,3 +6,4 @@ mod types;
 pub(crate) use constants::*;
 pub use llm_service::*;
 pub use services::*;
+pub use types::chat_types::*;
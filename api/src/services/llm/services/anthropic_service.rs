@@ -1,37 +1,45 @@
 use async_openai::types::FunctionObject;
 use async_trait::async_trait;
-use serde_json::json;
+use serde_json::{json, Value};
 use tracing::debug;
 
 use crate::services::llm::types::anthropic_types::{CompletionContent, CompletionResponse};
-use crate::services::llm::{LLMError, LLMServiceExt};
+use crate::services::llm::{ChatImage, ChatMessage, ChatReply, LLMError, LLMServiceExt, ToolCall};
 
 pub struct AnthropicService {
     pub http_client: reqwest::Client,
     pub api_key: String,
 }
 
-#[async_trait]
-impl LLMServiceExt for AnthropicService {
-    async fn chat_with_fn(
-        &self,
-        prompt: String,
-        fn_obj: FunctionObject,
-    ) -> Result<(Vec<String>, String), LLMError> {
-        debug!("prompt: {prompt}");
-        let completion = self
-            .http_client
+impl AnthropicService {
+    async fn create_message(&self, body: Value) -> Result<String, LLMError> {
+        self.http_client
             .post("https://api.anthropic.com/v1/messages")
             .header("Content-Type", "application/json")
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
-            .json(&json!({
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| LLMError::Unknown(e.into()))?
+            .text()
+            .await
+            .map_err(|e| LLMError::Unknown(e.into()))
+    }
+
+    async fn call_fn(
+        &self,
+        content: Value,
+        fn_obj: FunctionObject,
+    ) -> Result<(Vec<String>, String), LLMError> {
+        let completion = self
+            .create_message(json!({
                 "model": "claude-3-haiku-20240307",
                 "max_tokens": 1800,
                 "messages": [
                     {
                         "role": "user",
-                        "content": prompt
+                        "content": content
                     }
                 ],
                 "tools": [
@@ -42,12 +50,7 @@ impl LLMServiceExt for AnthropicService {
                     }
                 ]
             }))
-            .send()
-            .await
-            .map_err(|e| LLMError::Unknown(e.into()))?
-            .text()
-            .await
-            .map_err(|e| LLMError::Unknown(e.into()))?;
+            .await?;
 
         debug!("completion: {:?}", completion);
         let response = serde_json::from_str::<CompletionResponse>(&completion)
@@ -68,4 +71,189 @@ impl LLMServiceExt for AnthropicService {
 
         Ok((contents, completion))
     }
+
+    /// Images go first as base64 blocks, the prompt follows as the last text block.
+    fn image_content(prompt: String, images: Vec<ChatImage>) -> Value {
+        let mut content = images
+            .into_iter()
+            .map(|image| {
+                json!({
+                    "type": "image",
+                    "source": {
+                        "type": "base64",
+                        "media_type": image.media_type,
+                        "data": image.data
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        content.push(json!({ "type": "text", "text": prompt }));
+
+        Value::Array(content)
+    }
+
+    /// Anthropic keeps the system prompt outside the message list and expects
+    /// tool results as `tool_result` blocks of a single user turn.
+    fn to_request_messages(messages: Vec<ChatMessage>) -> (Option<String>, Vec<Value>) {
+        let mut system = None;
+        let mut request_messages: Vec<Value> = vec![];
+        let mut tool_results: Vec<Value> = vec![];
+
+        for message in messages {
+            if !matches!(message, ChatMessage::Tool { .. }) && !tool_results.is_empty() {
+                request_messages.push(json!({
+                    "role": "user",
+                    "content": std::mem::take(&mut tool_results)
+                }));
+            }
+
+            match message {
+                ChatMessage::System(content) => system = Some(content),
+                ChatMessage::User(content) => request_messages.push(json!({
+                    "role": "user",
+                    "content": content
+                })),
+                ChatMessage::Assistant {
+                    content,
+                    tool_calls,
+                } => {
+                    let mut blocks = vec![];
+                    if let Some(text) = content.filter(|text| !text.is_empty()) {
+                        blocks.push(json!({ "type": "text", "text": text }));
+                    }
+                    for tool_call in tool_calls {
+                        let input = serde_json::from_str::<Value>(&tool_call.arguments)
+                            .unwrap_or_else(|_| json!({}));
+                        blocks.push(json!({
+                            "type": "tool_use",
+                            "id": tool_call.id,
+                            "name": tool_call.name,
+                            "input": input
+                        }));
+                    }
+                    request_messages.push(json!({ "role": "assistant", "content": blocks }));
+                }
+                ChatMessage::Tool {
+                    tool_call_id,
+                    content,
+                } => tool_results.push(json!({
+                    "type": "tool_result",
+                    "tool_use_id": tool_call_id,
+                    "content": content
+                })),
+            }
+        }
+
+        if !tool_results.is_empty() {
+            request_messages.push(json!({ "role": "user", "content": tool_results }));
+        }
+
+        (system, request_messages)
+    }
+}
+
+#[async_trait]
+impl LLMServiceExt for AnthropicService {
+    async fn chat_with_fn(
+        &self,
+        prompt: String,
+        fn_obj: FunctionObject,
+    ) -> Result<(Vec<String>, String), LLMError> {
+        debug!("prompt: {prompt}");
+        self.call_fn(json!(prompt), fn_obj).await
+    }
+
+    async fn chat_with_fn_and_images(
+        &self,
+        prompt: String,
+        images: Vec<ChatImage>,
+        fn_obj: FunctionObject,
+    ) -> Result<(Vec<String>, String), LLMError> {
+        debug!("prompt: {prompt}, images: {}", images.len());
+        self.call_fn(Self::image_content(prompt, images), fn_obj)
+            .await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: Vec<FunctionObject>,
+    ) -> Result<(ChatReply, String), LLMError> {
+        let (system, messages) = Self::to_request_messages(messages);
+        let mut body = json!({
+            "model": "claude-3-haiku-20240307",
+            "max_tokens": 1800,
+            "messages": messages,
+        });
+        if let Some(system) = system {
+            body["system"] = json!(system);
+        }
+        if !tools.is_empty() {
+            body["tools"] = tools
+                .into_iter()
+                .map(|fn_obj| {
+                    json!({
+                        "name": fn_obj.name,
+                        "description": fn_obj.description,
+                        "input_schema": fn_obj.parameters
+                    })
+                })
+                .collect();
+        }
+
+        let completion = self.create_message(body).await?;
+        debug!("completion: {:?}", completion);
+
+        let response = serde_json::from_str::<CompletionResponse>(&completion)
+            .map_err(|e| LLMError::Unknown(e.into()))?;
+        let mut reply = ChatReply::default();
+        for content in response.content {
+            match content {
+                CompletionContent::Text(text_content) => {
+                    let text = reply.content.get_or_insert_with(String::new);
+                    text.push_str(&text_content.text);
+                }
+                CompletionContent::ToolUse(tool_use_content) => reply.tool_calls.push(ToolCall {
+                    id: tool_use_content.id,
+                    name: tool_use_content.name,
+                    arguments: tool_use_content.input.to_string(),
+                }),
+            }
+        }
+
+        Ok((reply, completion))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_content() {
+        let images = vec![
+            ChatImage {
+                media_type: "image/jpeg".to_string(),
+                data: "AAAA".to_string(),
+            },
+            ChatImage {
+                media_type: "image/png".to_string(),
+                data: "BBBB".to_string(),
+            },
+        ];
+
+        let content = AnthropicService::image_content("Read the receipt".to_string(), images);
+
+        let blocks = content.as_array().unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0]["type"], "image");
+        assert_eq!(blocks[0]["source"]["type"], "base64");
+        assert_eq!(blocks[0]["source"]["media_type"], "image/jpeg");
+        assert_eq!(blocks[0]["source"]["data"], "AAAA");
+        assert_eq!(blocks[1]["source"]["media_type"], "image/png");
+        assert_eq!(
+            blocks[2],
+            json!({ "type": "text", "text": "Read the receipt" })
+        );
+    }
 }
@@ -1,18 +1,73 @@
 use async_openai::config::OpenAIConfig;
 use async_openai::types::{
-    ChatCompletionRequestMessage, ChatCompletionRequestUserMessageArgs, ChatCompletionToolArgs,
-    ChatCompletionToolType, CreateChatCompletionRequestArgs, FunctionObject,
+    ChatCompletionMessageToolCall, ChatCompletionRequestAssistantMessageArgs,
+    ChatCompletionRequestMessage, ChatCompletionRequestMessageContentPart,
+    ChatCompletionRequestMessageContentPartImage, ChatCompletionRequestMessageContentPartText,
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestToolMessageArgs,
+    ChatCompletionRequestUserMessageArgs, ChatCompletionRequestUserMessageContent,
+    ChatCompletionToolArgs, ChatCompletionToolType, CreateChatCompletionRequestArgs, FunctionCall,
+    FunctionObject, ImageUrl,
 };
 use async_openai::Client;
 use async_trait::async_trait;
 
-use crate::services::llm::{LLMError, LLMServiceExt};
+use crate::services::llm::{ChatImage, ChatMessage, ChatReply, LLMError, LLMServiceExt, ToolCall};
 
 #[derive(Clone)]
 pub struct OpenAIService {
     pub client: Client<OpenAIConfig>,
 }
 
+impl OpenAIService {
+    fn to_request_message(message: ChatMessage) -> Result<ChatCompletionRequestMessage, LLMError> {
+        let message = match message {
+            ChatMessage::System(content) => ChatCompletionRequestSystemMessageArgs::default()
+                .content(content)
+                .build()?
+                .into(),
+            ChatMessage::User(content) => ChatCompletionRequestUserMessageArgs::default()
+                .content(content)
+                .build()?
+                .into(),
+            ChatMessage::Assistant {
+                content,
+                tool_calls,
+            } => {
+                let mut args = ChatCompletionRequestAssistantMessageArgs::default();
+                if let Some(content) = content {
+                    args.content(content);
+                }
+                if !tool_calls.is_empty() {
+                    args.tool_calls(
+                        tool_calls
+                            .into_iter()
+                            .map(|tool_call| ChatCompletionMessageToolCall {
+                                id: tool_call.id,
+                                r#type: ChatCompletionToolType::Function,
+                                function: FunctionCall {
+                                    name: tool_call.name,
+                                    arguments: tool_call.arguments,
+                                },
+                            })
+                            .collect::<Vec<_>>(),
+                    );
+                }
+                args.build()?.into()
+            }
+            ChatMessage::Tool {
+                tool_call_id,
+                content,
+            } => ChatCompletionRequestToolMessageArgs::default()
+                .tool_call_id(tool_call_id)
+                .content(content)
+                .build()?
+                .into(),
+        };
+
+        Ok(message)
+    }
+}
+
 #[async_trait]
 impl LLMServiceExt for OpenAIService {
     async fn chat_with_fn(
@@ -51,4 +106,118 @@ impl LLMServiceExt for OpenAIService {
 
         Ok((contents, serde_json::to_string(&response).unwrap()))
     }
+
+    async fn chat_with_fn_and_images(
+        &self,
+        prompt: String,
+        images: Vec<ChatImage>,
+        fn_obj: FunctionObject,
+    ) -> Result<(Vec<String>, String), LLMError> {
+        let mut parts = images
+            .into_iter()
+            .map(|image| {
+                ChatCompletionRequestMessageContentPart::ImageUrl(
+                    ChatCompletionRequestMessageContentPartImage {
+                        image_url: ImageUrl {
+                            url: format!("data:{};base64,{}", image.media_type, image.data),
+                            detail: None,
+                        },
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        parts.push(ChatCompletionRequestMessageContentPart::Text(
+            ChatCompletionRequestMessageContentPartText { text: prompt },
+        ));
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .messages(vec![ChatCompletionRequestMessage::User(
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(ChatCompletionRequestUserMessageContent::Array(parts))
+                    .build()?,
+            )])
+            .tools(vec![ChatCompletionToolArgs::default()
+                .r#type(ChatCompletionToolType::Function)
+                .function(fn_obj)
+                .build()?])
+            // gpt-3.5-turbo does not accept image parts
+            .model("gpt-4o-mini".to_string())
+            .max_tokens(1800u32)
+            .temperature(0.2)
+            .n(1)
+            .build()?;
+
+        let response = self.client.chat().create(request).await?;
+        let completion =
+            serde_json::to_string(&response).map_err(|e| LLMError::Unknown(e.into()))?;
+
+        let contents = response
+            .choices
+            .into_iter()
+            .filter_map(|choice| choice.message.tool_calls)
+            .flatten()
+            .map(|tool_call| tool_call.function.arguments)
+            .collect::<Vec<_>>();
+
+        Ok((contents, completion))
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: Vec<ChatMessage>,
+        tools: Vec<FunctionObject>,
+    ) -> Result<(ChatReply, String), LLMError> {
+        let messages = messages
+            .into_iter()
+            .map(Self::to_request_message)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut args = CreateChatCompletionRequestArgs::default();
+        args.messages(messages)
+            .model("gpt-3.5-turbo".to_string())
+            .max_tokens(800u32)
+            .temperature(0.2)
+            .n(1);
+        if !tools.is_empty() {
+            args.tools(
+                tools
+                    .into_iter()
+                    .map(|fn_obj| {
+                        ChatCompletionToolArgs::default()
+                            .r#type(ChatCompletionToolType::Function)
+                            .function(fn_obj)
+                            .build()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+        let request = args.build()?;
+
+        let response = self.client.chat().create(request).await?;
+        let completion =
+            serde_json::to_string(&response).map_err(|e| LLMError::Unknown(e.into()))?;
+
+        let message = response
+            .choices
+            .into_iter()
+            .next()
+            .ok_or(LLMError::EmptyResponse)?
+            .message;
+
+        let reply = ChatReply {
+            content: message.content,
+            tool_calls: message
+                .tool_calls
+                .unwrap_or_default()
+                .into_iter()
+                .map(|tool_call| ToolCall {
+                    id: tool_call.id,
+                    name: tool_call.function.name,
+                    arguments: tool_call.function.arguments,
+                })
+                .collect(),
+        };
+
+        Ok((reply, completion))
+    }
 }
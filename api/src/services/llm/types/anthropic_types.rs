@@ -31,6 +31,8 @@ pub struct CompletionTextContent {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CompletionToolUseContent {
     pub r#type: CompletionType,
+    #[serde(default)]
+    pub id: String,
     pub name: String,
     pub input: serde_json::Value,
 }
@@ -1 +1,2 @@
 pub mod anthropic_types;
+pub mod chat_types;
@@ -3,4 +3,7 @@ pub mod currencyapi;
 pub mod gcp;
 pub mod jwt;
 pub mod llm;
+pub mod pdf;
 pub mod r2;
+pub mod speech;
+pub mod tesseract;
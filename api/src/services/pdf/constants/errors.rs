@@ -0,0 +1,29 @@
+use crate::common::errors::ErrorResponse;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PdfError {
+    #[error("pdf has no pages")]
+    Empty,
+    #[error("pdf has more than {0} pages")]
+    TooManyPages(usize),
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for PdfError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            Self::Empty => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::TooManyPages(_) => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
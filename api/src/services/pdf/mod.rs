@@ -0,0 +1,5 @@
+mod constants;
+mod pdf_service;
+
+pub use constants::*;
+pub use pdf_service::*;
@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::services::pdf::PdfError;
+use crate::settings::PdfConfig;
+
+pub const PDF_CONTENT_TYPE: &str = "application/pdf";
+
+/// A single page of an uploaded pdf. Pages with an embedded text layer are read as text,
+/// scanned pages are rasterized to png so they can go through OCR.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfPage {
+    Text(String),
+    Scanned(Vec<u8>),
+}
+
+#[async_trait]
+pub trait PdfServiceExt: Send + Sync {
+    async fn read_pages(&self, content: Vec<u8>) -> Result<Vec<PdfPage>, PdfError>;
+}
+
+pub type PdfServiceDyn = Arc<dyn PdfServiceExt + Send + Sync>;
+
+/// Reads pdfs with the poppler CLI tools (`pdftotext`, `pdftoppm`).
+pub struct PdfService {
+    pub config: PdfConfig,
+}
+
+// Removes the working directory of a pdf once it is processed.
+struct WorkDir(PathBuf);
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+impl PdfService {
+    // pages with fewer alphanumeric characters are treated as scanned
+    const MIN_TEXT_CHARS: usize = 16;
+
+    async fn run(&self, program: &str, args: &[&str]) -> Result<Vec<u8>, PdfError> {
+        let output = Command::new(Path::new(&self.config.poppler_path).join(program))
+            .args(args)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| PdfError::Unknown(anyhow!("failed to run {program}: {e}")))?;
+
+        if !output.status.success() {
+            return Err(PdfError::Unknown(anyhow!(
+                "{program} exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(output.stdout)
+    }
+
+    async fn rasterize(&self, dir: &Path, file: &str, page: usize) -> Result<Vec<u8>, PdfError> {
+        let prefix = dir.join(format!("page-{page}"));
+        let page = page.to_string();
+        let dpi = self.config.dpi.to_string();
+        let prefix_arg = prefix.to_string_lossy();
+        self.run(
+            "pdftoppm",
+            &[
+                "-f",
+                &page,
+                "-l",
+                &page,
+                "-r",
+                &dpi,
+                "-png",
+                "-singlefile",
+                file,
+                &prefix_arg,
+            ],
+        )
+        .await?;
+
+        tokio::fs::read(prefix.with_extension("png"))
+            .await
+            .map_err(|e| PdfError::Unknown(e.into()))
+    }
+}
+
+/// Splits `pdftotext` output into pages, which are separated by form feeds.
+pub fn split_pages(text: &str) -> Vec<String> {
+    let mut pages = text
+        .split('\u{c}')
+        .map(|page| page.trim().to_string())
+        .collect::<Vec<_>>();
+    // the output ends with a form feed after the last page
+    if text.ends_with('\u{c}') {
+        pages.pop();
+    }
+
+    pages
+}
+
+pub fn is_text_page(text: &str) -> bool {
+    text.chars().filter(|c| c.is_alphanumeric()).count() >= PdfService::MIN_TEXT_CHARS
+}
+
+#[async_trait]
+impl PdfServiceExt for PdfService {
+    async fn read_pages(&self, content: Vec<u8>) -> Result<Vec<PdfPage>, PdfError> {
+        let dir = WorkDir(std::env::temp_dir().join(format!("pdf-{}", Uuid::new_v4())));
+        tokio::fs::create_dir_all(&dir.0)
+            .await
+            .map_err(|e| PdfError::Unknown(e.into()))?;
+        let path = dir.0.join("document.pdf");
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|e| PdfError::Unknown(e.into()))?;
+        let file = path.to_string_lossy().to_string();
+
+        let text = self.run("pdftotext", &["-layout", &file, "-"]).await?;
+        let pages = split_pages(&String::from_utf8_lossy(&text));
+        if pages.is_empty() {
+            return Err(PdfError::Empty);
+        }
+        if pages.len() > self.config.max_pages {
+            return Err(PdfError::TooManyPages(self.config.max_pages));
+        }
+
+        let mut result = vec![];
+        for (index, text) in pages.into_iter().enumerate() {
+            if is_text_page(&text) {
+                result.push(PdfPage::Text(text));
+                continue;
+            }
+
+            // pdftoppm counts pages from 1
+            let image = self.rasterize(&dir.0, &file, index + 1).await?;
+            result.push(PdfPage::Scanned(image));
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_pages() {
+        let text = "Invoice #1001\n  Hosting    12.00\u{c}Total      12.00\n\u{c}\u{c}";
+
+        assert_eq!(
+            split_pages(text),
+            vec!["Invoice #1001\n  Hosting    12.00", "Total      12.00", ""]
+        );
+        assert!(split_pages("").iter().all(|page| page.is_empty()));
+    }
+
+    #[test]
+    fn test_is_text_page() {
+        assert!(is_text_page("Electricity bill, March 2024  Total 45.20"));
+        assert!(!is_text_page(""));
+        assert!(!is_text_page("  - 1 -  \n\n"));
+    }
+}
@@ -6,6 +6,12 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum R2Error {
+    #[error("object not found")]
+    NotFound,
+    #[error("invalid object key")]
+    InvalidKey,
+    #[error("invalid or expired signature")]
+    InvalidSignature,
     #[error(transparent)]
     Unknown(#[from] anyhow::Error),
 }
@@ -13,6 +19,9 @@ pub enum R2Error {
 impl IntoResponse for R2Error {
     fn into_response(self) -> axum::response::Response {
         let (status, message) = match self {
+            Self::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            Self::InvalidKey => (StatusCode::BAD_REQUEST, self.to_string()),
+            Self::InvalidSignature => (StatusCode::FORBIDDEN, self.to_string()),
             Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
         };
 
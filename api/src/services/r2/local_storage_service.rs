@@ -0,0 +1,240 @@
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::services::r2::{R2Error, R2ServiceExt, StoredObject};
+use crate::settings::LocalStorageConfig;
+
+/// Objects stored on disk under `{root}/{bucket}`. The presigned urls point at the storage
+/// routes of the API, which check the signature before reading or writing the file.
+pub struct LocalStorageService {
+    pub config: LocalStorageConfig,
+    pub bucket: String,
+}
+
+impl LocalStorageService {
+    const DEFAULT_EXPIRED_SEC: i64 = 3600;
+
+    fn file_path(&self, key: &str) -> Result<PathBuf, R2Error> {
+        check_key(key)?;
+
+        Ok(Path::new(&self.config.root).join(&self.bucket).join(key))
+    }
+
+    fn presign(&self, method: &str, key: &str) -> Result<String, R2Error> {
+        check_key(key)?;
+        let expires = chrono::Utc::now().timestamp() + Self::DEFAULT_EXPIRED_SEC;
+        let signature = sign(&self.config.secret, method, key, expires)?;
+
+        Ok(format!(
+            "{}/{}?expires={}&signature={}",
+            self.config.base_url.trim_end_matches('/'),
+            key,
+            expires,
+            signature
+        ))
+    }
+}
+
+/// Keys are relative paths, they must not leave the bucket folder.
+pub fn check_key(key: &str) -> Result<(), R2Error> {
+    let is_valid = !key.is_empty()
+        && Path::new(key)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(R2Error::InvalidKey)
+    }
+}
+
+/// HMAC-SHA256 of the method, key and expiry of a presigned url, in url-safe base64. An
+/// empty secret would let anyone sign urls, nothing is signed with it.
+pub fn sign(secret: &str, method: &str, key: &str, expires: i64) -> Result<String, R2Error> {
+    if secret.is_empty() {
+        return Err(R2Error::Unknown(anyhow!("storage.local.secret is not set")));
+    }
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key size");
+    mac.update(format!("{method}\n{key}\n{expires}").as_bytes());
+
+    Ok(BASE64_URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+}
+
+pub fn verify_signature(
+    secret: &str,
+    method: &str,
+    key: &str,
+    expires: i64,
+    signature: &str,
+) -> Result<(), R2Error> {
+    if secret.is_empty() || expires < chrono::Utc::now().timestamp() {
+        return Err(R2Error::InvalidSignature);
+    }
+    let signature = BASE64_URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| R2Error::InvalidSignature)?;
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("hmac accepts any key size");
+    mac.update(format!("{method}\n{key}\n{expires}").as_bytes());
+    mac.verify_slice(&signature)
+        .map_err(|_| R2Error::InvalidSignature)
+}
+
+#[async_trait]
+impl R2ServiceExt for LocalStorageService {
+    async fn upload_object(
+        &self,
+        bucket: String,
+        path: String,
+        content: &[u8],
+        _content_type: &str,
+    ) -> Result<String, R2Error> {
+        let file_path = self.file_path(&path)?;
+        if let Some(parent) = file_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| R2Error::Unknown(anyhow!(e)))?;
+        }
+        tokio::fs::write(&file_path, content)
+            .await
+            .map_err(|e| R2Error::Unknown(anyhow!(e)))?;
+
+        Ok(format!("{}/{}", bucket, path))
+    }
+
+    async fn get_object(&self, path: String) -> Result<Vec<u8>, R2Error> {
+        tokio::fs::read(self.file_path(&path)?)
+            .await
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => R2Error::NotFound,
+                _ => R2Error::Unknown(anyhow!(e)),
+            })
+    }
+
+    async fn object_size(&self, path: String) -> Result<u64, R2Error> {
+        tokio::fs::metadata(self.file_path(&path)?)
+            .await
+            .map(|metadata| metadata.len())
+            .map_err(|e| match e.kind() {
+                ErrorKind::NotFound => R2Error::NotFound,
+                _ => R2Error::Unknown(anyhow!(e)),
+            })
+    }
+
+    async fn presign_get(&self, path: String) -> Result<String, R2Error> {
+        self.presign("GET", &path)
+    }
+
+    async fn presign_post(&self, path: String) -> Result<String, R2Error> {
+        self.presign("PUT", &path)
+    }
+
+    async fn delete_object(&self, path: String) -> Result<(), R2Error> {
+        match tokio::fs::remove_file(self.file_path(&path)?).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(R2Error::Unknown(anyhow!(e))),
+        }
+    }
+
+    async fn list_objects(&self, prefix: String) -> Result<Vec<StoredObject>, R2Error> {
+        let root = Path::new(&self.config.root).join(&self.bucket);
+
+        let mut objects = vec![];
+        let mut folders = vec![root.clone()];
+        while let Some(folder) = folders.pop() {
+            let mut entries = match tokio::fs::read_dir(&folder).await {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(R2Error::Unknown(anyhow!(e))),
+            };
+            while let Some(entry) = entries
+                .next_entry()
+                .await
+                .map_err(|e| R2Error::Unknown(anyhow!(e)))?
+            {
+                let metadata = entry
+                    .metadata()
+                    .await
+                    .map_err(|e| R2Error::Unknown(anyhow!(e)))?;
+                if metadata.is_dir() {
+                    folders.push(entry.path());
+                    continue;
+                }
+
+                let Some(key) = entry
+                    .path()
+                    .strip_prefix(&root)
+                    .ok()
+                    .and_then(|key| key.to_str())
+                    .map(|key| key.replace(std::path::MAIN_SEPARATOR, "/"))
+                else {
+                    continue;
+                };
+                if !key.starts_with(&prefix) {
+                    continue;
+                }
+                let last_modified = metadata
+                    .modified()
+                    .map_err(|e| R2Error::Unknown(anyhow!(e)))?;
+                objects.push(StoredObject {
+                    key,
+                    last_modified: last_modified.into(),
+                });
+            }
+        }
+
+        Ok(objects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_key() {
+        assert!(check_key("66990b1947d76ec3781adc9d/ae7441fd/0.jpg").is_ok());
+        assert!(check_key("").is_err());
+        assert!(check_key("../secrets.env").is_err());
+        assert!(check_key("66990b1947d76ec3781adc9d/../../0.jpg").is_err());
+        assert!(check_key("/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let expires = chrono::Utc::now().timestamp() + 60;
+        let signature = sign("secret", "GET", "a/0.jpg", expires).unwrap();
+
+        assert!(verify_signature("secret", "GET", "a/0.jpg", expires, &signature).is_ok());
+        assert!(verify_signature("secret", "PUT", "a/0.jpg", expires, &signature).is_err());
+        assert!(verify_signature("secret", "GET", "a/1.jpg", expires, &signature).is_err());
+        assert!(verify_signature("other", "GET", "a/0.jpg", expires, &signature).is_err());
+
+        let expired = chrono::Utc::now().timestamp() - 1;
+        let signature = sign("secret", "GET", "a/0.jpg", expired).unwrap();
+        assert!(verify_signature("secret", "GET", "a/0.jpg", expired, &signature).is_err());
+    }
+
+    #[test]
+    fn test_empty_secret() {
+        let expires = chrono::Utc::now().timestamp() + 60;
+        assert!(sign("", "GET", "a/0.jpg", expires).is_err());
+
+        // an empty key is a valid hmac key, a forged url must still be refused
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"").unwrap();
+        mac.update(format!("GET\na/0.jpg\n{expires}").as_bytes());
+        let forged = BASE64_URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+        assert!(verify_signature("", "GET", "a/0.jpg", expires, &forged).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::services::r2::{R2Error, R2ServiceExt, StoredObject};
+
+/// Objects kept in memory, lost on restart. For tests and trying the API without storage.
+#[derive(Default)]
+pub struct MemoryStorageService {
+    pub objects: Mutex<HashMap<String, MemoryObject>>,
+}
+
+pub struct MemoryObject {
+    pub content: Vec<u8>,
+    pub last_modified: chrono::DateTime<chrono::Utc>,
+}
+
+#[async_trait]
+impl R2ServiceExt for MemoryStorageService {
+    async fn upload_object(
+        &self,
+        bucket: String,
+        path: String,
+        content: &[u8],
+        _content_type: &str,
+    ) -> Result<String, R2Error> {
+        self.objects.lock().unwrap().insert(
+            path.clone(),
+            MemoryObject {
+                content: content.to_vec(),
+                last_modified: chrono::Utc::now(),
+            },
+        );
+
+        Ok(format!("{}/{}", bucket, path))
+    }
+
+    async fn get_object(&self, path: String) -> Result<Vec<u8>, R2Error> {
+        self.objects
+            .lock()
+            .unwrap()
+            .get(&path)
+            .map(|object| object.content.clone())
+            .ok_or(R2Error::NotFound)
+    }
+
+    async fn object_size(&self, path: String) -> Result<u64, R2Error> {
+        self.objects
+            .lock()
+            .unwrap()
+            .get(&path)
+            .map(|object| object.content.len() as u64)
+            .ok_or(R2Error::NotFound)
+    }
+
+    async fn presign_get(&self, path: String) -> Result<String, R2Error> {
+        Ok(format!("memory://{}", path))
+    }
+
+    async fn presign_post(&self, path: String) -> Result<String, R2Error> {
+        Ok(format!("memory://{}", path))
+    }
+
+    async fn delete_object(&self, path: String) -> Result<(), R2Error> {
+        self.objects.lock().unwrap().remove(&path);
+
+        Ok(())
+    }
+
+    async fn list_objects(&self, prefix: String) -> Result<Vec<StoredObject>, R2Error> {
+        Ok(self
+            .objects
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, object)| StoredObject {
+                key: key.clone(),
+                last_modified: object.last_modified,
+            })
+            .collect())
+    }
+}
@@ -1,5 +1,9 @@
 mod constants;
+mod local_storage_service;
+mod memory_storage_service;
 mod r2_service;
 
 pub use constants::*;
+pub use local_storage_service::*;
+pub use memory_storage_service::*;
 pub use r2_service::*;
@@ -3,10 +3,11 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use async_trait::async_trait;
 use s3::creds::Credentials;
+use s3::error::S3Error;
 use s3::{Bucket, Region};
 
 use crate::services::r2::constants::R2Error;
-use crate::settings::R2Config;
+use crate::settings::{R2Config, S3Config};
 
 #[async_trait]
 pub trait R2ServiceExt: Send + Sync {
@@ -17,33 +18,61 @@ pub trait R2ServiceExt: Send + Sync {
         content: &[u8],
         content_type: &str,
     ) -> Result<String, R2Error>;
+    async fn get_object(&self, path: String) -> Result<Vec<u8>, R2Error>;
+    /// Size in bytes, read without downloading the object.
+    async fn object_size(&self, path: String) -> Result<u64, R2Error>;
     async fn presign_get(&self, path: String) -> Result<String, R2Error>;
     async fn presign_post(&self, path: String) -> Result<String, R2Error>;
+    /// Deleting an object that does not exist succeeds.
+    async fn delete_object(&self, path: String) -> Result<(), R2Error>;
+    async fn list_objects(&self, prefix: String) -> Result<Vec<StoredObject>, R2Error>;
+}
+
+/// An object of the bucket, as listed.
+#[derive(Debug, Clone)]
+pub struct StoredObject {
+    pub key: String,
+    pub last_modified: chrono::DateTime<chrono::Utc>,
 }
 
 pub type R2ServiceDyn = Arc<dyn R2ServiceExt + Send + Sync>;
 
+/// Cloudflare R2, or any other S3-compatible service.
 pub struct R2Service {
     pub bucket: Bucket,
 }
 
 impl R2Service {
-    pub fn new(r2_config: R2Config) -> Self {
+    pub fn new(r2_config: R2Config, bucket: &str) -> Self {
         let region = Region::R2 {
             account_id: r2_config.account_id,
         };
-        let credentials = Credentials::new(
-            Some(r2_config.access_key.as_str()),
-            Some(r2_config.secret_key.as_str()),
-            None,
-            None,
-            None,
-        )
-        .expect("failed to create credentials");
-        let bucket = Bucket::new("invoices", region, credentials).expect("failed to create bucket");
+        let credentials = Self::credentials(&r2_config.access_key, &r2_config.secret_key);
+        let bucket = Bucket::new(bucket, region, credentials).expect("failed to create bucket");
+
+        Self { bucket }
+    }
+
+    pub fn s3(s3_config: S3Config, bucket: &str) -> Self {
+        let region = Region::Custom {
+            region: s3_config.region,
+            endpoint: s3_config.endpoint,
+        };
+        let credentials = Self::credentials(&s3_config.access_key, &s3_config.secret_key);
+        let bucket = Bucket::new(bucket, region, credentials).expect("failed to create bucket");
+        let bucket = if s3_config.path_style {
+            bucket.with_path_style()
+        } else {
+            bucket
+        };
 
         Self { bucket }
     }
+
+    fn credentials(access_key: &str, secret_key: &str) -> Credentials {
+        Credentials::new(Some(access_key), Some(secret_key), None, None, None)
+            .expect("failed to create credentials")
+    }
 }
 
 impl R2Service {
@@ -68,6 +97,31 @@ impl R2ServiceExt for R2Service {
         Ok(path)
     }
 
+    async fn get_object(&self, path: String) -> Result<Vec<u8>, R2Error> {
+        self.bucket
+            .get_object(path)
+            .await
+            .map(|response| response.to_vec())
+            .map_err(|e| match e {
+                S3Error::HttpFailWithBody(404, _) => R2Error::NotFound,
+                e => R2Error::Unknown(anyhow!(e)),
+            })
+    }
+
+    async fn object_size(&self, path: String) -> Result<u64, R2Error> {
+        let (head, status) = self.bucket.head_object(path).await.map_err(|e| match e {
+            S3Error::HttpFailWithBody(404, _) => R2Error::NotFound,
+            e => R2Error::Unknown(anyhow!(e)),
+        })?;
+        if status == 404 {
+            return Err(R2Error::NotFound);
+        }
+
+        head.content_length
+            .and_then(|length| u64::try_from(length).ok())
+            .ok_or(R2Error::Unknown(anyhow!("no content length")))
+    }
+
     async fn presign_get(&self, path: String) -> Result<String, R2Error> {
         self.bucket
             .presign_get(path, Self::DEFAULT_EXPIRED_SEC, None)
@@ -81,4 +135,32 @@ impl R2ServiceExt for R2Service {
             .await
             .map_err(|e| R2Error::Unknown(anyhow!(e)))
     }
+
+    async fn delete_object(&self, path: String) -> Result<(), R2Error> {
+        match self.bucket.delete_object(path).await {
+            Ok(_) | Err(S3Error::HttpFailWithBody(404, _)) => Ok(()),
+            Err(e) => Err(R2Error::Unknown(anyhow!(e))),
+        }
+    }
+
+    async fn list_objects(&self, prefix: String) -> Result<Vec<StoredObject>, R2Error> {
+        let pages = self
+            .bucket
+            .list(prefix, None)
+            .await
+            .map_err(|e| R2Error::Unknown(anyhow!(e)))?;
+
+        pages
+            .into_iter()
+            .flat_map(|page| page.contents)
+            .map(|object| {
+                let last_modified = chrono::DateTime::parse_from_rfc3339(&object.last_modified)
+                    .map_err(|e| R2Error::Unknown(anyhow!(e)))?;
+                Ok(StoredObject {
+                    key: object.key,
+                    last_modified: last_modified.to_utc(),
+                })
+            })
+            .collect()
+    }
 }
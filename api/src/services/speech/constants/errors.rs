@@ -0,0 +1,26 @@
+use crate::common::errors::ErrorResponse;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SpeechError {
+    #[error("no speech found in the audio")]
+    Empty,
+    #[error(transparent)]
+    Unknown(#[from] anyhow::Error),
+}
+
+impl IntoResponse for SpeechError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, message) = match self {
+            Self::Empty => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            Self::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        };
+
+        let error_response = ErrorResponse { message };
+
+        (status, Json(error_response)).into_response()
+    }
+}
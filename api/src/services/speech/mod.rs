@@ -0,0 +1,9 @@
+mod constants;
+mod speech_service;
+mod stub_speech_service;
+mod whisper_service;
+
+pub use constants::*;
+pub use speech_service::*;
+pub use stub_speech_service::*;
+pub use whisper_service::*;
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::services::speech::SpeechError;
+
+#[async_trait]
+pub trait SpeechToTextServiceExt: Send + Sync {
+    /// Transcribes a voice note. `language` is a hint such as `en` or `vi`.
+    async fn transcribe(
+        &self,
+        content: Vec<u8>,
+        content_type: &str,
+        language: Option<&str>,
+    ) -> Result<String, SpeechError>;
+}
+
+pub type SpeechToTextServiceDyn = Arc<dyn SpeechToTextServiceExt + Send + Sync>;
+
+/// Joins the transcript into one line and drops the markers whisper writes for silence
+/// and noise, e.g. `[BLANK_AUDIO]` or `(music)`.
+pub fn clean_transcript(text: &str) -> String {
+    let mut cleaned = String::new();
+    let mut depth = 0;
+    for c in text.chars() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' if depth > 0 => depth -= 1,
+            _ if depth == 0 => cleaned.push(c),
+            _ => {}
+        }
+    }
+
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_transcript() {
+        assert_eq!(
+            clean_transcript(" Lunch 80 thousand\n with Nam.\n[BLANK_AUDIO]\n"),
+            "Lunch 80 thousand with Nam."
+        );
+        assert_eq!(clean_transcript("(wind blowing) [MUSIC]"), "");
+    }
+}
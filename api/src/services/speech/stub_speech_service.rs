@@ -0,0 +1,24 @@
+use async_trait::async_trait;
+
+use crate::services::speech::{clean_transcript, SpeechError, SpeechToTextServiceExt};
+
+/// Reads the uploaded "audio" as the transcript itself, so voice notes can be tried
+/// without a speech model, e.g. with a text file sent as `audio/wav`.
+pub struct StubSpeechToTextService;
+
+#[async_trait]
+impl SpeechToTextServiceExt for StubSpeechToTextService {
+    async fn transcribe(
+        &self,
+        content: Vec<u8>,
+        _content_type: &str,
+        _language: Option<&str>,
+    ) -> Result<String, SpeechError> {
+        let transcript = clean_transcript(&String::from_utf8_lossy(&content));
+        if transcript.is_empty() {
+            return Err(SpeechError::Empty);
+        }
+
+        Ok(transcript)
+    }
+}
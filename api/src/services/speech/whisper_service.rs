@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use tokio::process::Command;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::services::speech::{clean_transcript, SpeechError, SpeechToTextServiceExt};
+use crate::settings::WhisperConfig;
+
+/// Runs a whisper.cpp style CLI locally. Voice notes are converted to the 16kHz mono wav
+/// whisper expects with `ffmpeg` first.
+pub struct WhisperService {
+    pub config: WhisperConfig,
+}
+
+// Removes the working directory of a voice note once it is transcribed.
+struct WorkDir(PathBuf);
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+impl WhisperService {
+    const AUTO_LANGUAGE: &'static str = "auto";
+
+    async fn run(&self, program: &str, args: &[&str]) -> Result<(), SpeechError> {
+        let output = Command::new(program)
+            .args(args)
+            .kill_on_drop(true)
+            .output()
+            .await
+            .map_err(|e| SpeechError::Unknown(anyhow!("failed to run {program}: {e}")))?;
+
+        if !output.status.success() {
+            return Err(SpeechError::Unknown(anyhow!(
+                "{program} exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        Ok(())
+    }
+
+    async fn convert(&self, input: &Path, wav: &Path) -> Result<(), SpeechError> {
+        let max_duration = self.config.max_duration_secs.to_string();
+        self.run(
+            &self.config.ffmpeg_path,
+            &[
+                "-nostdin",
+                "-y",
+                "-i",
+                &input.to_string_lossy(),
+                "-t",
+                &max_duration,
+                "-ar",
+                "16000",
+                "-ac",
+                "1",
+                "-c:a",
+                "pcm_s16le",
+                &wav.to_string_lossy(),
+            ],
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl SpeechToTextServiceExt for WhisperService {
+    async fn transcribe(
+        &self,
+        content: Vec<u8>,
+        _content_type: &str,
+        language: Option<&str>,
+    ) -> Result<String, SpeechError> {
+        let dir = WorkDir(std::env::temp_dir().join(format!("speech-{}", Uuid::new_v4())));
+        tokio::fs::create_dir_all(&dir.0)
+            .await
+            .map_err(|e| SpeechError::Unknown(e.into()))?;
+
+        // ffmpeg detects the format from the content, the extension is not needed
+        let input = dir.0.join("input");
+        tokio::fs::write(&input, content)
+            .await
+            .map_err(|e| SpeechError::Unknown(e.into()))?;
+        let wav = dir.0.join("audio.wav");
+        self.convert(&input, &wav).await?;
+
+        // whisper appends `.txt` to the output prefix
+        let output = dir.0.join("transcript");
+        let threads = self.config.threads.to_string();
+        self.run(
+            &self.config.path,
+            &[
+                "-m",
+                &self.config.model,
+                "-f",
+                &wav.to_string_lossy(),
+                "-l",
+                language.unwrap_or(Self::AUTO_LANGUAGE),
+                "-t",
+                &threads,
+                "-nt",
+                "-np",
+                "-otxt",
+                "-of",
+                &output.to_string_lossy(),
+            ],
+        )
+        .await?;
+
+        let text = tokio::fs::read_to_string(output.with_extension("txt"))
+            .await
+            .map_err(|e| SpeechError::Unknown(e.into()))?;
+        let transcript = clean_transcript(&text);
+        debug!("transcript: {transcript}");
+
+        if transcript.is_empty() {
+            return Err(SpeechError::Empty);
+        }
+
+        Ok(transcript)
+    }
+}
@@ -0,0 +1,3 @@
+mod tesseract_service;
+
+pub use tesseract_service::*;
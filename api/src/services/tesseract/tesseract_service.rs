@@ -0,0 +1,212 @@
+use std::process::Stdio;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::services::gcp::vision::types::{
+    BoundingPoly, DetectTextResponse, TextAnnotation, TextResponse, Vertex,
+};
+use crate::services::gcp::vision::{
+    to_detected_text, DetectedText, GCPVisionError, VisionServiceExt,
+};
+use crate::settings::TesseractConfig;
+
+/// Runs the `tesseract` CLI locally, for deployments without Google Cloud credentials.
+pub struct TesseractService {
+    pub config: TesseractConfig,
+}
+
+impl TesseractService {
+    // level of a single word in tesseract's tsv output
+    const WORD_LEVEL: &'static str = "5";
+
+    async fn run(&self, image: Vec<u8>) -> Result<String, GCPVisionError> {
+        let mut child = Command::new(&self.config.path)
+            .args(["stdin", "stdout", "-l", &self.config.languages, "tsv"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| GCPVisionError::Unknown(anyhow!("failed to run tesseract: {e}")))?;
+
+        let mut stdin = child.stdin.take().ok_or(GCPVisionError::Unknown(anyhow!(
+            "tesseract stdin unavailable"
+        )))?;
+        let writer = tokio::spawn(async move { stdin.write_all(&image).await });
+
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| GCPVisionError::Unknown(e.into()))?;
+        writer
+            .await
+            .map_err(|e| GCPVisionError::Unknown(e.into()))?
+            .map_err(|e| GCPVisionError::Unknown(e.into()))?;
+
+        if !output.status.success() {
+            return Err(GCPVisionError::Unknown(anyhow!(
+                "tesseract exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        String::from_utf8(output.stdout).map_err(|e| GCPVisionError::Unknown(e.into()))
+    }
+}
+
+/// Converts tesseract's tsv output into the Cloud Vision response layout. Like Cloud Vision,
+/// the first annotation holds the full text and the rest are single words.
+pub fn parse_tsv(tsv: &str) -> DetectTextResponse {
+    let mut words = vec![];
+    for line in tsv.lines().skip(1) {
+        let columns = line.split('\t').collect::<Vec<_>>();
+        if columns.len() < 12 || columns[0] != TesseractService::WORD_LEVEL {
+            continue;
+        }
+
+        let text = columns[11].trim();
+        let confidence = columns[10].parse::<f32>().unwrap_or(-1.0);
+        if text.is_empty() || confidence < 0.0 {
+            continue;
+        }
+
+        let [left, top, width, height] =
+            [columns[6], columns[7], columns[8], columns[9]].map(|v| v.parse::<i32>().unwrap_or(0));
+        words.push(TextAnnotation {
+            description: text.to_string(),
+            bounding_poly: BoundingPoly {
+                vertices: vec![
+                    Vertex { x: left, y: top },
+                    Vertex {
+                        x: left + width,
+                        y: top,
+                    },
+                    Vertex {
+                        x: left + width,
+                        y: top + height,
+                    },
+                    Vertex {
+                        x: left,
+                        y: top + height,
+                    },
+                ],
+            },
+        });
+    }
+
+    if words.is_empty() {
+        return DetectTextResponse {
+            responses: vec![TextResponse {
+                text_annotations: None,
+            }],
+        };
+    }
+
+    let vertices = |pick: fn(&Vertex) -> i32, index: usize| {
+        words
+            .iter()
+            .map(|word| pick(&word.bounding_poly.vertices[index]))
+            .collect::<Vec<_>>()
+    };
+    let (min_x, min_y) = (
+        vertices(|v| v.x, 0).into_iter().min().unwrap_or(0),
+        vertices(|v| v.y, 0).into_iter().min().unwrap_or(0),
+    );
+    let (max_x, max_y) = (
+        vertices(|v| v.x, 2).into_iter().max().unwrap_or(0),
+        vertices(|v| v.y, 2).into_iter().max().unwrap_or(0),
+    );
+    let full_text = TextAnnotation {
+        description: words
+            .iter()
+            .map(|word| word.description.as_str())
+            .collect::<Vec<_>>()
+            .join(" "),
+        bounding_poly: BoundingPoly {
+            vertices: vec![
+                Vertex { x: min_x, y: min_y },
+                Vertex { x: max_x, y: min_y },
+                Vertex { x: max_x, y: max_y },
+                Vertex { x: min_x, y: max_y },
+            ],
+        },
+    };
+
+    let mut annotations = vec![full_text];
+    annotations.extend(words);
+
+    DetectTextResponse {
+        responses: vec![TextResponse {
+            text_annotations: Some(annotations),
+        }],
+    }
+}
+
+#[async_trait]
+impl VisionServiceExt for TesseractService {
+    async fn detect_text(&self, content: String) -> Result<DetectedText, GCPVisionError> {
+        let image = BASE64_STANDARD
+            .decode(content)
+            .map_err(|e| GCPVisionError::Unknown(e.into()))?;
+
+        let tsv = self.run(image).await?;
+        let response = parse_tsv(&tsv);
+
+        let annotations = response
+            .responses
+            .into_iter()
+            .next()
+            .and_then(|response| response.text_annotations)
+            .unwrap_or_default();
+
+        Ok(to_detected_text(annotations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::gcp::vision::join_annotations;
+
+    use super::*;
+
+    const TSV: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t
+4\t1\t1\t1\t1\t0\t40\t50\t300\t20\t-1\t
+5\t1\t1\t1\t1\t1\t40\t50\t80\t20\t95.1\tCoffee
+5\t1\t1\t1\t1\t2\t300\t52\t40\t18\t91.3\t5.00
+5\t1\t1\t1\t2\t1\t40\t90\t60\t20\t90.0\tTotal
+5\t1\t1\t1\t2\t2\t300\t91\t40\t20\t-1\t
+5\t1\t1\t1\t2\t3\t300\t91\t40\t20\t88.0\t5.00
+";
+
+    #[test]
+    fn test_parse_tsv() {
+        let response = parse_tsv(TSV);
+        let annotations = response.responses[0].text_annotations.clone().unwrap();
+
+        assert_eq!(annotations.len(), 5);
+        assert_eq!(annotations[0].description, "Coffee 5.00 Total 5.00");
+        assert_eq!(annotations[0].bounding_poly.vertices[0].y, 50);
+        assert_eq!(annotations[0].bounding_poly.vertices[2].x, 340);
+        assert_eq!(annotations[2].bounding_poly.vertices[0].x, 300);
+        assert_eq!(annotations[2].bounding_poly.vertices[2].y, 70);
+
+        assert_eq!(
+            join_annotations(annotations),
+            "Coffee ... 5.00\nTotal ... 5.00"
+        );
+    }
+
+    #[test]
+    fn test_parse_tsv_without_words() {
+        let response = parse_tsv("level\tpage_num\n");
+
+        assert!(response.responses[0].text_annotations.is_none());
+    }
+}
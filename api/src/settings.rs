@@ -94,16 +94,386 @@ pub struct R2Config {
     pub secret_key: String,
 }
 
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageProvider {
+    #[default]
+    R2,
+    S3,
+    Local,
+    Memory,
+}
+
+/// Any S3-compatible service, e.g. MinIO.
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct S3Config {
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default = "S3Config::default_region")]
+    pub region: String,
+    #[serde(default)]
+    pub access_key: String,
+    #[serde(default)]
+    pub secret_key: String,
+    // MinIO serves buckets under the path instead of a subdomain
+    #[serde(default = "S3Config::default_path_style")]
+    pub path_style: bool,
+}
+
+impl S3Config {
+    fn default_region() -> String {
+        "us-east-1".to_string()
+    }
+
+    fn default_path_style() -> bool {
+        true
+    }
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            region: Self::default_region(),
+            access_key: String::new(),
+            secret_key: String::new(),
+            path_style: Self::default_path_style(),
+        }
+    }
+}
+
+/// Files kept on disk, served by the API itself with signed urls.
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct LocalStorageConfig {
+    #[serde(default = "LocalStorageConfig::default_root")]
+    pub root: String,
+    // public url of the storage routes, signed urls are built on it
+    #[serde(default = "LocalStorageConfig::default_base_url")]
+    pub base_url: String,
+    #[serde(default)]
+    pub secret: String,
+}
+
+impl LocalStorageConfig {
+    fn default_root() -> String {
+        "storage".to_string()
+    }
+
+    fn default_base_url() -> String {
+        "http://localhost:3000/api/v1/storage".to_string()
+    }
+}
+
+impl Default for LocalStorageConfig {
+    fn default() -> Self {
+        Self {
+            root: Self::default_root(),
+            base_url: Self::default_base_url(),
+            secret: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct StorageConfig {
+    #[serde(default)]
+    pub provider: StorageProvider,
+    #[serde(default)]
+    pub s3: S3Config,
+    #[serde(default)]
+    pub local: LocalStorageConfig,
+    // 0 turns the orphan sweeper off
+    #[serde(default = "StorageConfig::default_sweep_interval_hours")]
+    pub sweep_interval_hours: u64,
+    // younger objects may still wait for their invoice, e.g. in a queued job
+    #[serde(default = "StorageConfig::default_orphan_min_age_days")]
+    pub orphan_min_age_days: i64,
+}
+
+impl StorageConfig {
+    fn default_sweep_interval_hours() -> u64 {
+        24
+    }
+
+    fn default_orphan_min_age_days() -> i64 {
+        7
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            provider: StorageProvider::default(),
+            s3: S3Config::default(),
+            local: LocalStorageConfig::default(),
+            sweep_interval_hours: Self::default_sweep_interval_hours(),
+            orphan_min_age_days: Self::default_orphan_min_age_days(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, Default)]
 #[allow(unused)]
 pub struct GCPConfig {
     pub service_account: String,
 }
 
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrProvider {
+    #[default]
+    Gcp,
+    Tesseract,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct TesseractConfig {
+    #[serde(default = "TesseractConfig::default_path")]
+    pub path: String,
+    // e.g. "eng+vie+jpn"
+    #[serde(default = "TesseractConfig::default_languages")]
+    pub languages: String,
+}
+
+impl TesseractConfig {
+    fn default_path() -> String {
+        "tesseract".to_string()
+    }
+
+    fn default_languages() -> String {
+        "eng".to_string()
+    }
+}
+
+impl Default for TesseractConfig {
+    fn default() -> Self {
+        Self {
+            path: Self::default_path(),
+            languages: Self::default_languages(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[allow(unused)]
+pub struct OcrConfig {
+    #[serde(default)]
+    pub provider: OcrProvider,
+    #[serde(default)]
+    pub tesseract: TesseractConfig,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeechProvider {
+    #[default]
+    Whisper,
+    // reads the upload as the transcript, for local development and tests
+    Stub,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct WhisperConfig {
+    // whisper.cpp cli, e.g. `whisper-cli` or the older `main`
+    #[serde(default = "WhisperConfig::default_path")]
+    pub path: String,
+    #[serde(default = "WhisperConfig::default_model")]
+    pub model: String,
+    #[serde(default = "WhisperConfig::default_ffmpeg_path")]
+    pub ffmpeg_path: String,
+    #[serde(default = "WhisperConfig::default_threads")]
+    pub threads: usize,
+    #[serde(default = "WhisperConfig::default_max_duration_secs")]
+    pub max_duration_secs: u32,
+}
+
+impl WhisperConfig {
+    fn default_path() -> String {
+        "whisper-cli".to_string()
+    }
+
+    fn default_model() -> String {
+        "models/ggml-base.bin".to_string()
+    }
+
+    fn default_ffmpeg_path() -> String {
+        "ffmpeg".to_string()
+    }
+
+    fn default_threads() -> usize {
+        4
+    }
+
+    fn default_max_duration_secs() -> u32 {
+        60
+    }
+}
+
+impl Default for WhisperConfig {
+    fn default() -> Self {
+        Self {
+            path: Self::default_path(),
+            model: Self::default_model(),
+            ffmpeg_path: Self::default_ffmpeg_path(),
+            threads: Self::default_threads(),
+            max_duration_secs: Self::default_max_duration_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[allow(unused)]
+pub struct SpeechConfig {
+    #[serde(default)]
+    pub provider: SpeechProvider,
+    #[serde(default)]
+    pub whisper: WhisperConfig,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct PdfConfig {
+    // directory of the poppler binaries, empty to look them up in PATH
+    #[serde(default)]
+    pub poppler_path: String,
+    // resolution scanned pages are rasterized at
+    #[serde(default = "PdfConfig::default_dpi")]
+    pub dpi: u32,
+    #[serde(default = "PdfConfig::default_max_pages")]
+    pub max_pages: usize,
+}
+
+impl PdfConfig {
+    fn default_dpi() -> u32 {
+        200
+    }
+
+    fn default_max_pages() -> usize {
+        10
+    }
+}
+
+impl Default for PdfConfig {
+    fn default() -> Self {
+        Self {
+            poppler_path: String::new(),
+            dpi: Self::default_dpi(),
+            max_pages: Self::default_max_pages(),
+        }
+    }
+}
+
+/// How receipts are read: OCR text sent to the invoice prompt, or the image itself sent to
+/// a multimodal model.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InvoiceExtraction {
+    #[default]
+    Ocr,
+    Vision,
+}
+
 #[derive(Debug, Deserialize, Clone, Default)]
 #[allow(unused)]
 pub struct InvoiceConfig {
     pub bucket: String,
+    #[serde(default)]
+    pub extraction: InvoiceExtraction,
+    #[serde(default = "InvoiceConfig::default_workers")]
+    pub workers: usize,
+    #[serde(default = "InvoiceConfig::default_max_attempts")]
+    pub max_attempts: u32,
+    // longest side of stored photos, still sharp enough for OCR
+    #[serde(default = "InvoiceConfig::default_max_image_side")]
+    pub max_image_side: u32,
+    #[serde(default = "InvoiceConfig::default_thumbnail_side")]
+    pub thumbnail_side: u32,
+    #[serde(default = "InvoiceConfig::default_jpeg_quality")]
+    pub jpeg_quality: u8,
+    // files uploaded with a presigned url never go through the request body limit
+    #[serde(default = "InvoiceConfig::default_max_upload_size")]
+    pub max_upload_size: usize,
+}
+
+impl InvoiceConfig {
+    fn default_workers() -> usize {
+        2
+    }
+
+    fn default_max_attempts() -> u32 {
+        3
+    }
+
+    fn default_max_image_side() -> u32 {
+        2048
+    }
+
+    fn default_thumbnail_side() -> u32 {
+        320
+    }
+
+    fn default_jpeg_quality() -> u8 {
+        85
+    }
+
+    fn default_max_upload_size() -> usize {
+        10 * 1024 * 1024
+    }
+}
+
+/// Receipts forwarded by email: `{token}@{domain}` is given to each user, the provider
+/// posts the raw message to `/emails/inbound` with the shared secret and the envelope
+/// sender it authenticated with SPF or DKIM.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[allow(unused)]
+pub struct EmailConfig {
+    #[serde(default)]
+    pub domain: String,
+    #[serde(default)]
+    pub inbound_secret: String,
+}
+
+/// Admin endpoints are called with the shared secret, they are disabled while it is empty.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[allow(unused)]
+pub struct AdminConfig {
+    #[serde(default)]
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct DuplicateConfig {
+    // new invoices are compared with the photos and transactions of these last days
+    #[serde(default = "DuplicateConfig::default_window_days")]
+    pub window_days: i64,
+    // differing bits of two photo hashes still treated as the same receipt
+    #[serde(default = "DuplicateConfig::default_max_image_distance")]
+    pub max_image_distance: u32,
+}
+
+impl DuplicateConfig {
+    fn default_window_days() -> i64 {
+        14
+    }
+
+    fn default_max_image_distance() -> u32 {
+        6
+    }
+}
+
+impl Default for DuplicateConfig {
+    fn default() -> Self {
+        Self {
+            window_days: Self::default_window_days(),
+            max_image_distance: Self::default_max_image_distance(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -115,9 +485,25 @@ pub struct Settings {
     pub currencyapi: CurrencyapiConfig,
     pub llm: LLMConfig,
     pub redis: RedisConfig,
+    #[serde(default)]
     pub r2: R2Config,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
     pub gcp: GCPConfig,
     pub invoice: InvoiceConfig,
+    #[serde(default)]
+    pub ocr: OcrConfig,
+    #[serde(default)]
+    pub pdf: PdfConfig,
+    #[serde(default)]
+    pub email: EmailConfig,
+    #[serde(default)]
+    pub speech: SpeechConfig,
+    #[serde(default)]
+    pub duplicate: DuplicateConfig,
+    #[serde(default)]
+    pub admin: AdminConfig,
 }
 
 impl Settings {
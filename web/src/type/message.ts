@@ -20,12 +20,18 @@ export interface Invoice {
   total: number
   currency: string
   cardNumber: string | null
-  mediaPath: string | null
-  mediaType: string | null
+  media: Media[]
   createdAt: string
   updatedAt: string
 }
 
+export interface Media {
+  path: string
+  contentType: string
+  thumbnailPath?: string
+  hash?: string
+}
+
 export interface Transaction {
   id: string
   messageId: string